@@ -0,0 +1,335 @@
+//! Application-level flows that tie several drivers together.
+
+pub mod self_test;
+
+pub use self_test::{SelfTestChecks, SelfTestReport, self_test};
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    pwm::SetDutyCycle,
+};
+
+use crate::{
+    drivers::{
+        button::{Button, ButtonEvent},
+        buzzer::{Buzzer, SetFrequency},
+        eeprom::{self, Eeprom, LayoutStatus, StoreError, WaypointStore},
+        gps::FixSource,
+    },
+    guidance::{Guidance, GuidanceConfig, Turn},
+    notify::Notifier,
+};
+
+/// Rising two-note chime looped by [`acknowledge_arrival`].
+pub const ARRIVAL_CHIME: &[(u32, u8, u32)] = &[(1_568, 50, 120), (2_093, 50, 240)];
+
+/// Reason [`mark_waypoint`] could not store a waypoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkError<E> {
+    /// The GPS has no valid fix.
+    NoFix,
+    /// Every waypoint slot is already in use.
+    StorageFull,
+    /// Reading or writing the waypoint store failed.
+    Storage(StoreError<E>),
+}
+
+/// Poll `button` and, on a short press, save the current GPS fix as a waypoint.
+///
+/// On success the fix is written to the first free slot of `store`,
+/// [`Notifier::confirm`] is played (a double beep on a buzzer) and the slot
+/// index is returned. If there is no fix, the store is full or the write
+/// fails, [`Notifier::error`] is played (a low triple beep) and the reason is
+/// returned as an error.
+///
+/// Returns `Ok(None)` when no short press completed on this poll. Call this
+/// from the main loop in place of [`Button::poll`]; long presses are ignored.
+pub fn mark_waypoint<P, G, E, N>(
+    button: &mut Button<P>,
+    gps: &mut G,
+    store: &mut WaypointStore<E>,
+    notifier: &mut N,
+) -> Result<Option<u8>, MarkError<E::Error>>
+where
+    P: InputPin,
+    G: FixSource,
+    E: Eeprom,
+    N: Notifier,
+{
+    if button.poll() != Some(ButtonEvent::Press) {
+        return Ok(None);
+    }
+
+    let result = store_fix(gps, store);
+    match result {
+        Ok(_) => notifier.confirm(),
+        Err(_) => notifier.error(),
+    }
+    result.map(Some)
+}
+
+/// Loop `chime` until `button` is pressed, then acknowledge the arrival.
+///
+/// Use with [`GuidanceConfig::arrival_requires_ack`](crate::guidance::GuidanceConfig::arrival_requires_ack)
+/// after [`Guidance::update`] reports an arrival. Each repetition is followed
+/// by `gap_ms` of silence. The button is polled every millisecond, during the
+/// chime notes as well as the gap (see [`Buzzer::tone_until`]), so long
+/// presses are ignored but a short press is never missed. Returns immediately
+/// if no arrival is waiting, or if `chime` is empty, since nothing would then
+/// poll the button; the arrival stays unacknowledged.
+pub fn acknowledge_arrival<const N: usize, P, PWM, D, EN>(
+    guidance: &mut Guidance<N>,
+    button: &mut Button<P>,
+    buzzer: &mut Buzzer<PWM, D, EN>,
+    chime: &[(u32, u8, u32)],
+    gap_ms: u32,
+) where
+    P: InputPin,
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+    EN: OutputPin,
+{
+    if !guidance.is_awaiting_ack() || chime.is_empty() {
+        return;
+    }
+    let mut pressed = || button.poll() == Some(ButtonEvent::Press);
+    'chime: loop {
+        for &(frequency_hz, duty_percent, duration_ms) in chime {
+            // A rejected note is rested through; keep waiting for the press.
+            let stopped = buzzer
+                .tone_until(frequency_hz, duty_percent, duration_ms, &mut pressed)
+                .unwrap_or_else(|_| buzzer.rest_until(duration_ms, &mut pressed));
+            if stopped {
+                break 'chime;
+            }
+        }
+        if buzzer.rest_until(gap_ms, &mut pressed) {
+            break;
+        }
+    }
+    guidance.acknowledge();
+}
+
+/// Beep the turn towards the target with the configured sound.
+///
+/// The pitch rises with the size of the turn as set by
+/// [`GuidanceConfig::turn_tones`], and the duty is
+/// [`GuidanceConfig::volume_percent`], so an
+/// [`AudioPreset`](crate::guidance::AudioPreset) changes what is heard.
+/// `relative_bearing_deg` is the bearing to the target minus the heading, as
+/// for [`stereo_cue`](crate::guidance::stereo_cue). Returns the [`Turn`] that
+/// was signalled.
+pub fn turn_cue<PWM, D, EN>(
+    buzzer: &mut Buzzer<PWM, D, EN>,
+    config: &GuidanceConfig,
+    relative_bearing_deg: f32,
+    duration_ms: u32,
+) -> Result<Turn, <PWM as SetFrequency>::Error>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+    EN: OutputPin,
+{
+    let frequency_hz = config.turn_tones.frequency_hz(relative_bearing_deg);
+    buzzer.tone(frequency_hz, config.volume_percent, duration_ms)?;
+    Ok(Turn::from_relative_deg(relative_bearing_deg))
+}
+
+/// Run [`eeprom::check_layout`] at start-up and play [`Notifier::error`] if
+/// the stored data had to be erased.
+///
+/// A blank, freshly formatted or migrated EEPROM is silent; an old layout
+/// that could not be upgraded, or data without the layout header, warns that
+/// whatever was stored is gone.
+pub fn open_storage<E, M, N>(
+    eeprom: &mut E,
+    version: u8,
+    len: u16,
+    migrate: M,
+    notifier: &mut N,
+) -> Result<LayoutStatus, E::Error>
+where
+    E: Eeprom,
+    M: FnMut(&mut E, u8) -> Result<bool, E::Error>,
+    N: Notifier,
+{
+    let status = eeprom::check_layout(eeprom, version, len, migrate)?;
+    if let LayoutStatus::Reset { .. } | LayoutStatus::Unrecognised = status {
+        notifier.error();
+    }
+    Ok(status)
+}
+
+fn store_fix<G, E>(gps: &mut G, store: &mut WaypointStore<E>) -> Result<u8, MarkError<E::Error>>
+where
+    G: FixSource,
+    E: Eeprom,
+{
+    let fix = gps.latest_fix().ok_or(MarkError::NoFix)?;
+    let slot = store
+        .next_free_slot()
+        .map_err(MarkError::Storage)?
+        .ok_or(MarkError::StorageFull)?;
+    store.save(slot, fix).map_err(MarkError::Storage)?;
+    Ok(slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::delay::NopDelay;
+    use crate::firmware::shared::fixtures::{BeepLog, FakeGps, RamEeprom, RecordingPwm};
+    use crate::guidance::{AudioPreset, GuidanceEvent};
+    use crate::nav::{Coord, route::Route};
+    use crate::notify::{CONFIRM_HZ, ERROR_HZ};
+    use core::convert::Infallible;
+    use embedded_hal_mock::eh1::digital::{Mock as PinMock, State, Transaction as PinTxn};
+
+    /// Run one press-and-release through `mark_waypoint`.
+    fn press(
+        gps: &mut FakeGps,
+        store: &mut WaypointStore<RamEeprom<32>>,
+    ) -> (Result<Option<u8>, MarkError<Infallible>>, Vec<u32>) {
+        let expectations = [PinTxn::get(State::Low), PinTxn::get(State::High)];
+        let mut button = Button::new(PinMock::new(&expectations), 0, 500);
+        let mut buzzer = Buzzer::new(BeepLog::default(), NopDelay::new());
+
+        assert_eq!(
+            mark_waypoint(&mut button, gps, store, &mut buzzer),
+            Ok(None)
+        );
+        let result = mark_waypoint(&mut button, gps, store, &mut buzzer);
+        button.release().done();
+        (result, buzzer.release().0.beeps)
+    }
+
+    #[test]
+    fn test_press_stores_fix_in_next_slot_with_double_beep() {
+        let mut gps = FakeGps(Some(Coord::new(40.0, -105.0)));
+        let mut store = WaypointStore::new(RamEeprom([0xFF; 32]), 0, 2);
+
+        let (result, beeps) = press(&mut gps, &mut store);
+        assert_eq!(result, Ok(Some(0)));
+        assert_eq!(beeps, [CONFIRM_HZ, CONFIRM_HZ]);
+        assert!(store.load(0).unwrap().is_some());
+
+        let (result, _) = press(&mut gps, &mut store);
+        assert_eq!(result, Ok(Some(1)));
+    }
+
+    #[test]
+    fn test_no_fix_triple_beeps() {
+        let mut gps = FakeGps(None);
+        let mut store = WaypointStore::new(RamEeprom([0xFF; 32]), 0, 2);
+
+        let (result, beeps) = press(&mut gps, &mut store);
+        assert_eq!(result, Err(MarkError::NoFix));
+        assert_eq!(beeps, [ERROR_HZ; 3]);
+        assert_eq!(store.next_free_slot(), Ok(Some(0)));
+    }
+
+    #[test]
+    fn test_full_store_triple_beeps() {
+        let mut gps = FakeGps(Some(Coord::new(40.0, -105.0)));
+        let mut store = WaypointStore::new(RamEeprom([0x00; 32]), 0, 2);
+
+        let (result, beeps) = press(&mut gps, &mut store);
+        assert_eq!(result, Err(MarkError::StorageFull));
+        assert_eq!(beeps, [ERROR_HZ; 3]);
+    }
+
+    #[test]
+    fn test_arrival_chime_loops_until_press() {
+        let a = Coord::new(10.0, 20.0);
+        let route = Route::<2>::from_slice(&[a, Coord::new(10.01, 20.0)]).unwrap();
+        let config = GuidanceConfig::builder().arrival_requires_ack(true).build();
+        let mut guidance = Guidance::with_config(route, config);
+        assert_eq!(guidance.update(a), Some(GuidanceEvent::Arrived(0)));
+        assert!(guidance.is_awaiting_ack());
+        assert_eq!(guidance.update(a), None);
+        assert_eq!(guidance.route().current_index(), 0);
+
+        // One full chime (one poll per note millisecond + 5 ms gap), then
+        // pressed and released within the first note of the next chime.
+        let mut expectations = vec![PinTxn::get(State::High); 120 + 240 + 5];
+        expectations.extend([PinTxn::get(State::Low), PinTxn::get(State::High)]);
+        let mut button = Button::new(PinMock::new(&expectations), 0, 500);
+        let mut buzzer = Buzzer::new(BeepLog::default(), NopDelay::new());
+
+        acknowledge_arrival(&mut guidance, &mut button, &mut buzzer, ARRIVAL_CHIME, 5);
+        button.release().done();
+        assert_eq!(buzzer.release().0.beeps, [1_568, 2_093, 1_568]);
+        assert!(!guidance.is_awaiting_ack());
+        assert_eq!(guidance.route().current_index(), 1);
+    }
+
+    #[test]
+    fn test_press_inside_a_chime_note_stops_the_chime() {
+        let a = Coord::new(10.0, 20.0);
+        let route = Route::<2>::from_slice(&[a, Coord::new(10.01, 20.0)]).unwrap();
+        let config = GuidanceConfig::builder().arrival_requires_ack(true).build();
+        let mut guidance = Guidance::with_config(route, config);
+        guidance.update(a);
+
+        // A 1 ms press 100 ms into the 120 ms first note.
+        let mut expectations = vec![PinTxn::get(State::High); 100];
+        expectations.extend([PinTxn::get(State::Low), PinTxn::get(State::High)]);
+        let mut button = Button::new(PinMock::new(&expectations), 0, 500);
+        let mut buzzer = Buzzer::new(BeepLog::default(), NopDelay::new());
+
+        acknowledge_arrival(&mut guidance, &mut button, &mut buzzer, ARRIVAL_CHIME, 5);
+        button.release().done();
+        let (pwm, delay, _) = buzzer.release_with_enable_pin();
+        assert_eq!(pwm.beeps, [1_568]);
+        assert_eq!(delay.elapsed_ms(), 101);
+        assert!(!guidance.is_awaiting_ack());
+    }
+
+    #[test]
+    fn test_empty_chime_returns_without_acknowledging() {
+        let a = Coord::new(10.0, 20.0);
+        let route = Route::<2>::from_slice(&[a, Coord::new(10.01, 20.0)]).unwrap();
+        let config = GuidanceConfig::builder().arrival_requires_ack(true).build();
+        let mut guidance = Guidance::with_config(route, config);
+        guidance.update(a);
+
+        let mut button = Button::new(PinMock::new(&[]), 0, 500);
+        let mut buzzer = Buzzer::new(BeepLog::default(), NopDelay::new());
+        acknowledge_arrival(&mut guidance, &mut button, &mut buzzer, &[], 0);
+        button.release().done();
+        assert!(buzzer.release().0.beeps.is_empty());
+        assert!(guidance.is_awaiting_ack());
+    }
+
+    #[test]
+    fn test_turn_cue_plays_the_preset_pitch_and_volume() {
+        let play = |preset| {
+            let config = GuidanceConfig::preset(preset);
+            let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+            assert_eq!(turn_cue(&mut buzzer, &config, -60.0, 10), Ok(Turn::Left));
+            let pwm = buzzer.release().0;
+            (pwm.frequencies, pwm.duties)
+        };
+        // RecordingPwm's maximum duty is 100, so the duty is the percentage.
+        assert_eq!(play(AudioPreset::Subtle), (vec![720], vec![0, 10, 0]));
+        assert_eq!(play(AudioPreset::Loud), (vec![2_480], vec![0, 50, 0]));
+    }
+
+    #[test]
+    fn test_open_storage_warns_only_when_data_is_lost() {
+        let mut buzzer = Buzzer::new(BeepLog::default(), NopDelay::new());
+        let mut ram = RamEeprom([0xFF; 32]);
+        let status = open_storage(&mut ram, 2, 30, |_, _| Ok(false), &mut buzzer);
+        assert_eq!(status, Ok(LayoutStatus::Formatted));
+
+        ram.0[1] = 1;
+        let status = open_storage(&mut ram, 2, 30, |_, _| Ok(false), &mut buzzer);
+        assert_eq!(status, Ok(LayoutStatus::Reset { from: 1 }));
+
+        let mut foreign = RamEeprom([0x00; 32]);
+        let status = open_storage(&mut foreign, 2, 30, |_, _| Ok(false), &mut buzzer);
+        assert_eq!(status, Ok(LayoutStatus::Unrecognised));
+        assert_eq!(buzzer.release().0.beeps, [ERROR_HZ; 6]);
+    }
+}
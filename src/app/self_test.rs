@@ -0,0 +1,327 @@
+//! Power-on hardware self-test.
+//!
+//! Each subsystem is exercised through a [`SelfCheck`]; pass `None` for any
+//! peripheral the board does not have. Failures are reported in a
+//! [`SelfTestReport`] and can be announced on the buzzer with
+//! [`SelfTestReport::announce`], one counted beep group per failure, so a
+//! unit in the field can be diagnosed without a serial cable.
+
+use embedded_hal::{delay::DelayNs, digital::OutputPin, pwm::SetDutyCycle};
+use embedded_hal_nb::serial::Read;
+
+use crate::{
+    drivers::{
+        adc::AdcChannel,
+        buzzer::{Buzzer, SetFrequency},
+        tick::TickSource,
+    },
+    notify::Notifier,
+};
+
+/// Length of each beep in a [`SelfTestReport::announce`] failure code.
+pub const CODE_UNIT_MS: u32 = 150;
+/// Silence between failure codes.
+pub const CODE_GAP_MS: u32 = 800;
+
+/// One hardware check run by [`self_test`].
+pub trait SelfCheck {
+    /// Exercise the subsystem and return `true` if it responded correctly.
+    fn run(&mut self) -> bool;
+}
+
+/// Outcome of one subsystem's check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckResult {
+    /// The peripheral is not fitted, so nothing was checked.
+    Skipped,
+    /// The check succeeded.
+    Passed,
+    /// The check failed.
+    Failed,
+}
+
+/// Subsystems covered by [`self_test`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    /// The buzzer and its PWM (code 1).
+    Buzzer,
+    /// The status LED (code 2).
+    Led,
+    /// The GPS receiver's serial link (code 3).
+    Gps,
+    /// The battery voltage reading (code 4).
+    Battery,
+}
+
+impl Subsystem {
+    /// Number of beeps identifying this subsystem in
+    /// [`SelfTestReport::announce`].
+    pub const fn code(self) -> u8 {
+        match self {
+            Subsystem::Buzzer => 1,
+            Subsystem::Led => 2,
+            Subsystem::Gps => 3,
+            Subsystem::Battery => 4,
+        }
+    }
+}
+
+/// Checks to run, one per subsystem; `None` skips it.
+#[derive(Default)]
+pub struct SelfTestChecks<'a> {
+    /// Check for [`Subsystem::Buzzer`], e.g. a [`Chirp`].
+    pub buzzer: Option<&'a mut dyn SelfCheck>,
+    /// Check for [`Subsystem::Led`], e.g. a [`LedFlash`].
+    pub led: Option<&'a mut dyn SelfCheck>,
+    /// Check for [`Subsystem::Gps`], e.g. a [`GpsPresence`].
+    pub gps: Option<&'a mut dyn SelfCheck>,
+    /// Check for [`Subsystem::Battery`], e.g. a [`BatteryLevel`].
+    pub battery: Option<&'a mut dyn SelfCheck>,
+}
+
+/// Per-subsystem results of [`self_test`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Result for [`Subsystem::Buzzer`].
+    pub buzzer: CheckResult,
+    /// Result for [`Subsystem::Led`].
+    pub led: CheckResult,
+    /// Result for [`Subsystem::Gps`].
+    pub gps: CheckResult,
+    /// Result for [`Subsystem::Battery`].
+    pub battery: CheckResult,
+}
+
+impl SelfTestReport {
+    /// `true` if no check failed; skipped checks do not count.
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Subsystems whose check failed, in [`Subsystem::code`] order.
+    pub fn failures(&self) -> impl Iterator<Item = Subsystem> + '_ {
+        [
+            (Subsystem::Buzzer, self.buzzer),
+            (Subsystem::Led, self.led),
+            (Subsystem::Gps, self.gps),
+            (Subsystem::Battery, self.battery),
+        ]
+        .into_iter()
+        .filter(|&(_, result)| result == CheckResult::Failed)
+        .map(|(subsystem, _)| subsystem)
+    }
+
+    /// Play [`Notifier::confirm`] if everything passed, otherwise
+    /// [`Subsystem::code`] beeps for each failed subsystem, separated by
+    /// [`CODE_GAP_MS`].
+    pub fn announce<PWM, D, EN>(&self, buzzer: &mut Buzzer<PWM, D, EN>)
+    where
+        PWM: SetDutyCycle + SetFrequency,
+        D: DelayNs,
+        EN: OutputPin,
+    {
+        if self.passed() {
+            buzzer.confirm();
+            return;
+        }
+        for (i, subsystem) in self.failures().enumerate() {
+            if i > 0 {
+                buzzer.rest(CODE_GAP_MS);
+            }
+            let _ = buzzer.beep_count(subsystem.code(), CODE_UNIT_MS);
+        }
+    }
+}
+
+/// Run every check in `checks` once, in [`Subsystem::code`] order.
+pub fn self_test(checks: SelfTestChecks<'_>) -> SelfTestReport {
+    let run = |check: Option<&mut dyn SelfCheck>| match check.map(|c| c.run()) {
+        None => CheckResult::Skipped,
+        Some(true) => CheckResult::Passed,
+        Some(false) => CheckResult::Failed,
+    };
+    SelfTestReport {
+        buzzer: run(checks.buzzer),
+        led: run(checks.led),
+        gps: run(checks.gps),
+        battery: run(checks.battery),
+    }
+}
+
+/// Short chirp; fails if the PWM rejects the frequency.
+pub struct Chirp<'a, PWM, D, EN>(pub &'a mut Buzzer<PWM, D, EN>)
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+    EN: OutputPin;
+
+impl<PWM, D, EN> SelfCheck for Chirp<'_, PWM, D, EN>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+    EN: OutputPin,
+{
+    fn run(&mut self) -> bool {
+        self.0.tone(2_000, 50, 60).is_ok()
+    }
+}
+
+/// Light `pin` for `on_ms`; fails on a pin error.
+pub struct LedFlash<P, D> {
+    /// LED pin, driven high to light it.
+    pub pin: P,
+    /// Delay used to hold the LED on.
+    pub delay: D,
+    /// How long the LED stays lit.
+    pub on_ms: u32,
+}
+
+impl<P: OutputPin, D: DelayNs> SelfCheck for LedFlash<P, D> {
+    fn run(&mut self) -> bool {
+        let on = self.pin.set_high().is_ok();
+        self.delay.delay_ms(self.on_ms);
+        self.pin.set_low().is_ok() && on
+    }
+}
+
+/// Passes once any byte arrives from the receiver within `timeout_ms`.
+///
+/// Most modules talk at 1 Hz from power-up, so a timeout of ~1.5 s tells a
+/// missing or unpowered receiver from a quiet one.
+pub struct GpsPresence<S, T> {
+    /// Serial port the receiver is wired to.
+    pub serial: S,
+    /// Millisecond clock timing the wait.
+    pub ticks: T,
+    /// How long to wait for the first byte.
+    pub timeout_ms: u32,
+}
+
+impl<S: Read<u8>, T: TickSource> SelfCheck for GpsPresence<S, T> {
+    fn run(&mut self) -> bool {
+        let start = self.ticks.now_ms();
+        while self.ticks.now_ms().wrapping_sub(start) < self.timeout_ms {
+            if self.serial.read().is_ok() {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Passes if the battery reading is at least `min_raw` ADC counts.
+pub struct BatteryLevel<A> {
+    /// ADC channel reading the battery voltage.
+    pub adc: A,
+    /// Lowest raw reading that passes.
+    pub min_raw: u16,
+}
+
+impl<A: AdcChannel> SelfCheck for BatteryLevel<A> {
+    fn run(&mut self) -> bool {
+        self.adc.read().is_ok_and(|raw| raw >= self.min_raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drivers::buzzer::COUNT_BEEP_HZ;
+    use crate::firmware::shared::delay::NopDelay;
+    use crate::firmware::shared::fixtures::{BeepLog, SteppingTicks};
+    use crate::notify::CONFIRM_HZ;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+
+    struct Fixed(bool);
+    impl SelfCheck for Fixed {
+        fn run(&mut self) -> bool {
+            self.0
+        }
+    }
+
+    struct Adc(u16);
+    impl AdcChannel for Adc {
+        type Error = Infallible;
+        fn full_scale(&self) -> u16 {
+            1023
+        }
+        fn read(&mut self) -> Result<u16, Infallible> {
+            Ok(self.0)
+        }
+    }
+
+    struct SilentSerial;
+    impl embedded_hal_nb::serial::ErrorType for SilentSerial {
+        type Error = Infallible;
+    }
+    impl Read<u8> for SilentSerial {
+        fn read(&mut self) -> nb::Result<u8, Infallible> {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    #[test]
+    fn test_report_aggregates_present_checks_only() {
+        let (mut led, mut battery) = (
+            Fixed(true),
+            BatteryLevel {
+                adc: Adc(500),
+                min_raw: 600,
+            },
+        );
+        let ticks = SteppingTicks(Cell::new(0));
+        let mut gps = GpsPresence {
+            serial: SilentSerial,
+            ticks: &ticks,
+            timeout_ms: 100,
+        };
+        let report = self_test(SelfTestChecks {
+            led: Some(&mut led),
+            gps: Some(&mut gps),
+            battery: Some(&mut battery),
+            ..SelfTestChecks::default()
+        });
+
+        assert_eq!(
+            report,
+            SelfTestReport {
+                buzzer: CheckResult::Skipped,
+                led: CheckResult::Passed,
+                gps: CheckResult::Failed,
+                battery: CheckResult::Failed,
+            }
+        );
+        assert!(!report.passed());
+        assert!(report.failures().eq([Subsystem::Gps, Subsystem::Battery]));
+    }
+
+    #[test]
+    fn test_announce_beeps_each_failure_code() {
+        let mut buzzer = Buzzer::new(BeepLog::default(), NopDelay::new());
+        let report = self_test(SelfTestChecks {
+            buzzer: Some(&mut Chirp(&mut buzzer)),
+            led: Some(&mut Fixed(true)),
+            ..SelfTestChecks::default()
+        });
+        assert!(report.passed());
+        report.announce(&mut buzzer);
+        let (pwm, _) = buzzer.release();
+        assert_eq!(pwm.beeps, [2_000, CONFIRM_HZ, CONFIRM_HZ]);
+
+        let failed = SelfTestReport {
+            gps: CheckResult::Failed,
+            battery: CheckResult::Failed,
+            ..report
+        };
+        let mut buzzer = Buzzer::new(BeepLog::default(), NopDelay::new());
+        failed.announce(&mut buzzer);
+        let (pwm, delay) = buzzer.release();
+        assert_eq!(pwm.beeps, [COUNT_BEEP_HZ; 7]);
+        // 3 + 4 beeps with 2 + 3 gaps, plus one gap between the codes.
+        assert_eq!(
+            delay.elapsed_ms(),
+            u64::from(12 * CODE_UNIT_MS + CODE_GAP_MS)
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Analog-to-digital converter interface.
+
+/// One input channel of an analog-to-digital converter.
+pub trait AdcChannel {
+    /// Error type returned when a conversion fails.
+    ///
+    /// Use [`core::convert::Infallible`] if the operation cannot fail.
+    type Error;
+
+    /// Largest value [`AdcChannel::read`] can return (`1023` for 10 bits).
+    fn full_scale(&self) -> u16;
+
+    /// Run one conversion and return the raw reading.
+    fn read(&mut self) -> Result<u16, Self::Error>;
+}
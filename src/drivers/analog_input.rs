@@ -0,0 +1,104 @@
+//! Menu selection from a potentiometer or joystick axis.
+
+use super::adc::AdcChannel;
+
+/// Maps an analog input onto `items` discrete menu positions.
+///
+/// The ADC range is split into equal bands, one per item. To stop the
+/// selection flickering between neighbours when the wiper sits on a band
+/// edge, the current item is kept until the reading moves `hysteresis`
+/// counts past the edge.
+pub struct PotSelector<A: AdcChannel> {
+    adc: A,
+    items: usize,
+    hysteresis: u16,
+    selected: Option<usize>,
+}
+
+impl<A: AdcChannel> PotSelector<A> {
+    /// Create a selector.
+    ///
+    /// * `adc` – channel the potentiometer wiper is connected to
+    /// * `items` – number of menu items (at least 1)
+    /// * `hysteresis` – ADC counts a reading must pass a band edge by
+    pub fn new(adc: A, items: usize, hysteresis: u16) -> Self {
+        Self {
+            adc,
+            items: items.max(1),
+            hysteresis,
+            selected: None,
+        }
+    }
+
+    /// Read the input and return the selected item index, `0..items`.
+    ///
+    /// A failed conversion keeps the previous selection (item 0 if nothing
+    /// has been read yet).
+    pub fn selection(&mut self) -> usize {
+        let Ok(raw) = self.adc.read() else {
+            return self.selected.unwrap_or(0);
+        };
+        let raw = u32::from(raw);
+        let selected = match self.selected {
+            Some(current) => {
+                let (low, high) = self.band(current);
+                let h = u32::from(self.hysteresis);
+                if raw + h < low || raw >= high + h {
+                    self.item_for(raw)
+                } else {
+                    current
+                }
+            }
+            None => self.item_for(raw),
+        };
+        self.selected = Some(selected);
+        selected
+    }
+
+    /// Consume the selector and return the ADC channel.
+    pub fn release(self) -> A {
+        self.adc
+    }
+
+    /// Number of ADC codes, i.e. `full_scale + 1`.
+    fn span(&self) -> u32 {
+        u32::from(self.adc.full_scale()) + 1
+    }
+
+    fn item_for(&self, raw: u32) -> usize {
+        let item = raw as u64 * self.items as u64 / u64::from(self.span());
+        (item as usize).min(self.items - 1)
+    }
+
+    /// ADC codes `low..high` belonging to `item`.
+    fn band(&self, item: usize) -> (u32, u32) {
+        let edge =
+            |i: usize| (i as u64 * u64::from(self.span())).div_ceil(self.items as u64) as u32;
+        (edge(item), edge(item + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::Readings;
+
+    fn selections(items: usize, hysteresis: u16, raw: &[u16]) -> Vec<usize> {
+        let mut pot = PotSelector::new(Readings(raw.iter()), items, hysteresis);
+        raw.iter().map(|_| pot.selection()).collect()
+    }
+
+    #[test]
+    fn test_range_maps_to_equal_bands() {
+        let raw = [0, 255, 256, 511, 512, 767, 768, 1023];
+        assert_eq!(selections(4, 0, &raw), [0, 0, 1, 1, 2, 2, 3, 3]);
+        assert_eq!(selections(1, 0, &[0, 1023]), [0, 0]);
+    }
+
+    #[test]
+    fn test_hysteresis_holds_item_at_band_edge() {
+        // Band 0 is 0..256, band 1 is 256..512.
+        let raw = [250, 258, 262, 266, 260, 250, 246, 245];
+        assert_eq!(selections(4, 10, &raw), [0, 0, 0, 1, 1, 1, 1, 0]);
+    }
+}
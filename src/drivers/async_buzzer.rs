@@ -0,0 +1,193 @@
+//! Non-blocking beep patterns for a buzzer, advanced from the main loop.
+
+use embedded_hal::pwm::SetDutyCycle;
+
+use super::{buzzer::SetFrequency, tick::TickSource, util::duty_from_percent};
+
+/// Duty of every beep, the loudest a square wave gets.
+const BEEP_DUTY_PERCENT: u8 = 50;
+
+/// A run of equal beeps started by [`AsyncBuzzer::play_beeps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pattern {
+    start_ms: u32,
+    frequency_hz: u32,
+    count: u8,
+    beep_ms: u32,
+    gap_ms: u32,
+}
+
+impl Pattern {
+    /// Whether the output should sound `elapsed_ms` into the pattern, or
+    /// `None` once it has finished. Saturates rather than overflowing, so an
+    /// absurdly long pattern just plays until stopped.
+    fn sounding(&self, elapsed_ms: u32) -> Option<bool> {
+        let period = self.beep_ms.saturating_add(self.gap_ms);
+        let total = period
+            .saturating_mul(u32::from(self.count))
+            .saturating_sub(self.gap_ms);
+        if elapsed_ms >= total {
+            return None;
+        }
+        Some(elapsed_ms % period < self.beep_ms)
+    }
+}
+
+/// Buzzer that plays beeps in the background instead of blocking in a delay.
+///
+/// Start a pattern with [`AsyncBuzzer::play_beeps`], then call
+/// [`AsyncBuzzer::poll`] from the main loop (every millisecond or so) to
+/// switch the tone on and off. Playing a new pattern replaces the current
+/// one.
+pub struct AsyncBuzzer<PWM, T>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    T: TickSource,
+{
+    pwm: PWM,
+    ticks: T,
+    pattern: Option<Pattern>,
+    on: bool,
+}
+
+impl<PWM, T> AsyncBuzzer<PWM, T>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    T: TickSource,
+{
+    /// Create a new [`AsyncBuzzer`], silent and in standby.
+    ///
+    /// * `pwm`   – PWM channel implementing [`SetDutyCycle`] and [`SetFrequency`]
+    /// * `ticks` – millisecond clock, e.g. `&SystemClock`
+    pub fn new(mut pwm: PWM, ticks: T) -> Self {
+        let _ = pwm.set_duty_cycle(0);
+        pwm.standby();
+        Self {
+            pwm,
+            ticks,
+            pattern: None,
+            on: false,
+        }
+    }
+
+    /// Start `count` beeps at `frequency_hz`, each `beep_ms` long and
+    /// separated by `gap_ms` of silence, at 50% duty.
+    ///
+    /// The first beep starts immediately. A `count` of 0 or a
+    /// `frequency_hz` of 0 just stops the current pattern.
+    pub fn play_beeps(
+        &mut self,
+        frequency_hz: u32,
+        count: u8,
+        beep_ms: u32,
+        gap_ms: u32,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.stop();
+        if count == 0 || frequency_hz == 0 {
+            return Ok(());
+        }
+        self.pwm.resume();
+        self.pwm.set_frequency(frequency_hz)?;
+        self.pattern = Some(Pattern {
+            start_ms: self.ticks.now_ms(),
+            frequency_hz,
+            count,
+            beep_ms,
+            gap_ms,
+        });
+        self.poll();
+        Ok(())
+    }
+
+    /// Switch the tone on or off as the pattern requires, stopping once it
+    /// has finished.
+    pub fn poll(&mut self) {
+        let Some(pattern) = self.pattern else {
+            return;
+        };
+        let elapsed = self.ticks.now_ms().wrapping_sub(pattern.start_ms);
+        match pattern.sounding(elapsed) {
+            None => self.stop(),
+            Some(on) if on != self.on => {
+                let duty = if on {
+                    duty_from_percent(self.pwm.max_duty_cycle(), BEEP_DUTY_PERCENT)
+                } else {
+                    0
+                };
+                let _ = self.pwm.set_duty_cycle(duty);
+                self.on = on;
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// `true` while a pattern is playing, including its gaps.
+    pub fn is_playing(&self) -> bool {
+        self.pattern.is_some()
+    }
+
+    /// Frequency of the pattern being played, if any.
+    pub fn frequency_hz(&self) -> Option<u32> {
+        self.pattern.map(|p| p.frequency_hz)
+    }
+
+    /// Silence the output at once and put the PWM into standby.
+    pub fn stop(&mut self) {
+        let _ = self.pwm.set_duty_cycle(0);
+        if self.pattern.take().is_some() {
+            self.pwm.standby();
+        }
+        self.on = false;
+    }
+
+    /// Consume the driver and return the PWM channel and tick source.
+    pub fn release(self) -> (PWM, T) {
+        (self.pwm, self.ticks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::{CoarsePwm, ManualTicks};
+
+    #[test]
+    fn test_beeps_toggle_on_poll_and_finish() {
+        let ticks = ManualTicks::default();
+        let mut buzzer = AsyncBuzzer::new(CoarsePwm::new(100), &ticks);
+        buzzer.play_beeps(2_000, 2, 50, 30).unwrap();
+
+        let mut busy = Vec::new();
+        for t in [10, 50, 79, 80, 129, 130] {
+            ticks.0.set(t);
+            buzzer.poll();
+            busy.push(buzzer.is_playing());
+        }
+        assert_eq!(busy, [true, true, true, true, true, false]);
+        assert_eq!(buzzer.release().0.duties, [0, 0, 50, 0, 50, 0]);
+    }
+
+    #[test]
+    fn test_huge_pattern_saturates_instead_of_overflowing() {
+        let pattern = Pattern {
+            start_ms: 0,
+            frequency_hz: 2_000,
+            count: 255,
+            beep_ms: u32::MAX / 100,
+            gap_ms: u32::MAX / 100,
+        };
+        // 255 periods would overflow; the total saturates instead.
+        assert_eq!(pattern.sounding(0), Some(true));
+        assert_eq!(pattern.sounding(u32::MAX / 100), Some(false));
+        assert_eq!(pattern.sounding(u32::MAX), None);
+    }
+
+    #[test]
+    fn test_beep_on_one_step_pwm_is_audible() {
+        let ticks = ManualTicks::default();
+        let mut buzzer = AsyncBuzzer::new(CoarsePwm::new(1), &ticks);
+        buzzer.play_beeps(2_000, 1, 50, 0).unwrap();
+        buzzer.poll();
+        assert_eq!(buzzer.release().0.duties, [0, 0, 1]);
+    }
+}
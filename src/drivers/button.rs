@@ -0,0 +1,153 @@
+//! Debounced push-button driver built on an `embedded-hal` input pin.
+
+use embedded_hal::digital::InputPin;
+
+/// Event reported by [`Button::poll`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonEvent {
+    /// Button was pressed and released before the long-press threshold.
+    Press,
+    /// Button has been held for at least the long-press threshold.
+    ///
+    /// Reported once per hold; no [`ButtonEvent::Press`] follows on release.
+    LongPress,
+}
+
+/// Active-low push button (pin pulled up, switch to ground).
+///
+/// [`Button::poll`] must be called once per millisecond; all thresholds are
+/// counted in polls.
+pub struct Button<P>
+where
+    P: InputPin,
+{
+    pin: P,
+    debounce_ms: u32,
+    long_press_ms: u32,
+    raw: bool,
+    stable_ms: u32,
+    pressed: bool,
+    held_ms: u32,
+    long_reported: bool,
+}
+
+impl<P> Button<P>
+where
+    P: InputPin,
+{
+    /// Create a new [`Button`].
+    ///
+    /// * `pin`           – input pin implementing [`InputPin`], low when pressed
+    /// * `debounce_ms`   – how long the pin must read the same level before the
+    ///   change is accepted
+    /// * `long_press_ms` – hold time after which [`ButtonEvent::LongPress`] fires
+    pub fn new(pin: P, debounce_ms: u32, long_press_ms: u32) -> Self {
+        Self {
+            pin,
+            debounce_ms,
+            long_press_ms,
+            raw: false,
+            stable_ms: 0,
+            pressed: false,
+            held_ms: 0,
+            long_reported: false,
+        }
+    }
+
+    /// Sample the pin once and return any event that completed on this tick.
+    ///
+    /// A pin read error is treated as "released".
+    pub fn poll(&mut self) -> Option<ButtonEvent> {
+        let raw = self.pin.is_low().unwrap_or(false);
+        if raw != self.raw {
+            self.raw = raw;
+            self.stable_ms = 0;
+        } else if self.stable_ms < self.debounce_ms {
+            self.stable_ms += 1;
+        }
+
+        if self.stable_ms >= self.debounce_ms && raw != self.pressed {
+            self.pressed = raw;
+            if raw {
+                self.held_ms = 0;
+                self.long_reported = false;
+            } else if !self.long_reported {
+                return Some(ButtonEvent::Press);
+            }
+        }
+
+        if self.pressed && !self.long_reported {
+            self.held_ms = self.held_ms.saturating_add(1);
+            if self.held_ms >= self.long_press_ms {
+                self.long_reported = true;
+                return Some(ButtonEvent::LongPress);
+            }
+        }
+        None
+    }
+
+    /// Whether the debounced state is currently "pressed".
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Consume the driver and return the input pin.
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::eh1::digital::{Mock as PinMock, State, Transaction as PinTxn};
+
+    /// Build a pin expectation list from `(state, polls)` runs.
+    fn pin_sequence(runs: &[(State, usize)]) -> Vec<PinTxn> {
+        runs.iter()
+            .flat_map(|&(state, n)| core::iter::repeat_n(PinTxn::get(state), n))
+            .collect()
+    }
+
+    fn poll_all<P: InputPin>(button: &mut Button<P>, polls: usize) -> Vec<ButtonEvent> {
+        (0..polls).filter_map(|_| button.poll()).collect()
+    }
+
+    #[test]
+    fn test_short_press_with_bounce() {
+        let runs = [
+            (State::High, 5),
+            (State::Low, 1),
+            (State::High, 1),
+            (State::Low, 30),
+            (State::High, 10),
+        ];
+        let expectations = pin_sequence(&runs);
+        let mut button = Button::new(PinMock::new(&expectations), 5, 500);
+
+        let events = poll_all(&mut button, expectations.len());
+        assert_eq!(events, [ButtonEvent::Press]);
+        button.pin.done();
+    }
+
+    #[test]
+    fn test_bounce_shorter_than_debounce_is_ignored() {
+        let runs = [(State::High, 5), (State::Low, 3), (State::High, 10)];
+        let expectations = pin_sequence(&runs);
+        let mut button = Button::new(PinMock::new(&expectations), 5, 500);
+
+        assert!(poll_all(&mut button, expectations.len()).is_empty());
+        button.pin.done();
+    }
+
+    #[test]
+    fn test_long_press_fires_once_without_press() {
+        let runs = [(State::Low, 120), (State::High, 10)];
+        let expectations = pin_sequence(&runs);
+        let mut button = Button::new(PinMock::new(&expectations), 5, 100);
+
+        let events = poll_all(&mut button, expectations.len());
+        assert_eq!(events, [ButtonEvent::LongPress]);
+        button.pin.done();
+    }
+}
@@ -2,7 +2,14 @@
 
 use core::convert::Infallible;
 
-use embedded_hal::{delay::DelayNs, pwm::SetDutyCycle};
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{ErrorType as PinErrorType, OutputPin},
+    pwm::SetDutyCycle,
+};
+
+use super::{morse, note::Note, tick::Deadline, util::duty_from_percent};
+use crate::util::math;
 
 /// Change the output frequency of a PWM/timer peripheral.
 ///
@@ -23,20 +30,359 @@ pub trait SetFrequency {
     ///
     /// Returns `Err(Self::Error)` if the frequency cannot be applied (out of
     /// range, peripheral busy, etc.).
-    fn set_frequency(&mut self, hz: u32) -> Result<(), Infallible>;
+    fn set_frequency(&mut self, hz: u32) -> Result<(), Self::Error>;
+
+    /// Disconnect the output pin (and optionally stop the timer) to save power.
+    ///
+    /// Called by [`Buzzer::idle`]. The default does nothing, for outputs with
+    /// no low-power state.
+    fn standby(&mut self) {}
+
+    /// Undo [`SetFrequency::standby`] so the next tone is audible.
+    ///
+    /// The default does nothing.
+    fn resume(&mut self) {}
+}
+
+/// Adapter for a PWM channel that cannot retune, so it can drive a [`Buzzer`].
+///
+/// Many HAL PWM channels only implement [`SetDutyCycle`], with the frequency
+/// fixed when the timer is set up. Wrapped in [`FixedFrequency`] they gain a
+/// [`SetFrequency`] whose `set_frequency` does nothing, so every tone sounds
+/// at that fixed pitch: beeps, rests, Morse and volume still work, but
+/// melodies and sweeps collapse to one note. Suits a self-oscillating
+/// (active) buzzer switched by duty as well.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedFrequency<P>(pub P);
+
+impl<P: SetDutyCycle> FixedFrequency<P> {
+    /// Wrap `pwm`, whose frequency is set elsewhere.
+    pub const fn new(pwm: P) -> Self {
+        Self(pwm)
+    }
+
+    /// Return the wrapped PWM channel.
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P: SetDutyCycle> embedded_hal::pwm::ErrorType for FixedFrequency<P> {
+    type Error = P::Error;
+}
+
+impl<P: SetDutyCycle> SetDutyCycle for FixedFrequency<P> {
+    fn max_duty_cycle(&self) -> u16 {
+        self.0.max_duty_cycle()
+    }
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.0.set_duty_cycle(duty)
+    }
+}
+
+impl<P: SetDutyCycle> SetFrequency for FixedFrequency<P> {
+    type Error = Infallible;
+
+    /// Ignore `hz`; the pitch is whatever the channel was set up with.
+    fn set_frequency(&mut self, _hz: u32) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// How [`Buzzer::sweep`] divides the range between its end frequencies.
+///
+/// More, smaller steps sound smoother but each tone gets a shorter slice of
+/// the sweep; below ~10 ms per step the buzzer mostly clicks. Logarithmic
+/// spacing keeps a constant pitch ratio between steps, which the ear hears as
+/// an even glide, whereas linear steps seem to rush through the low end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepSteps {
+    /// This many tones, evenly spaced in hertz.
+    LinearCount(u16),
+    /// Tones at most this many hertz apart; the count follows from the range.
+    LinearHz(u32),
+    /// This many tones with a constant frequency ratio between neighbours.
+    LogCount(u16),
+    /// Tones at most this many cents apart (100 cents = one semitone).
+    LogCents(u16),
+}
+
+/// Character of a tone, set through the PWM duty cycle.
+///
+/// In fast PWM the duty is the only waveform control, so it sets both
+/// loudness and timbre. A 50% square wave has only odd harmonics and sounds
+/// hollow, like a clarinet; narrower pulses add the even harmonics for a
+/// fuller, buzzier sound at a slightly lower level (the fundamental falls
+/// with `sin(π · duty)`, about 3 dB at 25%). Pick a timbre per cue, e.g.
+/// [`Timbre::Hollow`] for arrival and [`Timbre::Full`] for errors, so they
+/// differ in character as well as pitch. In the firmware's CTC tone mode the
+/// output is always a 50% square wave and every timbre sounds the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timbre {
+    /// Symmetric square wave, odd harmonics only.
+    Hollow,
+    /// 25% pulse with even harmonics as well.
+    Full,
+}
+
+impl Timbre {
+    /// Duty cycle in percent producing this timbre.
+    pub const fn duty_percent(self) -> u8 {
+        match self {
+            Timbre::Hollow => 50,
+            Timbre::Full => 25,
+        }
+    }
+}
+
+/// Pitch of the beeps played by [`Buzzer::beep_count`].
+pub const COUNT_BEEP_HZ: u32 = 2_000;
+
+/// Upper pitch of [`Buzzer::panic_pattern`].
+pub const PANIC_HIGH_HZ: u32 = 3_000;
+/// Lower pitch of [`Buzzer::panic_pattern`].
+pub const PANIC_LOW_HZ: u32 = 1_500;
+/// Length of each half of a [`Buzzer::panic_pattern`] cycle.
+pub const PANIC_STEP_MS: u32 = 60;
+
+/// Check a `(frequency_hz, duty_percent, duration_ms)` melody table.
+///
+/// Returns `false` if any note has a duty above 100% or a zero duration.
+/// Being a `const fn`, it can reject a bad table at compile time:
+///
+/// ```
+/// use gps::drivers::buzzer::validate_melody;
+///
+/// const CHIME: &[(u32, u8, u32)] = &[(1_319, 60, 120), (1_760, 60, 240)];
+/// const _: () = assert!(validate_melody(CHIME));
+/// ```
+///
+/// A typo such as a 150% duty then fails the build:
+///
+/// ```compile_fail
+/// use gps::drivers::buzzer::validate_melody;
+///
+/// const CHIME: &[(u32, u8, u32)] = &[(1_319, 150, 120), (1_760, 60, 240)];
+/// const _: () = assert!(validate_melody(CHIME));
+/// ```
+pub const fn validate_melody(notes: &[(u32, u8, u32)]) -> bool {
+    let mut i = 0;
+    while i < notes.len() {
+        let (_, duty_percent, duration_ms) = notes[i];
+        if duty_percent > 100 || duration_ms == 0 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Object-safe view of a tone output, used by [`play_melody_dyn`].
+///
+/// Implemented for every `SetDutyCycle + SetFrequency` type. Duty-cycle
+/// errors are ignored just as [`Buzzer::tone`] ignores them; frequency
+/// errors are reported as a [`RejectedFrequency`], since the generic error
+/// type cannot cross the trait object.
+pub trait ToneOutput {
+    /// See [`SetFrequency::set_frequency`].
+    fn tone_frequency(&mut self, hz: u32) -> Result<(), RejectedFrequency>;
+    /// See [`SetDutyCycle::max_duty_cycle`].
+    fn tone_max_duty(&self) -> u16;
+    /// See [`SetDutyCycle::set_duty_cycle`].
+    fn tone_duty(&mut self, duty: u16);
+
+    /// Set the duty to `duty_percent` of [`ToneOutput::tone_max_duty`] with
+    /// [`duty_from_percent`], the rounding every player shares.
+    fn tone_duty_percent(&mut self, duty_percent: u8) {
+        let max = self.tone_max_duty();
+        self.tone_duty(duty_from_percent(max, duty_percent));
+    }
+}
+
+impl<T: SetDutyCycle + SetFrequency> ToneOutput for T {
+    fn tone_frequency(&mut self, hz: u32) -> Result<(), RejectedFrequency> {
+        self.set_frequency(hz).map_err(|_| RejectedFrequency { hz })
+    }
+    fn tone_max_duty(&self) -> u16 {
+        self.max_duty_cycle()
+    }
+    fn tone_duty(&mut self, duty: u16) {
+        let _ = self.set_duty_cycle(duty);
+    }
+}
+
+/// A [`ToneOutput`] refused to play `hz`, already clamped to the buzzer's
+/// limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RejectedFrequency {
+    /// The frequency that [`SetFrequency::set_frequency`] rejected.
+    pub hz: u32,
+}
+
+/// Size-optimised melody player using trait objects.
+///
+/// Plays `notes` as plain tones: each frequency is clamped to
+/// `min_hz..=max_hz`, 0 Hz is a rest, and every note is held at a fixed duty.
+/// The loop is compiled once for the whole program instead of once per
+/// `Buzzer<PWM, D>` instantiation, at the cost of an indirect call per
+/// register access.
+///
+/// # Notes
+/// - Unlike [`Buzzer::melody`] there is no edge fade
+///   ([`Buzzer::set_edge_fade_ms`]) and no dithering ([`Buzzer::set_dither`]);
+///   both would pull the per-millisecond logic into the shared loop.
+/// - Worth it only when several buzzer/PWM/delay types coexist (e.g. two
+///   buzzers, or a mock and a real delay in the same image); with a single
+///   instantiation the generic version is as small and slightly faster.
+/// - Duty rounding is shared with [`Buzzer::tone`] through
+///   [`ToneOutput::tone_duty_percent`], so both players emit the same duty.
+///
+/// # Errors
+/// Stops at the first note whose frequency the output rejects and returns
+/// it as a [`RejectedFrequency`]; the output is left silent.
+///
+/// # Flash
+/// The saving depends on how many instantiations the image holds, so measure
+/// it on the firmware in question: build a `--release` image calling
+/// [`Buzzer::melody`] and one calling [`Buzzer::melody_dyn`], then compare
+/// the `.text` column of `avr-size` for the two ELFs. Each extra
+/// `Buzzer<PWM, D>` type that plays melodies adds its own copy of the
+/// generic loop but shares the single `play_melody_dyn` body.
+pub fn play_melody_dyn(
+    output: &mut dyn ToneOutput,
+    delay: &mut dyn DelayNs,
+    notes: &[(u32, u8, u32)],
+    min_hz: u32,
+    max_hz: u32,
+) -> Result<(), RejectedFrequency> {
+    play_melody_dyn_with_tick(output, delay, notes, min_hz, max_hz, &mut None, &mut || {})
+}
+
+/// Play `notes` like [`play_melody_dyn`], calling `tick` before each note;
+/// see [`Buzzer::melody_with_tick`].
+///
+/// `tuned` is the frequency the output is known to be set to, if any. Notes
+/// at that pitch skip the frequency write, and it is updated as the melody
+/// retunes, so a caller can keep its own retune cache in step.
+pub fn play_melody_dyn_with_tick(
+    output: &mut dyn ToneOutput,
+    delay: &mut dyn DelayNs,
+    notes: &[(u32, u8, u32)],
+    min_hz: u32,
+    max_hz: u32,
+    tuned: &mut Option<u32>,
+    tick: &mut dyn FnMut(),
+) -> Result<(), RejectedFrequency> {
+    for &(frequency_hz, duty_percent, duration_ms) in notes {
+        tick();
+        if frequency_hz != 0 {
+            let hz = frequency_hz.clamp(min_hz, max_hz);
+            if *tuned != Some(hz) {
+                // Unknown until the write succeeds.
+                *tuned = None;
+                output.tone_frequency(hz)?;
+                *tuned = Some(hz);
+            }
+            output.tone_duty_percent(duty_percent);
+        }
+        delay.delay_ms(duration_ms);
+        output.tone_duty(0);
+    }
+    Ok(())
+}
+
+/// The notes of a [`Buzzer::sweep`].
+fn sweep_notes(
+    start_hz: u32,
+    end_hz: u32,
+    duty_percent: u8,
+    duration_ms: u32,
+    steps: SweepSteps,
+) -> impl Iterator<Item = (u32, u8, u32)> {
+    let (lo, hi) = (start_hz.min(end_hz), start_hz.max(end_hz));
+    let count = match steps {
+        SweepSteps::LinearCount(n) | SweepSteps::LogCount(n) => u32::from(n),
+        SweepSteps::LinearHz(hz) => (hi - lo).div_ceil(hz.max(1)) + 1,
+        SweepSteps::LogCents(cents) => {
+            let span_cents = 1_200.0 * math::log2(hi.max(1) as f32 / lo.max(1) as f32);
+            // Trim float noise so an exact multiple of `cents` is not rounded up.
+            math::ceil(span_cents / f32::from(cents.max(1)) - 1e-3) as u32 + 1
+        }
+    }
+    .max(1);
+    let log = matches!(steps, SweepSteps::LogCount(_) | SweepSteps::LogCents(_));
+    let step_ms = (duration_ms / count).max(1);
+
+    (0..count).map(move |i| {
+        let t = if count == 1 {
+            0.0
+        } else {
+            i as f32 / (count - 1) as f32
+        };
+        let frequency_hz = if log {
+            let octaves = math::log2(end_hz.max(1) as f32 / start_hz.max(1) as f32);
+            math::round_u32(start_hz.max(1) as f32 * math::exp2(octaves * t))
+        } else {
+            math::round_u32(start_hz as f32 + (end_hz as f32 - start_hz as f32) * t)
+        };
+        (frequency_hz, duty_percent, step_ms)
+    })
+}
+
+/// Length of one [`Buzzer::set_dither`] cycle. The duty pattern repeats at
+/// 125 Hz or faster, well above any audible flutter in loudness.
+pub const DITHER_PERIOD_MS: u32 = 8;
+
+/// Length in bytes of one record in the [`Buzzer::play_rle`] format.
+pub const RLE_RECORD_LEN: usize = 6;
+
+/// Placeholder enable pin for a [`Buzzer`] without an amplifier enable line.
+///
+/// Every operation is a no-op.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoEnable;
+
+impl PinErrorType for NoEnable {
+    type Error = Infallible;
+}
+
+impl OutputPin for NoEnable {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
 }
 
 /// Simple PWM-based buzzer.
 ///
-/// Owns a PWM channel (`PWM`) and a delay provider (`D`). Duty is given as a
-/// percentage (0–100), duration in milliseconds.
-pub struct Buzzer<PWM, D>
+/// Owns a PWM channel (`PWM`), a delay provider (`D`) and optionally an
+/// amplifier enable pin (`EN`, see [`Buzzer::with_enable_pin`]). Duty is given
+/// as a percentage (0–100), duration in milliseconds.
+///
+/// Tone frequencies are clamped to an optional `min_hz..=max_hz` range (the
+/// audible band of the transducer); by default every frequency is allowed.
+pub struct Buzzer<PWM, D, EN = NoEnable>
 where
     PWM: SetDutyCycle + SetFrequency,
     D: DelayNs,
+    EN: OutputPin,
 {
     pwm: PWM,
     delay: D,
+    enable: EN,
+    enabled: bool,
+    settle_ms: u32,
+    min_hz: u32,
+    max_hz: u32,
+    idle: bool,
+    deadline: Option<Deadline>,
+    frequency_hz: Option<u32>,
+    edge_fade_ms: u32,
+    dither: bool,
+    /// Inside a blocking player, which keeps any amplifier on between notes.
+    playing: bool,
 }
 
 impl<PWM, D> Buzzer<PWM, D>
@@ -48,9 +394,127 @@ where
     ///
     /// * `pwm`   – PWM channel implementing [`SetDutyCycle`]
     /// * `delay` – delay provider implementing [`DelayNs`]
-    pub fn new(mut pwm: PWM, delay: D) -> Self {
+    pub fn new(pwm: PWM, delay: D) -> Self {
+        Self::with_min_max_freq(pwm, delay, 0, u32::MAX)
+    }
+
+    /// Create a new [`Buzzer`] whose tones are clamped to `min_hz..=max_hz`.
+    ///
+    /// See [`Buzzer::set_frequency_limits`].
+    pub fn with_min_max_freq(pwm: PWM, delay: D, min_hz: u32, max_hz: u32) -> Self {
+        let mut buzzer = Buzzer::with_enable_pin(pwm, delay, NoEnable, 0);
+        buzzer.set_frequency_limits(min_hz, max_hz);
+        buzzer
+    }
+
+    /// Consume the driver and return the PWM channel and delay provider.
+    pub fn release(self) -> (PWM, D) {
+        (self.pwm, self.delay)
+    }
+}
+
+impl<PWM, D, EN> Buzzer<PWM, D, EN>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+    EN: OutputPin,
+{
+    /// Create a new [`Buzzer`] whose amplifier is gated by `enable`.
+    ///
+    /// `enable` is driven high before each tone, followed by `settle_ms` of
+    /// silence for the amplifier to power up, and low again once the tone
+    /// ends, cutting idle current and the click of an amplifier left on.
+    /// Melodies and the other players keep it high from their first note to
+    /// their last, so only the first note waits and the timing is unchanged.
+    /// Enable-pin errors are ignored, like duty-cycle errors.
+    pub fn with_enable_pin(mut pwm: PWM, delay: D, mut enable: EN, settle_ms: u32) -> Self {
         let _ = pwm.set_duty_cycle(0);
-        Self { pwm, delay }
+        let _ = enable.set_low();
+        Self {
+            pwm,
+            delay,
+            enable,
+            enabled: false,
+            settle_ms,
+            min_hz: 0,
+            max_hz: u32::MAX,
+            idle: false,
+            deadline: None,
+            frequency_hz: None,
+            edge_fade_ms: 0,
+            dither: false,
+            playing: false,
+        }
+    }
+
+    /// Assert the enable pin and wait for the amplifier to settle, unless it
+    /// is already on from a [`Buzzer::tone_continue`].
+    fn enable_output(&mut self) {
+        if self.enabled {
+            return;
+        }
+        let _ = self.enable.set_high();
+        self.enabled = true;
+        if self.settle_ms > 0 {
+            self.delay.delay_ms(self.settle_ms);
+        }
+    }
+
+    /// Release the enable pin once the output is silent, unless a player is
+    /// between notes.
+    fn disable_output(&mut self) {
+        if self.playing {
+            return;
+        }
+        let _ = self.enable.set_low();
+        self.enabled = false;
+    }
+
+    /// Restrict tone frequencies to `min_hz..=max_hz`.
+    ///
+    /// Tones outside the range are clamped to the nearest limit before they
+    /// reach the PWM, so a computed cue never falls outside what the
+    /// transducer can reproduce. The limits may be given in either order.
+    pub fn set_frequency_limits(&mut self, min_hz: u32, max_hz: u32) {
+        self.min_hz = min_hz.min(max_hz);
+        self.max_hz = max_hz.max(min_hz);
+    }
+
+    /// Fade [`Buzzer::tone`] in and out over `ms` milliseconds at each edge,
+    /// in 1 ms duty steps; 0 (the default) switches it hard.
+    ///
+    /// A couple of milliseconds is enough to soften the click of the duty
+    /// jumping on a square-wave buzzer, without audibly changing the beep.
+    /// The fades are part of the tone's duration, and shortened to half of
+    /// it each for very short tones.
+    pub fn set_edge_fade_ms(&mut self, ms: u32) {
+        self.edge_fade_ms = ms;
+    }
+
+    /// See [`Buzzer::set_edge_fade_ms`].
+    pub fn edge_fade_ms(&self) -> u32 {
+        self.edge_fade_ms
+    }
+
+    /// Dither the duty of [`Buzzer::tone`] between the two nearest PWM steps
+    /// so that it averages out at the requested percentage; off by default.
+    ///
+    /// At high pitches in fast PWM, `max_duty_cycle` can be a handful of
+    /// steps and neighbouring volumes round to the same duty. With dithering
+    /// the steady part of each tone rewrites the duty every millisecond,
+    /// spreading the higher step evenly over each [`DITHER_PERIOD_MS`] cycle,
+    /// which costs one duty write per millisecond. The average is therefore
+    /// accurate to an eighth of a step. Duties within an eighth of a step,
+    /// duties below the first step (held at it, so they are never muted),
+    /// and tones left on by [`Buzzer::start_tone`] or
+    /// [`Buzzer::tone_continue`] are not dithered.
+    pub fn set_dither(&mut self, enabled: bool) {
+        self.dither = enabled;
+    }
+
+    /// See [`Buzzer::set_dither`].
+    pub fn dither(&self) -> bool {
+        self.dither
     }
 
     /// Play a tone at `duty_percent` for `duration_ms` milliseconds.
@@ -58,46 +522,581 @@ where
     /// * `frequency_hz` is pitch of tone
     /// * `duty_percent` is volumne of tone (must be `0->100`).
     /// * `duration_ms` is milliseconds to keep the tone active.
+    ///
+    /// The duty is rounded to the nearest step of
+    /// [`SetDutyCycle::max_duty_cycle`], which may be coarse for high pitches
+    /// in fast PWM. A non-zero `duty_percent` is never rounded down to silence.
+    ///
+    /// A `frequency_hz` of 0 is played as a [`Buzzer::rest`] and never reaches
+    /// [`SetFrequency::set_frequency`], whose period maths would divide by it.
     pub fn tone(
         &mut self,
         frequency_hz: u32,
         duty_percent: u8,
         duration_ms: u32,
-    ) -> Result<(), Infallible> {
-        self.pwm.set_frequency(frequency_hz)?;
-        let max = self.pwm.max_duty_cycle();
-        let duty = (u32::from(max) * (duty_percent as u32) / 100) as u16;
-        let _ = self.pwm.set_duty_cycle(duty);
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        if frequency_hz == 0 {
+            self.rest(duration_ms);
+            return Ok(());
+        }
+        let fade_ms = self.edge_fade_ms.min(duration_ms / 2);
+        if fade_ms == 0 {
+            self.start_tone(frequency_hz, duty_percent)?;
+            self.hold(duty_percent, duration_ms);
+            self.stop_tone();
+            return Ok(());
+        }
+        // Step `i` of `fade_ms` plays at i / (fade_ms + 1) of full duty.
+        let step = |i: u32| (u32::from(duty_percent) * i / (fade_ms + 1)) as u8;
+        self.start_tone(frequency_hz, step(1))?;
+        self.delay.delay_ms(1);
+        for i in 2..=fade_ms {
+            self.set_duty_percent(step(i));
+            self.delay.delay_ms(1);
+        }
+        self.set_duty_percent(duty_percent);
+        self.hold(duty_percent, duration_ms - 2 * fade_ms);
+        for i in (1..=fade_ms).rev() {
+            self.set_duty_percent(step(i));
+            self.delay.delay_ms(1);
+        }
+        self.stop_tone();
+        Ok(())
+    }
+
+    /// Keep the tone already playing at `duty_percent` for `duration_ms`,
+    /// dithered if enabled (see [`Buzzer::set_dither`]).
+    fn hold(&mut self, duty_percent: u8, duration_ms: u32) {
+        let exact = if self.dither {
+            u32::from(self.pwm.max_duty_cycle()) * u32::from(duty_percent.min(100))
+        } else {
+            0
+        };
+        // Milliseconds of each cycle spent one step higher.
+        let high_ms = (exact % 100 * DITHER_PERIOD_MS + 50) / 100;
+        // Otherwise the duty from `start_tone` is already the best choice.
+        if exact < 100 || high_ms == 0 || high_ms == DITHER_PERIOD_MS {
+            self.delay.delay_ms(duration_ms);
+            return;
+        }
+        let low = (exact / 100) as u16;
+        let mut error = 0;
+        for _ in 0..duration_ms {
+            error += high_ms;
+            let duty = if error >= DITHER_PERIOD_MS {
+                error -= DITHER_PERIOD_MS;
+                low + 1
+            } else {
+                low
+            };
+            let _ = self.pwm.set_duty_cycle(duty);
+            self.delay.delay_ms(1);
+        }
+    }
+
+    /// Change the duty of the tone already playing.
+    fn set_duty_percent(&mut self, duty_percent: u8) {
+        self.pwm.tone_duty_percent(duty_percent);
+    }
+
+    /// Play a tone like [`Buzzer::tone`] with the duty chosen by `timbre`.
+    pub fn tone_timbre(
+        &mut self,
+        frequency_hz: u32,
+        timbre: Timbre,
+        duration_ms: u32,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.tone(frequency_hz, timbre.duty_percent(), duration_ms)
+    }
+
+    /// Play a tone like [`Buzzer::tone`] but leave it sounding afterwards.
+    ///
+    /// The next `tone_continue` or [`Buzzer::tone`] changes pitch and duty
+    /// directly, without the 0% write in between that clicks on some
+    /// transducers, so a run of calls plays legato. Use [`Buzzer::tone`] for
+    /// a clean single tone or the last note of a run, or call
+    /// [`Buzzer::stop_tone`] to end it. A `frequency_hz` of 0 silences the output
+    /// for `duration_ms` but keeps any amplifier enabled.
+    pub fn tone_continue(
+        &mut self,
+        frequency_hz: u32,
+        duty_percent: u8,
+        duration_ms: u32,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        if frequency_hz == 0 {
+            self.rest(duration_ms);
+            return Ok(());
+        }
+        self.start_tone(frequency_hz, duty_percent)?;
         self.delay.delay_ms(duration_ms);
+        Ok(())
+    }
+
+    /// Turn on a tone at `duty_percent` and return at once.
+    ///
+    /// **The tone stays on until [`Buzzer::stop_tone`]** (or the next
+    /// [`Buzzer::tone`], which ends by silencing it); there is no timeout.
+    /// Meant for tuning tools, e.g. holding a steady pitch while finding a
+    /// piezo's resonance. A `frequency_hz` of 0 just silences the output.
+    pub fn start_tone(
+        &mut self,
+        frequency_hz: u32,
+        duty_percent: u8,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.deadline = None;
+        if frequency_hz == 0 {
+            let _ = self.pwm.set_duty_cycle(0);
+            return Ok(());
+        }
+        if self.idle {
+            self.pwm.resume();
+            self.idle = false;
+        }
+        self.retune(frequency_hz.clamp(self.min_hz, self.max_hz))?;
+        self.enable_output();
+        self.pwm.tone_duty_percent(duty_percent);
+        Ok(())
+    }
+
+    /// The frequency last written to the PWM, after clamping, or `None`
+    /// before the first tone.
+    pub fn frequency_hz(&self) -> Option<u32> {
+        self.frequency_hz
+    }
+
+    /// Write `hz` to the PWM unless it is already set, sparing a timer
+    /// reload (and a possible glitch) for repeated cues at one pitch.
+    ///
+    /// PWMs that quantise the frequency may also skip writes that land on
+    /// the same period, as the firmware `BuzzerPwm` does for `OCR1A`.
+    fn retune(&mut self, hz: u32) -> Result<(), <PWM as SetFrequency>::Error> {
+        if self.frequency_hz == Some(hz) {
+            return Ok(());
+        }
+        // Unknown until the write succeeds.
+        self.frequency_hz = None;
+        self.pwm.set_frequency(hz)?;
+        self.frequency_hz = Some(hz);
+        Ok(())
+    }
+
+    /// Silence a tone left on by [`Buzzer::start_tone`] or
+    /// [`Buzzer::tone_continue`] and release the amplifier.
+    pub fn stop_tone(&mut self) {
+        self.deadline = None;
         let _ = self.pwm.set_duty_cycle(0);
+        self.disable_output();
+    }
+
+    /// Start a tone that [`Buzzer::service`] ends at the returned deadline,
+    /// and return at once.
+    ///
+    /// A lighter alternative to [`AsyncBuzzer`](super::async_buzzer::AsyncBuzzer)
+    /// for a cooperative main loop: no tick source is owned, the caller
+    /// passes `now_ms` here and to [`Buzzer::service`]. The tone may run
+    /// past `duration_ms` by up to one loop iteration. Any other tone call
+    /// cancels the deadline.
+    pub fn beep_at(
+        &mut self,
+        frequency_hz: u32,
+        duty_percent: u8,
+        now_ms: u32,
+        duration_ms: u32,
+    ) -> Result<Deadline, <PWM as SetFrequency>::Error> {
+        self.start_tone(frequency_hz, duty_percent)?;
+        let deadline = Deadline::after(now_ms, duration_ms);
+        self.deadline = Some(deadline);
+        Ok(deadline)
+    }
+
+    /// End a tone started by [`Buzzer::beep_at`] once `now_ms` reaches its
+    /// deadline.
+    ///
+    /// Returns `true` on the call that silenced it. Call from the main loop.
+    pub fn service(&mut self, now_ms: u32) -> bool {
+        match self.deadline {
+            Some(deadline) if deadline.is_due(now_ms) => {
+                self.stop_tone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Play `note` at 50% duty for `duration_ms`.
+    pub fn play_note(
+        &mut self,
+        note: Note,
+        duration_ms: u32,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.tone(note.frequency_hz(), 50, duration_ms)
+    }
+
+    /// Play `steps` ascending chromatic notes starting at `root`, each for
+    /// `note_ms`.
+    ///
+    /// A bring-up aid: on hardware every step should sound an even semitone
+    /// higher, so a stuck, skipped or out-of-tune step points at the timer
+    /// maths or the frequency limits.
+    pub fn play_scale(
+        &mut self,
+        root: Note,
+        steps: u8,
+        note_ms: u32,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        for step in 0..steps {
+            self.play_note(root.up(step), note_ms)?;
+        }
         Ok(())
     }
+
+    /// Play a sequence of `(frequency_hz, duty_percent, duration_ms)` notes.
+    ///
+    /// `notes` can be any note source: an owned array, `slice.iter().copied()`,
+    /// or an iterator that reads from EEPROM or generates notes on the fly, so
+    /// nothing has to be buffered first.
+    pub fn melody(
+        &mut self,
+        notes: impl IntoIterator<Item = (u32, u8, u32)>,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.melody_with_tick(notes, &mut || {})
+    }
+
+    /// Play `notes` like [`Buzzer::melody`], calling `tick` before each note.
+    ///
+    /// # Watchdog
+    ///
+    /// A long melody blocks for its whole length, which can exceed the
+    /// watchdog timeout and reset the device. Feed the watchdog from `tick`
+    /// so it is only ever starved for one note: every `duration_ms` must then
+    /// stay below the watchdog timeout, less a margin for the callback and
+    /// timer set-up. With the ATmega16's longest timeout of ~2.1 s at 5 V,
+    /// keep notes under about 2 s. [`Buzzer::melody_repeat`] checks
+    /// `should_stop` at the same points, so an alarm loop can feed the
+    /// watchdog there. Every other player has a `_with_tick` variant that
+    /// does the same.
+    pub fn melody_with_tick(
+        &mut self,
+        notes: impl IntoIterator<Item = (u32, u8, u32)>,
+        tick: &mut impl FnMut(),
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.play_notes(notes, || {
+            tick();
+            false
+        })
+        .map(|_| ())
+    }
+
+    /// The loop behind the blocking players: play `notes`, calling `before`
+    /// ahead of each one and stopping early once it returns `true`.
+    ///
+    /// Returns `Ok(true)` if `before` stopped playback.
+    fn play_notes(
+        &mut self,
+        notes: impl IntoIterator<Item = (u32, u8, u32)>,
+        mut before: impl FnMut() -> bool,
+    ) -> Result<bool, <PWM as SetFrequency>::Error> {
+        self.playing = true;
+        let play = || {
+            for (frequency_hz, duty_percent, duration_ms) in notes {
+                if before() {
+                    return Ok(true);
+                }
+                self.tone(frequency_hz, duty_percent, duration_ms)?;
+            }
+            Ok(false)
+        };
+        let result = play();
+        self.playing = false;
+        self.disable_output();
+        result
+    }
+
+    /// Play `notes` through [`play_melody_dyn`], whose loop is shared by every
+    /// buzzer type; see its `# Flash` section.
+    ///
+    /// The notes are plain tones: [`Buzzer::set_edge_fade_ms`] and
+    /// [`Buzzer::set_dither`] do not apply. Use [`Buzzer::melody`] for those.
+    pub fn melody_dyn(&mut self, notes: &[(u32, u8, u32)]) -> Result<(), RejectedFrequency> {
+        self.melody_dyn_with_tick(notes, &mut || {})
+    }
+
+    /// Play `notes` like [`Buzzer::melody_dyn`], calling `tick` before each
+    /// note; see [`Buzzer::melody_with_tick`].
+    pub fn melody_dyn_with_tick(
+        &mut self,
+        notes: &[(u32, u8, u32)],
+        tick: &mut dyn FnMut(),
+    ) -> Result<(), RejectedFrequency> {
+        if self.idle {
+            self.pwm.resume();
+            self.idle = false;
+        }
+        // Enabled once for the whole melody rather than per note.
+        self.enable_output();
+        let result = play_melody_dyn_with_tick(
+            &mut self.pwm,
+            &mut self.delay,
+            notes,
+            self.min_hz,
+            self.max_hz,
+            &mut self.frequency_hz,
+            tick,
+        );
+        self.disable_output();
+        result
+    }
+
+    /// Play `notes` up to `repeats` times, stopping early once `should_stop`
+    /// returns `true`.
+    ///
+    /// `should_stop` is checked before every note, so the worst-case reaction
+    /// time is the longest note in the melody. It is also the place to tick,
+    /// as for [`Buzzer::melody_with_tick`]. Returns `Ok(true)` if playback
+    /// was stopped early and `Ok(false)` if every repeat played.
+    pub fn melody_repeat(
+        &mut self,
+        notes: &[(u32, u8, u32)],
+        repeats: u32,
+        should_stop: impl FnMut() -> bool,
+    ) -> Result<bool, <PWM as SetFrequency>::Error> {
+        let notes = (0..repeats).flat_map(|_| notes.iter().copied());
+        self.play_notes(notes, should_stop)
+    }
+
+    /// Glide from `start_hz` to `end_hz` over roughly `duration_ms`.
+    ///
+    /// The tones are spaced according to `steps` (see [`SweepSteps`] for the
+    /// smoothness/time trade-off) and share `duration_ms` equally, each lasting
+    /// at least 1 ms. Both end frequencies are always played; a sweep may go
+    /// down as well as up.
+    pub fn sweep(
+        &mut self,
+        start_hz: u32,
+        end_hz: u32,
+        duty_percent: u8,
+        duration_ms: u32,
+        steps: SweepSteps,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.sweep_with_tick(
+            start_hz,
+            end_hz,
+            duty_percent,
+            duration_ms,
+            steps,
+            &mut || {},
+        )
+    }
+
+    /// Glide like [`Buzzer::sweep`], calling `tick` before each step; see
+    /// [`Buzzer::melody_with_tick`].
+    pub fn sweep_with_tick(
+        &mut self,
+        start_hz: u32,
+        end_hz: u32,
+        duty_percent: u8,
+        duration_ms: u32,
+        steps: SweepSteps,
+        tick: &mut impl FnMut(),
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        let notes = sweep_notes(start_hz, end_hz, duty_percent, duration_ms, steps);
+        self.melody_with_tick(notes, tick)
+    }
+
+    /// Play a run-length-encoded melody.
+    ///
+    /// `data` is a sequence of [`RLE_RECORD_LEN`]-byte records, each playing
+    /// one note `count` times back to back:
+    ///
+    /// | Byte | Field                            |
+    /// |------|----------------------------------|
+    /// | 0    | `count` (repeats, 0 skips)       |
+    /// | 1–2  | `frequency_hz`, `u16` little-endian |
+    /// | 3    | `duty_percent`                   |
+    /// | 4–5  | `duration_ms`, `u16` little-endian |
+    ///
+    /// A trailing partial record is ignored. Compared to a table of
+    /// `(u32, u8, u32)` triples this is half the size for single notes and
+    /// far smaller for repeated ones.
+    pub fn play_rle(&mut self, data: &[u8]) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.play_rle_with_tick(data, &mut || {})
+    }
+
+    /// Play `data` like [`Buzzer::play_rle`], calling `tick` before each
+    /// note; see [`Buzzer::melody_with_tick`].
+    pub fn play_rle_with_tick(
+        &mut self,
+        data: &[u8],
+        tick: &mut impl FnMut(),
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        let notes = data.chunks_exact(RLE_RECORD_LEN).flat_map(|record| {
+            let frequency_hz = u32::from(u16::from_le_bytes([record[1], record[2]]));
+            let duration_ms = u32::from(u16::from_le_bytes([record[4], record[5]]));
+            let note = (frequency_hz, record[3], duration_ms);
+            core::iter::repeat_n(note, usize::from(record[0]))
+        });
+        self.melody_with_tick(notes, tick)
+    }
+
+    /// Play `n` short beeps so the user can count them, e.g. "waypoint 3".
+    ///
+    /// Each beep is `unit_ms` long at [`COUNT_BEEP_HZ`] and 50% duty, with a
+    /// gap of `unit_ms` between beeps and none after the last. `n == 0` plays
+    /// nothing.
+    pub fn beep_count(&mut self, n: u8, unit_ms: u32) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.beep_count_with_tick(n, unit_ms, &mut || {})
+    }
+
+    /// Beep like [`Buzzer::beep_count`], calling `tick` before each beep and
+    /// gap; see [`Buzzer::melody_with_tick`].
+    pub fn beep_count_with_tick(
+        &mut self,
+        n: u8,
+        unit_ms: u32,
+        tick: &mut impl FnMut(),
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        let notes = (0..n).flat_map(|i| {
+            let gap = (i > 0).then_some((0, 0, unit_ms));
+            gap.into_iter().chain([(COUNT_BEEP_HZ, 50, unit_ms)])
+        });
+        self.melody_with_tick(notes, tick)
+    }
+
+    /// Warble rapidly between [`PANIC_HIGH_HZ`] and [`PANIC_LOW_HZ`] for
+    /// `cycles` high-low cycles, with no gaps.
+    ///
+    /// Reserved for fatal conditions such as a panic or an imminent
+    /// watchdog reset: no routine cue is continuous or alternates this fast,
+    /// so it cannot be mistaken for one. Always played at 50% duty, the
+    /// loudest a square wave gets, whatever volume other cues use.
+    pub fn panic_pattern(&mut self, cycles: u32) -> Result<(), <PWM as SetFrequency>::Error> {
+        for _ in 0..cycles {
+            self.tone_continue(PANIC_HIGH_HZ, 50, PANIC_STEP_MS)?;
+            self.tone_continue(PANIC_LOW_HZ, 50, PANIC_STEP_MS)?;
+        }
+        self.stop_tone();
+        Ok(())
+    }
+
+    /// Send `text` in Morse code at `frequency_hz`, one unit being `unit_ms`.
+    ///
+    /// See [`morse::segments`] for the timing; ~60 ms units give a readable
+    /// 20 words per minute.
+    pub fn morse(
+        &mut self,
+        text: &[u8],
+        frequency_hz: u32,
+        unit_ms: u32,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        self.morse_with_tick(text, frequency_hz, unit_ms, &mut || {})
+    }
+
+    /// Send `text` like [`Buzzer::morse`], calling `tick` before each dot,
+    /// dash and gap; see [`Buzzer::melody_with_tick`].
+    pub fn morse_with_tick(
+        &mut self,
+        text: &[u8],
+        frequency_hz: u32,
+        unit_ms: u32,
+        tick: &mut impl FnMut(),
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        let notes = morse::segments(text).map(|segment| {
+            let hz = if segment.on { frequency_hz } else { 0 };
+            (hz, 50, unit_ms * u32::from(segment.units))
+        });
+        self.melody_with_tick(notes, tick)
+    }
+
+    /// Stay silent for `duration_ms` milliseconds (e.g. the gap between beeps).
+    pub fn rest(&mut self, duration_ms: u32) {
+        let _ = self.pwm.set_duty_cycle(0);
+        self.delay.delay_ms(duration_ms);
+    }
+
+    /// Stay silent for up to `duration_ms`, checking `stop` every millisecond.
+    ///
+    /// Returns `true` if `stop` ended the rest early. Polling once per
+    /// millisecond suits drivers such as [`Button`](super::button::Button)
+    /// that count their thresholds in polls.
+    pub fn rest_until(&mut self, duration_ms: u32, stop: impl FnMut() -> bool) -> bool {
+        let _ = self.pwm.set_duty_cycle(0);
+        self.wait_until(duration_ms, stop)
+    }
+
+    /// Wait up to `duration_ms` without touching the output, checking `stop`
+    /// every millisecond; the loop behind [`Buzzer::rest_until`] and
+    /// [`Buzzer::tone_until`].
+    fn wait_until(&mut self, duration_ms: u32, mut stop: impl FnMut() -> bool) -> bool {
+        for _ in 0..duration_ms {
+            if stop() {
+                return true;
+            }
+            self.delay.delay_ms(1);
+        }
+        false
+    }
+
+    /// Play a tone for up to `duration_ms`, checking `stop` every millisecond.
+    ///
+    /// The tone counterpart of [`Buzzer::rest_until`], for cues that must
+    /// react to a button within a note rather than between notes. The duty is
+    /// held steady: edge fade and dithering do not apply. A `frequency_hz` of
+    /// 0 rests as [`Buzzer::rest_until`] does. Returns `Ok(true)` if `stop`
+    /// ended the tone early.
+    pub fn tone_until(
+        &mut self,
+        frequency_hz: u32,
+        duty_percent: u8,
+        duration_ms: u32,
+        stop: impl FnMut() -> bool,
+    ) -> Result<bool, <PWM as SetFrequency>::Error> {
+        if frequency_hz == 0 {
+            return Ok(self.rest_until(duration_ms, stop));
+        }
+        self.start_tone(frequency_hz, duty_percent)?;
+        let stopped = self.wait_until(duration_ms, stop);
+        self.stop_tone();
+        Ok(stopped)
+    }
+
+    /// Silence the output and put the PWM into its low-power standby.
+    ///
+    /// On the AVR this disconnects OC1B from the pin and can stop the Timer1
+    /// clock (see [`SetFrequency::standby`]). The next [`Buzzer::tone`]
+    /// resumes the PWM automatically.
+    pub fn idle(&mut self) {
+        self.stop_tone();
+        if !self.idle {
+            self.pwm.standby();
+            self.idle = true;
+        }
+    }
+
+    /// Consume the driver and return the PWM channel, delay provider and
+    /// enable pin.
+    pub fn release_with_enable_pin(self) -> (PWM, D, EN) {
+        (self.pwm, self.delay, self.enable)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use core::cell::Cell;
+    use crate::firmware::shared::delay::NopDelay;
+    use crate::firmware::shared::fixtures::{CoarsePwm, RecordingPwm};
+    use core::convert::Infallible;
     use embedded_hal::delay::DelayNs;
     use embedded_hal_mock::eh1::pwm::{Mock as PwmMock, Transaction as PwmTxn};
 
-    struct TrackingDelay {
-        ms: Cell<Option<u32>>,
+    /// Delay that records every wait.
+    #[derive(Default)]
+    struct LoggingDelay {
+        waits_ms: Vec<u32>,
     }
-    impl TrackingDelay {
-        fn new() -> Self {
-            Self {
-                ms: Cell::new(None),
-            }
-        }
-        fn last_ms(&self) -> Option<u32> {
-            self.ms.get()
-        }
-    }
-    impl DelayNs for TrackingDelay {
+    impl DelayNs for LoggingDelay {
         fn delay_ns(&mut self, _ns: u32) {}
         fn delay_ms(&mut self, ms: u32) {
-            self.ms.set(Some(ms));
+            self.waits_ms.push(ms);
         }
     }
 
@@ -118,11 +1117,581 @@ mod tests {
         ];
 
         let pwm = PwmMock::new(&expectations);
-        let delay = TrackingDelay::new();
+        let delay = NopDelay::new();
 
         let mut buzzer = Buzzer::new(pwm, delay);
         buzzer.tone(440, 100 / 2, 200).unwrap();
         buzzer.pwm.done();
-        assert_eq!(buzzer.delay.last_ms(), Some(200));
+        assert_eq!(buzzer.delay.elapsed_ms(), 200);
+    }
+
+    #[test]
+    fn test_timbre_presets_set_distinct_duties() {
+        assert_ne!(Timbre::Hollow.duty_percent(), Timbre::Full.duty_percent());
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        buzzer.tone_timbre(880, Timbre::Hollow, 10).unwrap();
+        buzzer.tone_timbre(880, Timbre::Full, 10).unwrap();
+        assert_eq!(buzzer.pwm.duties, [0, 50, 0, 25, 0]);
+    }
+
+    #[test]
+    fn test_service_stops_beep_only_at_deadline() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        let deadline = buzzer.beep_at(1_000, 50, u32::MAX - 20, 50).unwrap();
+        assert_eq!(deadline.at_ms(), 29);
+        assert!(!buzzer.service(u32::MAX));
+        assert!(!buzzer.service(28));
+        assert_eq!(buzzer.pwm.duties, [0, 50]);
+
+        assert!(buzzer.service(29));
+        assert_eq!(buzzer.pwm.duties, [0, 50, 0]);
+        assert!(!buzzer.service(40));
+
+        // A blocking tone in between cancels the pending deadline.
+        buzzer.beep_at(1_000, 50, 100, 50).unwrap();
+        buzzer.tone(2_000, 50, 10).unwrap();
+        assert!(!buzzer.service(200));
+        assert_eq!(buzzer.delay.elapsed_ms(), 10);
+    }
+
+    #[test]
+    fn test_fixed_frequency_pwm_drives_buzzer_by_duty_only() {
+        // The embedded-hal-mock PWM only knows about duty cycles.
+        let expectations = [
+            PwmTxn::set_duty_cycle(0),
+            PwmTxn::max_duty_cycle(200),
+            PwmTxn::set_duty_cycle(100),
+            PwmTxn::set_duty_cycle(0),
+            PwmTxn::set_duty_cycle(0),
+            PwmTxn::max_duty_cycle(200),
+            PwmTxn::set_duty_cycle(50),
+            PwmTxn::set_duty_cycle(0),
+        ];
+        let pwm = FixedFrequency::new(PwmMock::new(&expectations));
+        let mut buzzer = Buzzer::new(pwm, NopDelay::new());
+        buzzer
+            .melody([(440, 50, 100), (0, 0, 50), (880, 25, 100)])
+            .unwrap();
+
+        let (pwm, delay) = buzzer.release();
+        pwm.into_inner().done();
+        assert_eq!(delay.elapsed_ms(), 250);
+    }
+
+    #[test]
+    fn test_panic_pattern_alternates_high_low_at_full_volume() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        buzzer.panic_pattern(3).unwrap();
+        assert_eq!(
+            buzzer.pwm.frequencies,
+            [PANIC_HIGH_HZ, PANIC_LOW_HZ].repeat(3)
+        );
+        // Continuous: no silence until the very end.
+        let (last, tones) = buzzer.pwm.duties[1..].split_last().unwrap();
+        assert!(tones.iter().all(|&d| d == 50), "{tones:?}");
+        assert_eq!(*last, 0);
+        assert_eq!(buzzer.delay.elapsed_ms(), u64::from(6 * PANIC_STEP_MS));
+    }
+
+    #[test]
+    fn test_dither_averages_to_requested_duty() {
+        let mut buzzer = Buzzer::new(CoarsePwm::new(4), NopDelay::new());
+        // 20% and 30% of 4 steps both round to 1 without dithering.
+        buzzer.tone(8_000, 30, 100).unwrap();
+        buzzer.tone(8_000, 20, 100).unwrap();
+        assert_eq!(buzzer.pwm.duties, [0, 1, 0, 1, 0]);
+
+        buzzer.set_dither(true);
+        for (percent, steps) in [(30, 1.2), (60, 2.4), (90, 3.6)] {
+            buzzer.pwm.duties.clear();
+            buzzer.tone(8_000, percent, 96).unwrap();
+            // Skip the initial rounded duty and the final 0.
+            let held = &buzzer.pwm.duties[1..buzzer.pwm.duties.len() - 1];
+            assert_eq!(held.len(), 96);
+            assert!(
+                held.iter()
+                    .all(|&d| d == steps as u16 || d == steps as u16 + 1)
+            );
+            // Every cycle is the same, so there is no slow flutter.
+            let period = DITHER_PERIOD_MS as usize;
+            assert!(held.chunks(period).all(|cycle| cycle == &held[..period]));
+            let mean = held.iter().map(|&d| f32::from(d)).sum::<f32>() / held.len() as f32;
+            assert!((mean - steps).abs() <= 1.0 / 16.0, "{percent}%: {mean}");
+        }
+
+        // An exact step is held without rewrites, and so is a duty below the
+        // first step, which stays audible rather than dithering down to 0.
+        for (percent, duty) in [(50, 2), (20, 1), (5, 1)] {
+            buzzer.pwm.duties.clear();
+            buzzer.tone(8_000, percent, 100).unwrap();
+            assert_eq!(buzzer.pwm.duties, [duty, 0]);
+        }
+        assert_eq!(buzzer.delay.elapsed_ms(), 788);
+    }
+
+    #[test]
+    fn test_edge_fade_steps_duty_at_both_edges() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), LoggingDelay::default());
+        buzzer.tone(1_000, 60, 10).unwrap();
+        assert_eq!(buzzer.pwm.duties, [0, 60, 0]);
+
+        buzzer.pwm.duties.clear();
+        buzzer.delay.waits_ms.clear();
+        buzzer.set_edge_fade_ms(2);
+        buzzer.tone(1_000, 60, 10).unwrap();
+        assert_eq!(buzzer.pwm.duties, [20, 40, 60, 40, 20, 0]);
+        assert_eq!(buzzer.delay.waits_ms, [1, 1, 6, 1, 1]);
+
+        // Too short for two full fades.
+        buzzer.pwm.duties.clear();
+        buzzer.tone(1_000, 60, 2).unwrap();
+        assert_eq!(buzzer.pwm.duties, [30, 60, 30, 0]);
+    }
+
+    #[test]
+    fn test_small_duty_at_small_max_stays_audible() {
+        let expectations = [
+            PwmTxn::set_duty_cycle(0),
+            PwmTxn::max_duty_cycle(100),
+            PwmTxn::set_duty_cycle(1),
+            PwmTxn::set_duty_cycle(0),
+        ];
+        let mut buzzer = Buzzer::new(PwmMock::new(&expectations), NopDelay::new());
+        buzzer.tone(8_000, 1, 10).unwrap();
+        buzzer.pwm.done();
+    }
+
+    #[test]
+    fn test_melody_repeat_stops_when_closure_returns_true() {
+        let note = [
+            PwmTxn::max_duty_cycle(100),
+            PwmTxn::set_duty_cycle(100),
+            PwmTxn::set_duty_cycle(0),
+        ];
+        let mut expectations = vec![PwmTxn::set_duty_cycle(0)];
+        for _ in 0..4 {
+            expectations.extend_from_slice(&note);
+        }
+
+        let pwm = PwmMock::new(&expectations);
+        let mut buzzer = Buzzer::new(pwm, NopDelay::new());
+        let melody = [(440, 100, 10), (660, 100, 10), (880, 100, 10)];
+
+        let mut checks = 0;
+        let stopped = buzzer
+            .melody_repeat(&melody, 5, || {
+                checks += 1;
+                checks > 4
+            })
+            .unwrap();
+
+        assert!(stopped);
+        buzzer.pwm.done();
+    }
+
+    #[test]
+    fn test_tone_outside_limits_is_clamped() {
+        let mut buzzer =
+            Buzzer::with_min_max_freq(RecordingPwm::default(), NopDelay::new(), 1_000, 4_000);
+        buzzer.tone(200, 50, 10).unwrap();
+        buzzer.tone(9_000, 50, 10).unwrap();
+        buzzer.tone(2_500, 50, 10).unwrap();
+
+        buzzer.set_frequency_limits(3_000, 500);
+        buzzer.tone(4_000, 50, 10).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [1_000, 4_000, 2_500, 3_000]);
+    }
+
+    #[test]
+    fn test_play_rle_matches_expanded_melody() {
+        let expanded = [
+            (880, 50, 100),
+            (880, 50, 100),
+            (880, 50, 100),
+            (440, 30, 250),
+            (1_320, 80, 50),
+            (1_320, 80, 50),
+        ];
+        #[rustfmt::skip]
+        let rle = [
+            3, 0x70, 0x03, 50, 100, 0,
+            1, 0xB8, 0x01, 30, 0xFA, 0,
+            2, 0x28, 0x05, 80, 50, 0,
+            9, 0x00,
+        ];
+
+        let mut plain = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        plain.melody(expanded).unwrap();
+        let mut packed = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        packed.play_rle(&rle).unwrap();
+
+        assert_eq!(packed.pwm.frequencies, plain.pwm.frequencies);
+        assert_eq!(packed.pwm.duties, plain.pwm.duties);
+    }
+
+    #[test]
+    fn test_idle_enters_standby_once_and_next_tone_resumes() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        buzzer.tone(440, 50, 10).unwrap();
+        assert_eq!((buzzer.pwm.standbys, buzzer.pwm.resumes), (0, 0));
+
+        buzzer.idle();
+        buzzer.idle();
+        assert_eq!((buzzer.pwm.standbys, buzzer.pwm.resumes), (1, 0));
+
+        buzzer.tone(440, 50, 10).unwrap();
+        buzzer.tone(440, 50, 10).unwrap();
+        assert_eq!((buzzer.pwm.standbys, buzzer.pwm.resumes), (1, 1));
+    }
+
+    #[test]
+    fn test_zero_frequency_tone_rests_without_set_frequency() {
+        let mut buzzer =
+            Buzzer::with_min_max_freq(RecordingPwm::default(), NopDelay::new(), 500, 4_000);
+        buzzer.tone(0, 50, 100).unwrap();
+        assert!(buzzer.pwm.frequencies.is_empty());
+        assert_eq!(buzzer.pwm.duties, [0, 0]);
+        assert_eq!(buzzer.delay.elapsed_ms(), 100);
+    }
+
+    #[test]
+    fn test_log_sweep_is_geometric() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        buzzer
+            .sweep(440, 3_520, 50, 400, SweepSteps::LogCount(4))
+            .unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [440, 880, 1_760, 3_520]);
+        assert_eq!(buzzer.delay.elapsed_ms(), 400);
+
+        let mut by_cents = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        by_cents
+            .sweep(3_520, 440, 50, 400, SweepSteps::LogCents(1_200))
+            .unwrap();
+        assert_eq!(by_cents.pwm.frequencies, [3_520, 1_760, 880, 440]);
+    }
+
+    #[test]
+    fn test_linear_sweep_by_step_size() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        buzzer
+            .sweep(1_000, 1_250, 50, 60, SweepSteps::LinearHz(100))
+            .unwrap();
+        // Three 100 Hz gaps are needed to cover 250 Hz, so four evenly spaced tones.
+        assert_eq!(buzzer.pwm.frequencies, [1_000, 1_083, 1_167, 1_250]);
+        assert_eq!(buzzer.delay.elapsed_ms(), 60);
+    }
+
+    #[test]
+    fn test_repeated_frequency_is_set_once() {
+        let mut buzzer =
+            Buzzer::with_min_max_freq(RecordingPwm::default(), NopDelay::new(), 500, 4_000);
+        assert_eq!(buzzer.frequency_hz(), None);
+        buzzer.tone(1_000, 50, 10).unwrap();
+        buzzer.tone(1_000, 50, 10).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [1_000]);
+        // Both clamp to the same 4 kHz.
+        buzzer.tone(5_000, 50, 10).unwrap();
+        buzzer.tone(6_000, 50, 10).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [1_000, 4_000]);
+        assert_eq!(buzzer.frequency_hz(), Some(4_000));
+
+        buzzer.melody_dyn(&[(1_000, 50, 10)]).unwrap();
+        buzzer.tone(4_000, 50, 10).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [1_000, 4_000, 1_000, 4_000]);
+    }
+
+    #[test]
+    fn test_beep_count_plays_evenly_spaced_beeps() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), LoggingDelay::default());
+        buzzer.beep_count(4, 120).unwrap();
+        // Same pitch each time, so the PWM is tuned only once.
+        assert_eq!(buzzer.pwm.frequencies, [COUNT_BEEP_HZ]);
+        // One 120 ms beep, then three (gap, beep) pairs.
+        assert_eq!(buzzer.delay.waits_ms, [120; 7]);
+        assert_eq!(buzzer.pwm.duties, [0, 50, 0, 0, 50, 0, 0, 50, 0, 0, 50, 0]);
+
+        buzzer.beep_count(0, 120).unwrap();
+        assert_eq!(buzzer.delay.waits_ms.len(), 7);
+    }
+
+    #[test]
+    fn test_dyn_melody_matches_generic() {
+        let notes = [(440, 50, 100), (0, 50, 40), (9_000, 80, 60), (200, 10, 20)];
+        let mut generic =
+            Buzzer::with_min_max_freq(RecordingPwm::default(), LoggingDelay::default(), 300, 4_000);
+        generic.melody(notes.iter().copied()).unwrap();
+        let mut dynamic =
+            Buzzer::with_min_max_freq(RecordingPwm::default(), LoggingDelay::default(), 300, 4_000);
+        dynamic.melody_dyn(&notes).unwrap();
+
+        assert_eq!(dynamic.pwm.frequencies, generic.pwm.frequencies);
+        assert_eq!(dynamic.pwm.duties, generic.pwm.duties);
+        assert_eq!(dynamic.delay.waits_ms, generic.delay.waits_ms);
+
+        // On a four-step PWM the shared rounding keeps 5% audible in both.
+        let notes = [(440, 5, 10), (440, 60, 10)];
+        let mut generic = Buzzer::new(CoarsePwm::new(4), NopDelay::new());
+        generic.melody(notes.iter().copied()).unwrap();
+        let mut dynamic = Buzzer::new(CoarsePwm::new(4), NopDelay::new());
+        dynamic.melody_dyn(&notes).unwrap();
+        assert_eq!(dynamic.pwm.duties, generic.pwm.duties);
+        assert!(dynamic.pwm.duties.contains(&1));
+    }
+
+    #[test]
+    fn test_dyn_melody_plays_plain_notes_and_keeps_the_retune_cache() {
+        let notes = [(440, 30, 10), (440, 30, 10), (880, 30, 10)];
+        let mut buzzer = Buzzer::new(CoarsePwm::new(4), LoggingDelay::default());
+        buzzer.set_edge_fade_ms(3);
+        buzzer.set_dither(true);
+        buzzer.melody_dyn(&notes).unwrap();
+        // One wait and one fixed duty per note: no fade ramp, no dither.
+        assert_eq!(buzzer.delay.waits_ms, [10; 3]);
+        assert_eq!(buzzer.pwm.duties, [0, 1, 0, 1, 0, 1, 0]);
+
+        // The generic player fades and dithers the same notes.
+        let mut generic = Buzzer::new(CoarsePwm::new(4), LoggingDelay::default());
+        generic.set_edge_fade_ms(3);
+        generic.set_dither(true);
+        generic.melody(notes.iter().copied()).unwrap();
+        assert!(generic.delay.waits_ms.len() > 3);
+        assert!(generic.pwm.duties.contains(&2));
+
+        // The repeated 440 Hz note is not retuned, and the cache survives.
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        buzzer.set_edge_fade_ms(3);
+        buzzer.melody_dyn(&notes).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [440, 880]);
+        assert_eq!(buzzer.frequency_hz(), Some(880));
+        buzzer.tone(880, 30, 10).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [440, 880]);
+    }
+
+    #[test]
+    fn test_dyn_melody_reports_a_rejected_frequency() {
+        /// Accepts frequencies up to 1 kHz only.
+        #[derive(Default)]
+        struct UpTo1k(RecordingPwm);
+        impl embedded_hal::pwm::ErrorType for UpTo1k {
+            type Error = Infallible;
+        }
+        impl SetDutyCycle for UpTo1k {
+            fn max_duty_cycle(&self) -> u16 {
+                self.0.max_duty_cycle()
+            }
+            fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Infallible> {
+                self.0.set_duty_cycle(duty)
+            }
+        }
+        impl SetFrequency for UpTo1k {
+            type Error = ();
+            fn set_frequency(&mut self, hz: u32) -> Result<(), ()> {
+                if hz > 1_000 {
+                    return Err(());
+                }
+                let _ = self.0.set_frequency(hz);
+                Ok(())
+            }
+        }
+
+        let mut buzzer = Buzzer::new(UpTo1k::default(), LoggingDelay::default());
+        let notes = [(440, 50, 10), (2_000, 50, 10), (880, 50, 10)];
+        assert_eq!(
+            buzzer.melody_dyn(&notes),
+            Err(RejectedFrequency { hz: 2_000 })
+        );
+        // Stopped at the rejected note, silent and with no cached pitch.
+        assert_eq!(buzzer.delay.waits_ms, [10]);
+        assert_eq!(buzzer.pwm.0.duties.last(), Some(&0));
+        assert_eq!(buzzer.frequency_hz(), None);
+    }
+
+    #[test]
+    fn test_melody_accepts_slices_and_generated_notes() {
+        const NOTES: &[(u32, u8, u32)] = &[(440, 50, 100), (880, 50, 50)];
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), LoggingDelay::default());
+        buzzer.melody(NOTES.iter().copied()).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [440, 880]);
+        assert_eq!(buzzer.delay.waits_ms, [100, 50]);
+
+        // Major scale from A4, generated from semitone steps without a buffer.
+        let scale = [0, 2, 4, 5, 7, 9, 11, 12].into_iter().map(|semitones| {
+            let hz = 440.0 * math::exp2(semitones as f32 / 12.0);
+            (math::round_u32(hz), 50, 20)
+        });
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), LoggingDelay::default());
+        buzzer.melody(scale).unwrap();
+        assert_eq!(
+            buzzer.pwm.frequencies,
+            [440, 494, 554, 587, 659, 740, 831, 880]
+        );
+    }
+
+    #[test]
+    fn test_melody_with_tick_ticks_once_per_note() {
+        const NOTES: &[(u32, u8, u32)] = &[(440, 50, 100), (0, 0, 50), (880, 50, 1_500)];
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        let mut ticks = 0;
+        buzzer
+            .melody_with_tick(NOTES.iter().copied(), &mut || ticks += 1)
+            .unwrap();
+        assert_eq!(ticks, NOTES.len());
+        assert_eq!(buzzer.pwm.frequencies, [440, 880]);
+        assert_eq!(buzzer.delay.elapsed_ms(), 1_650);
+    }
+
+    #[test]
+    fn test_tone_until_checks_stop_every_millisecond() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        let mut polls = 0;
+        let stopped = buzzer
+            .tone_until(440, 50, 100, || {
+                polls += 1;
+                polls == 30
+            })
+            .unwrap();
+        assert!(stopped);
+        assert_eq!(buzzer.delay.elapsed_ms(), 29);
+        assert_eq!(buzzer.pwm.duties, [0, 50, 0]);
+
+        assert_eq!(buzzer.tone_until(440, 50, 20, || false), Ok(false));
+        assert_eq!(buzzer.delay.elapsed_ms(), 49);
+    }
+
+    #[test]
+    fn test_every_player_ticks_before_each_note() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        let mut ticks = 0;
+        let mut tick = || ticks += 1;
+
+        buzzer
+            .melody_dyn_with_tick(&[(440, 50, 10), (0, 0, 10)], &mut tick)
+            .unwrap();
+        buzzer
+            .sweep_with_tick(400, 800, 50, 30, SweepSteps::LinearCount(3), &mut tick)
+            .unwrap();
+        let rle = [2, 0xB8, 0x01, 50, 10, 0, 0, 0xB8, 0x01, 50, 10, 0];
+        buzzer.play_rle_with_tick(&rle, &mut tick).unwrap();
+        // Three beeps and the two gaps between them.
+        buzzer.beep_count_with_tick(3, 10, &mut tick).unwrap();
+        // Dot, gap, dash.
+        buzzer.morse_with_tick(b"a", 1_000, 10, &mut tick).unwrap();
+        assert_eq!(ticks, 2 + 3 + 2 + 5 + 3);
+
+        let mut ticks = 0;
+        let mut output = RecordingPwm::default();
+        let notes = [(440, 50, 10); 4];
+        play_melody_dyn_with_tick(
+            &mut output,
+            &mut NopDelay::new(),
+            &notes,
+            0,
+            1_000,
+            &mut None,
+            &mut || ticks += 1,
+        )
+        .unwrap();
+        assert_eq!(ticks, 4);
+    }
+
+    #[test]
+    fn test_play_scale_rises_every_step() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), LoggingDelay::default());
+        buzzer.play_scale(Note::A4, 13, 30).unwrap();
+        let hz = &buzzer.pwm.frequencies;
+        assert_eq!(hz.len(), 13);
+        assert!(hz.windows(2).all(|pair| pair[0] < pair[1]), "{hz:?}");
+        assert_eq!((hz[0], hz[12]), (440, 880));
+        assert_eq!(buzzer.delay.waits_ms, [30; 13]);
+    }
+
+    #[test]
+    fn test_enable_pin_brackets_each_tone_with_settle_delay() {
+        use embedded_hal_mock::eh1::digital::{Mock as PinMock, State, Transaction as PinTxn};
+
+        let expectations = [
+            PinTxn::set(State::Low),
+            PinTxn::set(State::High),
+            PinTxn::set(State::Low),
+            PinTxn::set(State::High),
+            PinTxn::set(State::Low),
+        ];
+        let mut buzzer = Buzzer::with_enable_pin(
+            RecordingPwm::default(),
+            LoggingDelay::default(),
+            PinMock::new(&expectations),
+            5,
+        );
+        buzzer.tone(440, 50, 100).unwrap();
+        buzzer.tone(880, 50, 60).unwrap();
+
+        let (pwm, delay, mut pin) = buzzer.release_with_enable_pin();
+        pin.done();
+        // Each separate tone powers the amp up and waits 5 ms to settle.
+        assert_eq!(delay.waits_ms, [5, 100, 5, 60]);
+        assert_eq!(pwm.duties, [0, 50, 0, 50, 0]);
+    }
+
+    #[test]
+    fn test_enable_pin_settles_once_per_melody() {
+        use embedded_hal_mock::eh1::digital::{Mock as PinMock, State, Transaction as PinTxn};
+
+        const NOTES: [(u32, u8, u32); 4] =
+            [(440, 50, 100), (0, 50, 40), (880, 50, 60), (660, 50, 30)];
+        let expectations = [
+            PinTxn::set(State::Low),
+            PinTxn::set(State::High),
+            PinTxn::set(State::Low),
+        ];
+        let mut gated = Buzzer::with_enable_pin(
+            RecordingPwm::default(),
+            LoggingDelay::default(),
+            PinMock::new(&expectations),
+            5,
+        );
+        gated.melody(NOTES).unwrap();
+        let mut plain = Buzzer::new(RecordingPwm::default(), LoggingDelay::default());
+        plain.melody(NOTES).unwrap();
+
+        let (_, gated_delay, mut pin) = gated.release_with_enable_pin();
+        pin.done();
+        // Only the first note waits for the amp; the notes keep their lengths.
+        assert_eq!(gated_delay.waits_ms[0], 5);
+        assert_eq!(gated_delay.waits_ms[1..], plain.release().1.waits_ms);
+    }
+
+    #[test]
+    fn test_tone_continue_skips_zero_duty_between_notes() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), NopDelay::new());
+        buzzer.tone_continue(440, 50, 10).unwrap();
+        buzzer.tone_continue(660, 60, 10).unwrap();
+        buzzer.tone(880, 70, 10).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [440, 660, 880]);
+        assert_eq!(buzzer.pwm.duties, [0, 50, 60, 70, 0]);
+
+        buzzer.tone_continue(440, 50, 10).unwrap();
+        buzzer.stop_tone();
+        assert_eq!(buzzer.pwm.duties[5..], [50, 0]);
+    }
+
+    #[test]
+    fn test_start_tone_leaves_tone_on_without_delay() {
+        let mut buzzer = Buzzer::new(RecordingPwm::default(), LoggingDelay::default());
+        buzzer.start_tone(1_000, 40).unwrap();
+        assert_eq!(buzzer.pwm.frequencies, [1_000]);
+        assert_eq!(buzzer.pwm.duties, [0, 40]);
+        assert!(buzzer.delay.waits_ms.is_empty());
+
+        buzzer.stop_tone();
+        assert_eq!(buzzer.pwm.duties, [0, 40, 0]);
+    }
+
+    #[test]
+    fn test_validate_melody_flags_bad_duty_and_duration() {
+        const GOOD: &[(u32, u8, u32)] = &[(440, 0, 1), (880, 100, 500), (0, 50, 10)];
+        const _: () = assert!(validate_melody(GOOD));
+        assert!(validate_melody(&[]));
+        assert!(!validate_melody(&[(440, 50, 100), (440, 101, 100)]));
+        assert!(!validate_melody(&[(440, 50, 0)]));
     }
 }
@@ -0,0 +1,138 @@
+//! Ring of `N` LEDs used as a compass arrow towards the target.
+
+use crate::nav::Bearing;
+
+/// `N` LEDs in a circle, lighting the one that points at the target.
+///
+/// LED 0 is straight ahead and indices run clockwise, so with eight LEDs
+/// index 2 is to the right and index 4 behind. [`CompassRing::point`] takes
+/// the bearing relative to the user's heading, e.g. [`Steering::bearing_deg`]
+/// minus the heading.
+///
+/// The ring only decides what to show. For on/off LEDs on a
+/// [`Sr595`](super::shift_register::Sr595), write [`CompassRing::byte`];
+/// for dimmable LEDs such as [`PwmLed`](super::led::PwmLed), use
+/// [`CompassRing::brightness`], which can also light both neighbours dimly
+/// to show the bearing between two LEDs.
+///
+/// [`Steering::bearing_deg`]: crate::guidance::Steering::bearing_deg
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompassRing<const N: usize> {
+    neighbor_percent: u8,
+    lit: Option<usize>,
+}
+
+impl<const N: usize> CompassRing<N> {
+    /// Create a ring with every LED off and no neighbour dimming.
+    pub const fn new() -> Self {
+        Self {
+            neighbor_percent: 0,
+            lit: None,
+        }
+    }
+
+    /// Light the two LEDs either side of the pointer at `percent` in
+    /// [`CompassRing::brightness`].
+    pub const fn with_neighbor_percent(mut self, percent: u8) -> Self {
+        self.neighbor_percent = percent;
+        self
+    }
+
+    /// Point at `relative_bearing_deg` (any angle, clockwise from ahead)
+    /// and return the index of the LED nearest to it.
+    ///
+    /// Bearings exactly between two LEDs go to the clockwise one.
+    pub fn point(&mut self, relative_bearing_deg: f32) -> usize {
+        let index = led_for_bearing(relative_bearing_deg, N);
+        self.lit = (N > 0).then_some(index);
+        index
+    }
+
+    /// Turn every LED off.
+    pub fn clear(&mut self) {
+        self.lit = None;
+    }
+
+    /// Index of the LED pointing at the target, or `None` when cleared.
+    pub fn lit(&self) -> Option<usize> {
+        self.lit
+    }
+
+    /// Brightness of each LED in percent: 100 for the pointer, the
+    /// neighbour percentage either side of it and 0 elsewhere.
+    pub fn brightness(&self) -> [u8; N] {
+        let mut levels = [0; N];
+        if let Some(index) = self.lit {
+            levels[(index + 1) % N] = self.neighbor_percent;
+            levels[(index + N - 1) % N] = self.neighbor_percent;
+            levels[index] = 100;
+        }
+        levels
+    }
+
+    /// Output byte for up to eight on/off LEDs on a shift register, with
+    /// LED `i` on bit `i` (QA upwards). Neighbours are not shown.
+    pub fn byte(&self) -> u8 {
+        match self.lit {
+            Some(index) if index < 8 => 1 << index,
+            _ => 0,
+        }
+    }
+}
+
+impl<const N: usize> Default for CompassRing<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Index of the LED nearest to `relative_bearing_deg` on a ring of `n`,
+/// LED 0 being straight ahead; 0 if `n` is 0.
+pub fn led_for_bearing(relative_bearing_deg: f32, n: usize) -> usize {
+    if n == 0 {
+        return 0;
+    }
+    let deg = Bearing::new(relative_bearing_deg).deg();
+    (deg * n as f32 / 360.0 + 0.5) as usize % n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinal_bearings_pick_matching_led() {
+        let mut ring = CompassRing::<8>::new();
+        let picks = [
+            0.0, 45.0, 90.0, 135.0, 180.0, 225.0, 270.0, 315.0, 360.0, -90.0,
+        ]
+        .map(|deg| ring.point(deg));
+        assert_eq!(picks, [0, 1, 2, 3, 4, 5, 6, 7, 0, 6]);
+
+        // Nearest LED, with exact halfway points going clockwise.
+        assert_eq!(led_for_bearing(22.0, 8), 0);
+        assert_eq!(led_for_bearing(22.5, 8), 1);
+        assert_eq!(led_for_bearing(350.0, 8), 0);
+        assert_eq!(led_for_bearing(-10.0, 4), 0);
+        assert_eq!(led_for_bearing(100.0, 4), 1);
+    }
+
+    #[test]
+    fn test_brightness_and_byte_show_pointer_and_neighbours() {
+        let mut ring = CompassRing::<8>::new().with_neighbor_percent(20);
+        assert_eq!(ring.brightness(), [0; 8]);
+        assert_eq!(ring.byte(), 0);
+
+        ring.point(0.0);
+        assert_eq!(ring.brightness(), [100, 20, 0, 0, 0, 0, 0, 20]);
+        assert_eq!(ring.byte(), 0b0000_0001);
+
+        ring.point(270.0);
+        assert_eq!(ring.brightness(), [0, 0, 0, 0, 0, 20, 100, 20]);
+        assert_eq!(ring.byte(), 0b0100_0000);
+
+        ring.clear();
+        assert_eq!(ring.lit(), None);
+        assert_eq!(ring.brightness(), [0; 8]);
+    }
+}
@@ -0,0 +1,111 @@
+//! Two-voice buzzer driver for playing simple chords on two PWM channels.
+
+use embedded_hal::{delay::DelayNs, pwm::SetDutyCycle};
+
+use super::{buzzer::SetFrequency, util::duty_from_percent};
+
+/// Duty used for each voice of a chord, in percent.
+///
+/// 50% gives the loudest square wave on a piezo transducer.
+const CHORD_DUTY_PERCENT: u8 = 50;
+
+/// A voice of [`DualBuzzer::chord`] rejected its frequency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChordError<EA, EB> {
+    /// Voice `a` failed.
+    A(EA),
+    /// Voice `b` failed.
+    B(EB),
+}
+
+/// Two-channel PWM buzzer.
+///
+/// Owns two independent PWM channels (`A`, `B`) and a delay provider (`D`).
+///
+/// # Hardware constraint
+///
+/// Each voice needs its own period, so `A` and `B` must come from timers that
+/// can be retuned independently. On the ATmega16 the two compare outputs of
+/// Timer1 (OC1A/OC1B) share a single TOP value and therefore a single
+/// frequency; use OC1B on Timer1 for one voice and OC2 on Timer2 for the other.
+pub struct DualBuzzer<A, B, D>
+where
+    A: SetDutyCycle + SetFrequency,
+    B: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+{
+    a: A,
+    b: B,
+    delay: D,
+}
+
+impl<A, B, D> DualBuzzer<A, B, D>
+where
+    A: SetDutyCycle + SetFrequency,
+    B: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+{
+    /// Create a new [`DualBuzzer`], ensuring both channels start at 0% duty.
+    ///
+    /// * `a`     – first voice, implementing [`SetDutyCycle`] + [`SetFrequency`]
+    /// * `b`     – second voice, implementing [`SetDutyCycle`] + [`SetFrequency`]
+    /// * `delay` – delay provider implementing [`DelayNs`]
+    pub fn new(mut a: A, mut b: B, delay: D) -> Self {
+        let _ = a.set_duty_cycle(0);
+        let _ = b.set_duty_cycle(0);
+        Self { a, b, delay }
+    }
+
+    /// Play `freq_a` and `freq_b` together for `duration_ms` milliseconds.
+    ///
+    /// Both voices run at 50% duty and are silenced afterwards. If either
+    /// frequency is rejected nothing is played.
+    pub fn chord(
+        &mut self,
+        freq_a: u32,
+        freq_b: u32,
+        duration_ms: u32,
+    ) -> Result<(), ChordError<<A as SetFrequency>::Error, <B as SetFrequency>::Error>> {
+        self.a.set_frequency(freq_a).map_err(ChordError::A)?;
+        self.b.set_frequency(freq_b).map_err(ChordError::B)?;
+        let duty_a = duty_from_percent(self.a.max_duty_cycle(), CHORD_DUTY_PERCENT);
+        let duty_b = duty_from_percent(self.b.max_duty_cycle(), CHORD_DUTY_PERCENT);
+        let _ = self.a.set_duty_cycle(duty_a);
+        let _ = self.b.set_duty_cycle(duty_b);
+        self.delay.delay_ms(duration_ms);
+        let _ = self.a.set_duty_cycle(0);
+        let _ = self.b.set_duty_cycle(0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::{
+        delay::NopDelay,
+        fixtures::{CoarsePwm, RecordingPwm},
+    };
+
+    #[test]
+    fn test_chord_sets_each_channel_frequency() {
+        let mut buzzer = DualBuzzer::new(
+            RecordingPwm::default(),
+            RecordingPwm::default(),
+            NopDelay::new(),
+        );
+        buzzer.chord(523, 659, 100).unwrap();
+        assert_eq!(buzzer.a.frequencies, [523]);
+        assert_eq!(buzzer.b.frequencies, [659]);
+        assert_eq!(buzzer.a.duties, [0, 50, 0]);
+        assert_eq!(buzzer.b.duties, [0, 50, 0]);
+    }
+
+    #[test]
+    fn test_chord_duty_is_never_rounded_to_silence() {
+        let mut buzzer = DualBuzzer::new(CoarsePwm::new(1), CoarsePwm::new(7), NopDelay::new());
+        buzzer.chord(523, 659, 100).unwrap();
+        assert_eq!(buzzer.a.duties, [0, 1, 0]);
+        assert_eq!(buzzer.b.duties, [0, duty_from_percent(7, 50), 0]);
+    }
+}
@@ -0,0 +1,583 @@
+//! Waypoint and settings storage on top of a byte-addressable EEPROM.
+
+use crate::{
+    guidance::{GuidanceConfig, TurnTones},
+    nav::{Coord, EARTH_RADIUS_M, FIXED_SCALE, join_words, leg_distance_m, within_box},
+};
+
+/// Byte-addressable non-volatile memory.
+pub trait Eeprom {
+    /// Error type returned by a failed access.
+    ///
+    /// Use [`core::convert::Infallible`] if accesses cannot fail.
+    type Error;
+
+    /// Read the byte at `addr`.
+    fn read_byte(&mut self, addr: u16) -> Result<u8, Self::Error>;
+
+    /// Write `value` to `addr`, blocking until the write has completed.
+    fn write_byte(&mut self, addr: u16, value: u8) -> Result<(), Self::Error>;
+}
+
+/// Slot index, waypoint and distance in metres, as found by
+/// [`WaypointStore::nearest`].
+pub type NearestWaypoint = (u8, Coord, f32);
+
+/// Lets a [`WaypointStore`] borrow an EEPROM that is also used for other
+/// records, e.g. the config.
+impl<E: Eeprom + ?Sized> Eeprom for &mut E {
+    type Error = E::Error;
+
+    fn read_byte(&mut self, addr: u16) -> Result<u8, Self::Error> {
+        (**self).read_byte(addr)
+    }
+
+    fn write_byte(&mut self, addr: u16, value: u8) -> Result<(), Self::Error> {
+        (**self).write_byte(addr, value)
+    }
+}
+
+/// Bytes used by a single waypoint slot.
+pub const SLOT_SIZE: u16 = 8;
+
+/// Byte order of the integers in a waypoint slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// Least-significant byte first, as written by this firmware.
+    Little,
+    /// Most-significant byte first.
+    Big,
+}
+
+/// How coordinates are encoded in a [`WaypointStore`] slot.
+///
+/// Blobs produced by other tools may use 10⁻⁶ instead of 10⁻⁷ degrees or
+/// big-endian integers; reading them with the wrong format silently moves
+/// every waypoint by a factor of ten or worse, so set it explicitly with
+/// [`WaypointStore::with_format`] when importing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaypointFormat {
+    /// Stored integers are degrees multiplied by this factor.
+    pub scale: i32,
+    /// Byte order of the stored integers.
+    pub order: ByteOrder,
+}
+
+impl WaypointFormat {
+    /// The firmware's own format: little-endian degrees × 10⁷.
+    pub const NATIVE: Self = Self {
+        scale: FIXED_SCALE,
+        order: ByteOrder::Little,
+    };
+
+    fn decode(&self, raw: [u8; SLOT_SIZE as usize]) -> Coord {
+        match self.order {
+            ByteOrder::Little => Coord::from_fixed_le(raw, self.scale),
+            ByteOrder::Big => Coord::from_fixed_be(raw, self.scale),
+        }
+    }
+
+    fn encode(&self, coord: Coord) -> [u8; SLOT_SIZE as usize] {
+        let (lat, lon) = coord.to_fixed(self.scale);
+        match self.order {
+            ByteOrder::Little => join_words(lat.to_le_bytes(), lon.to_le_bytes()),
+            ByteOrder::Big => join_words(lat.to_be_bytes(), lon.to_be_bytes()),
+        }
+    }
+}
+
+impl Default for WaypointFormat {
+    fn default() -> Self {
+        Self::NATIVE
+    }
+}
+
+/// Error returned by [`WaypointStore`] operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreError<E> {
+    /// The slot index is not below [`WaypointStore::slots`].
+    InvalidSlot,
+    /// The underlying EEPROM access failed.
+    Eeprom(E),
+}
+
+/// Fixed-size waypoint slots on an [`Eeprom`].
+///
+/// # Layout
+///
+/// Slot `n` starts at `base + n * SLOT_SIZE`:
+///
+/// | Offset | Size | Field                                  |
+/// |--------|------|----------------------------------------|
+/// | 0      | 4    | latitude, `i32`, deg × `scale`         |
+/// | 4      | 4    | longitude, `i32`, deg × `scale`        |
+///
+/// `scale` and the byte order come from the store's [`WaypointFormat`],
+/// [`WaypointFormat::NATIVE`] (little-endian, 10⁷) unless set otherwise. A
+/// slot whose bytes are all `0xFF` (the erased EEPROM state) is free.
+pub struct WaypointStore<E>
+where
+    E: Eeprom,
+{
+    eeprom: E,
+    base: u16,
+    slots: u8,
+    format: WaypointFormat,
+}
+
+impl<E> WaypointStore<E>
+where
+    E: Eeprom,
+{
+    /// Create a new [`WaypointStore`].
+    ///
+    /// * `eeprom` – EEPROM implementing [`Eeprom`]
+    /// * `base`   – address of slot 0
+    /// * `slots`  – number of slots reserved from `base` onwards
+    pub fn new(eeprom: E, base: u16, slots: u8) -> Self {
+        Self::with_format(eeprom, base, slots, WaypointFormat::NATIVE)
+    }
+
+    /// Create a [`WaypointStore`] whose slots use `format`, e.g. to read a
+    /// blob written by another tool.
+    pub fn with_format(eeprom: E, base: u16, slots: u8, format: WaypointFormat) -> Self {
+        Self {
+            eeprom,
+            base,
+            slots,
+            format,
+        }
+    }
+
+    /// Encoding used for the slots.
+    pub fn format(&self) -> WaypointFormat {
+        self.format
+    }
+
+    /// Number of slots in this store.
+    pub fn slots(&self) -> u8 {
+        self.slots
+    }
+
+    /// Read the waypoint in `slot`, or `None` if the slot is free.
+    pub fn load(&mut self, slot: u8) -> Result<Option<Coord>, StoreError<E::Error>> {
+        let addr = self.slot_addr(slot)?;
+        let mut raw = [0u8; SLOT_SIZE as usize];
+        for (i, byte) in raw.iter_mut().enumerate() {
+            *byte = self
+                .eeprom
+                .read_byte(addr + i as u16)
+                .map_err(StoreError::Eeprom)?;
+        }
+        if raw.iter().all(|&b| b == 0xFF) {
+            return Ok(None);
+        }
+        Ok(Some(self.format.decode(raw)))
+    }
+
+    /// Store `coord` in `slot`, overwriting whatever was there.
+    pub fn save(&mut self, slot: u8, coord: Coord) -> Result<(), StoreError<E::Error>> {
+        let raw = self.format.encode(coord);
+        self.write_slot(slot, &raw)
+    }
+
+    /// Mark `slot` as free.
+    pub fn erase(&mut self, slot: u8) -> Result<(), StoreError<E::Error>> {
+        self.write_slot(slot, &[0xFF; SLOT_SIZE as usize])
+    }
+
+    /// Index of the first free slot, or `None` if every slot is in use.
+    pub fn next_free_slot(&mut self) -> Result<Option<u8>, StoreError<E::Error>> {
+        for slot in 0..self.slots {
+            if self.load(slot)?.is_none() {
+                return Ok(Some(slot));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Slot index, waypoint and distance in metres of the stored waypoint
+    /// nearest to `current`, or `None` if every slot is free.
+    ///
+    /// Once a candidate is found, later slots outside a [`within_box`] of
+    /// its distance are skipped without computing their distance; the box
+    /// is widened in longitude away from the equator, so no closer slot is
+    /// ever skipped. Ties keep the lower slot.
+    pub fn nearest(
+        &mut self,
+        current: Coord,
+    ) -> Result<Option<NearestWaypoint>, StoreError<E::Error>> {
+        const M_PER_DEG: f32 = EARTH_RADIUS_M * core::f32::consts::PI / 180.0;
+        let mut nearest: Option<NearestWaypoint> = None;
+        for slot in 0..self.slots {
+            let Some(coord) = self.load(slot)? else {
+                continue;
+            };
+            if let Some((_, _, best_m)) = nearest
+                && !within_box(current, best_m / M_PER_DEG, coord)
+            {
+                continue;
+            }
+            let distance = leg_distance_m(current, coord);
+            if nearest.is_none_or(|(_, _, best_m)| distance < best_m) {
+                nearest = Some((slot, coord, distance));
+            }
+        }
+        Ok(nearest)
+    }
+
+    fn slot_addr(&self, slot: u8) -> Result<u16, StoreError<E::Error>> {
+        if slot >= self.slots {
+            return Err(StoreError::InvalidSlot);
+        }
+        Ok(self.base + u16::from(slot) * SLOT_SIZE)
+    }
+
+    fn write_slot(
+        &mut self,
+        slot: u8,
+        raw: &[u8; SLOT_SIZE as usize],
+    ) -> Result<(), StoreError<E::Error>> {
+        let addr = self.slot_addr(slot)?;
+        for (i, &byte) in raw.iter().enumerate() {
+            self.eeprom
+                .write_byte(addr + i as u16, byte)
+                .map_err(StoreError::Eeprom)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bytes used by the layout header; see [`check_layout`].
+pub const HEADER_SIZE: u16 = 2;
+
+/// First header byte, marking an EEPROM formatted by this firmware.
+pub const LAYOUT_MAGIC: u8 = 0xA5;
+
+/// Outcome of [`check_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutStatus {
+    /// The stored layout already matches the firmware.
+    Current,
+    /// The EEPROM was blank and has been initialised.
+    Formatted,
+    /// The EEPROM held data without the header, e.g. from other firmware or
+    /// a factory test pattern, and has been erased and initialised. Nothing
+    /// of it can be trusted as waypoints; warn the user.
+    Unrecognised,
+    /// An older layout `from` was upgraded in place by the migration hook.
+    Migrated { from: u8 },
+    /// Layout `from` could not be upgraded, so the EEPROM was erased. Saved
+    /// waypoints and settings are lost; warn the user.
+    Reset { from: u8 },
+}
+
+/// Check the layout header at address 0 and bring the EEPROM up to
+/// `version`.
+///
+/// The header is [`LAYOUT_MAGIC`] followed by the layout version. When the
+/// stored version is older, `migrate(eeprom, from)` is called to rewrite the
+/// data in place; it returns `Ok(false)` if it cannot upgrade from that
+/// version. Without a header the EEPROM is formatted: silently
+/// ([`LayoutStatus::Formatted`]) if it is blank (`0xFF` throughout the header
+/// and the `len` bytes after it), otherwise as
+/// [`LayoutStatus::Unrecognised`] so the caller can warn. A failed migration
+/// or a version newer than `version` (firmware was downgraded) erases the
+/// EEPROM too. Erasing sets the `len` bytes after the header to `0xFF` so
+/// nothing stale is read as valid data; the header is then rewritten.
+///
+/// Call once at start-up, before any waypoint or config access.
+pub fn check_layout<E, M>(
+    eeprom: &mut E,
+    version: u8,
+    len: u16,
+    mut migrate: M,
+) -> Result<LayoutStatus, E::Error>
+where
+    E: Eeprom,
+    M: FnMut(&mut E, u8) -> Result<bool, E::Error>,
+{
+    let status = if eeprom.read_byte(0)? != LAYOUT_MAGIC {
+        if is_blank(eeprom, HEADER_SIZE + len)? {
+            LayoutStatus::Formatted
+        } else {
+            LayoutStatus::Unrecognised
+        }
+    } else {
+        match eeprom.read_byte(1)? {
+            v if v == version => return Ok(LayoutStatus::Current),
+            v if v < version && migrate(eeprom, v)? => LayoutStatus::Migrated { from: v },
+            v => LayoutStatus::Reset { from: v },
+        }
+    };
+    if !matches!(status, LayoutStatus::Migrated { .. }) {
+        for addr in HEADER_SIZE..HEADER_SIZE + len {
+            eeprom.write_byte(addr, 0xFF)?;
+        }
+    }
+    eeprom.write_byte(0, LAYOUT_MAGIC)?;
+    eeprom.write_byte(1, version)?;
+    Ok(status)
+}
+
+/// `true` if the first `len` bytes are all erased (`0xFF`).
+fn is_blank<E: Eeprom>(eeprom: &mut E, len: u16) -> Result<bool, E::Error> {
+    for addr in 0..len {
+        if eeprom.read_byte(addr)? != 0xFF {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Bytes used by a stored [`GuidanceConfig`]; see [`save_config`].
+pub const CONFIG_SIZE: u16 = 36;
+
+/// Layout version written by [`save_config`].
+pub const CONFIG_VERSION: u8 = 1;
+
+/// Store `config` at `base` so it survives power cycles.
+///
+/// # Layout
+///
+/// All multi-byte fields are little-endian:
+///
+/// | Offset | Size | Field                                  |
+/// |--------|------|----------------------------------------|
+/// | 0      | 1    | layout version, [`CONFIG_VERSION`]     |
+/// | 1      | 4    | `min_arrival_radius_m`, `f32`          |
+/// | 5      | 4    | `arrival_margin_m`, `f32`              |
+/// | 9      | 4    | `pre_alert_m`, `f32`                   |
+/// | 13     | 1    | `arrival_requires_ack`, 0 or 1         |
+/// | 14     | 4    | `dead_band_m`, `f32`                   |
+/// | 18     | 4    | `repeat_interval_ms`, `u32`            |
+/// | 22     | 4    | `min_speed_m_s`, `f32`                 |
+/// | 26     | 1    | `volume_percent`                       |
+/// | 27     | 4    | `turn_tones.base_hz`, `u32`            |
+/// | 31     | 4    | `turn_tones.hz_per_deg`, `f32`         |
+/// | 35     | 1    | checksum, see [`load_config`]          |
+pub fn save_config<E: Eeprom>(
+    eeprom: &mut E,
+    base: u16,
+    config: &GuidanceConfig,
+) -> Result<(), E::Error> {
+    let mut raw = [0u8; CONFIG_SIZE as usize];
+    raw[0] = CONFIG_VERSION;
+    raw[1..5].copy_from_slice(&config.min_arrival_radius_m.to_le_bytes());
+    raw[5..9].copy_from_slice(&config.arrival_margin_m.to_le_bytes());
+    raw[9..13].copy_from_slice(&config.pre_alert_m.to_le_bytes());
+    raw[13] = u8::from(config.arrival_requires_ack);
+    raw[14..18].copy_from_slice(&config.dead_band_m.to_le_bytes());
+    raw[18..22].copy_from_slice(&config.repeat_interval_ms.to_le_bytes());
+    raw[22..26].copy_from_slice(&config.min_speed_m_s.to_le_bytes());
+    raw[26] = config.volume_percent;
+    raw[27..31].copy_from_slice(&config.turn_tones.base_hz.to_le_bytes());
+    raw[31..35].copy_from_slice(&config.turn_tones.hz_per_deg.to_le_bytes());
+    raw[35] = config_checksum(&raw[..35]);
+    for (i, &byte) in raw.iter().enumerate() {
+        eeprom.write_byte(base + i as u16, byte)?;
+    }
+    Ok(())
+}
+
+/// Read the [`GuidanceConfig`] stored at `base` by [`save_config`].
+///
+/// The checksum is the bitwise NOT of the wrapping sum of the preceding
+/// bytes, so an erased (all `0xFF`) or zeroed area never passes. If it fails,
+/// or the layout version is unknown, [`GuidanceConfig::default`] is returned
+/// instead. Only EEPROM access errors are reported. Fields not in the layout,
+/// such as `dr_timeout_ms`, keep their defaults.
+pub fn load_config<E: Eeprom>(eeprom: &mut E, base: u16) -> Result<GuidanceConfig, E::Error> {
+    let mut raw = [0u8; CONFIG_SIZE as usize];
+    for (i, byte) in raw.iter_mut().enumerate() {
+        *byte = eeprom.read_byte(base + i as u16)?;
+    }
+    let checked = CONFIG_SIZE as usize - 1;
+    if raw[0] != CONFIG_VERSION || raw[checked] != config_checksum(&raw[..checked]) {
+        return Ok(GuidanceConfig::default());
+    }
+    let word = |at: usize| [raw[at], raw[at + 1], raw[at + 2], raw[at + 3]];
+    Ok(GuidanceConfig {
+        min_arrival_radius_m: f32::from_le_bytes(word(1)),
+        arrival_margin_m: f32::from_le_bytes(word(5)),
+        pre_alert_m: f32::from_le_bytes(word(9)),
+        arrival_requires_ack: raw[13] != 0,
+        dead_band_m: f32::from_le_bytes(word(14)),
+        repeat_interval_ms: u32::from_le_bytes(word(18)),
+        min_speed_m_s: f32::from_le_bytes(word(22)),
+        volume_percent: raw[26],
+        turn_tones: TurnTones {
+            base_hz: u32::from_le_bytes(word(27)),
+            hz_per_deg: f32::from_le_bytes(word(31)),
+        },
+        ..GuidanceConfig::default()
+    })
+}
+
+fn config_checksum(bytes: &[u8]) -> u8 {
+    !bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{firmware::shared::fixtures::RamEeprom, guidance::AudioPreset};
+
+    #[test]
+    fn test_save_load_round_trip_and_next_free_slot() {
+        let mut store = WaypointStore::new(RamEeprom([0xFF; 64]), 16, 4);
+        assert_eq!(store.next_free_slot(), Ok(Some(0)));
+
+        store.save(0, Coord::new(51.477_93, -0.001_47)).unwrap();
+        let loaded = store.load(0).unwrap().unwrap();
+        assert!(loaded.approx_eq(&Coord::new(51.477_93, -0.001_47), 1e-5));
+        assert_eq!(store.next_free_slot(), Ok(Some(1)));
+
+        store.erase(0).unwrap();
+        assert_eq!(store.load(0), Ok(None));
+        assert_eq!(store.load(4), Err(StoreError::InvalidSlot));
+    }
+
+    #[test]
+    fn test_nearest_skips_free_slots_and_picks_closest() {
+        let mut store = WaypointStore::new(RamEeprom([0xFF; 64]), 0, 6);
+        assert_eq!(store.nearest(Coord::new(51.5, -0.1)), Ok(None));
+
+        store.save(0, Coord::new(51.6, -0.1)).unwrap();
+        store.save(1, Coord::new(48.85, 2.35)).unwrap();
+        store.save(3, Coord::new(51.501, -0.101)).unwrap();
+        store.save(4, Coord::new(51.51, -0.1)).unwrap();
+        store.save(5, Coord::new(51.5, 179.9)).unwrap();
+
+        let (slot, coord, distance) = store.nearest(Coord::new(51.5, -0.1)).unwrap().unwrap();
+        assert_eq!(slot, 3);
+        assert!(coord.approx_eq(&Coord::new(51.501, -0.101), 1e-5));
+        assert!((125.0..135.0).contains(&distance), "{distance}");
+    }
+
+    #[test]
+    fn test_nearest_finds_closer_slot_east_at_high_latitude() {
+        let mut store = WaypointStore::new(RamEeprom([0xFF; 64]), 0, 2);
+        // 1000 m north, then 600 m east, where a degree of longitude is
+        // half as long as one of latitude.
+        store.save(0, Coord::new(60.008_99, 10.0)).unwrap();
+        store.save(1, Coord::new(60.0, 10.010_79)).unwrap();
+
+        let (slot, _, distance) = store.nearest(Coord::new(60.0, 10.0)).unwrap().unwrap();
+        assert_eq!(slot, 1);
+        assert!((590.0..610.0).contains(&distance), "{distance}");
+    }
+
+    #[test]
+    fn test_imported_big_endian_micro_degree_blob() {
+        let mut eeprom = RamEeprom([0xFF; 64]);
+        // 47.6062, -122.3321 as big-endian degrees × 10⁶.
+        eeprom.0[..8].copy_from_slice(&[0x02, 0xD6, 0x69, 0xB8, 0xF8, 0xB5, 0x5C, 0x3C]);
+        let format = WaypointFormat {
+            scale: 1_000_000,
+            order: ByteOrder::Big,
+        };
+        let mut store = WaypointStore::with_format(eeprom, 0, 2, format);
+        let c = store.load(0).unwrap().unwrap();
+        assert!(c.approx_eq(&Coord::new(47.606_2, -122.332_1), 1e-5));
+
+        store.save(1, c).unwrap();
+        assert_eq!(store.eeprom.0[8..10], [0x02, 0xD6]);
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut eeprom = RamEeprom([0xFF; 64]);
+        let config = GuidanceConfig::builder()
+            .pre_alert_m(40.0)
+            .arrival_requires_ack(true)
+            .repeat_interval_ms(3_500)
+            .preset(AudioPreset::Musical)
+            .volume_percent(30)
+            .build();
+        save_config(&mut eeprom, 20, &config).unwrap();
+        assert_eq!(load_config(&mut eeprom, 20), Ok(config));
+    }
+
+    #[test]
+    fn test_corrupt_or_blank_config_falls_back_to_defaults() {
+        let mut eeprom = RamEeprom([0xFF; 64]);
+        assert_eq!(load_config(&mut eeprom, 0), Ok(GuidanceConfig::default()));
+
+        let config = GuidanceConfig::builder().dead_band_m(8.0).build();
+        save_config(&mut eeprom, 0, &config).unwrap();
+        eeprom.0[15] ^= 0x01;
+        assert_eq!(load_config(&mut eeprom, 0), Ok(GuidanceConfig::default()));
+    }
+
+    #[test]
+    fn test_v1_layout_is_migrated_by_v2_firmware() {
+        // v1: header, then a waypoint slot at 2. v2 moved waypoints to 10.
+        let mut eeprom = RamEeprom([0xFF; 64]);
+        let mut v1 = WaypointStore::new(RamEeprom([0xFF; 64]), HEADER_SIZE, 1);
+        v1.save(0, Coord::new(1.5, 2.5)).unwrap();
+        eeprom.0 = v1.eeprom.0;
+        eeprom.0[..2].copy_from_slice(&[LAYOUT_MAGIC, 1]);
+
+        let mut from_versions = Vec::new();
+        let status = check_layout(&mut eeprom, 2, 40, |e, from| {
+            from_versions.push(from);
+            for i in 0..SLOT_SIZE {
+                let byte = e.read_byte(HEADER_SIZE + i)?;
+                e.write_byte(10 + i, byte)?;
+            }
+            Ok(true)
+        });
+        assert_eq!(status, Ok(LayoutStatus::Migrated { from: 1 }));
+        assert_eq!(from_versions, [1]);
+        assert_eq!(eeprom.0[..2], [LAYOUT_MAGIC, 2]);
+        let mut v2 = WaypointStore::new(eeprom, 10, 1);
+        assert_eq!(v2.load(0), Ok(Some(Coord::new(1.5, 2.5))));
+
+        // Already current: the hook is not called again.
+        let status = check_layout(&mut v2.eeprom, 2, 40, |_, _| unreachable!());
+        assert_eq!(status, Ok(LayoutStatus::Current));
+    }
+
+    #[test]
+    fn test_unmigratable_layout_is_erased() {
+        let mut eeprom = RamEeprom([0x00; 64]);
+        eeprom.0[..2].copy_from_slice(&[LAYOUT_MAGIC, 1]);
+        let status = check_layout(&mut eeprom, 2, 8, |_, _| Ok(false));
+        assert_eq!(status, Ok(LayoutStatus::Reset { from: 1 }));
+        assert_eq!(
+            eeprom.0[..12],
+            [
+                LAYOUT_MAGIC,
+                2,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0xFF,
+                0,
+                0
+            ]
+        );
+
+        let mut blank = RamEeprom([0xFF; 64]);
+        let status = check_layout(&mut blank, 2, 8, |_, _| unreachable!());
+        assert_eq!(status, Ok(LayoutStatus::Formatted));
+    }
+
+    #[test]
+    fn test_headerless_data_is_erased_not_migrated() {
+        // A waypoint-looking slot at address 0, but no header.
+        let mut foreign = WaypointStore::new(RamEeprom([0xFF; 64]), 0, 2);
+        foreign.save(1, Coord::new(1.5, 2.5)).unwrap();
+        let mut eeprom = foreign.eeprom;
+
+        let status = check_layout(&mut eeprom, 1, 40, |_, _| unreachable!());
+        assert_eq!(status, Ok(LayoutStatus::Unrecognised));
+        assert_eq!(eeprom.0[..2], [LAYOUT_MAGIC, 1]);
+        assert!(eeprom.0[2..42].iter().all(|&b| b == 0xFF));
+    }
+}
@@ -0,0 +1,137 @@
+//! Progress cues while waiting for the first fix.
+
+use crate::drivers::tick::TickSource;
+
+/// Periodic cue emitted by [`FixAcquisition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcquisitionCue {
+    /// Still searching, within the expected time to first fix.
+    Searching,
+    /// Searching for longer than the timeout; the user may need to move to
+    /// open sky or trigger a [`restart`](super::restart).
+    TimedOut,
+}
+
+impl AcquisitionCue {
+    /// Beep pitch: a neutral tick while searching, a low tone once timed out.
+    pub const fn pitch_hz(self) -> u32 {
+        match self {
+            AcquisitionCue::Searching => 1_000,
+            AcquisitionCue::TimedOut => 400,
+        }
+    }
+}
+
+/// Tracks time to first fix and paces "still searching" cues.
+///
+/// A cold start after long storage can take several minutes, so rather than
+/// staying silent the navigator emits a cue every `interval_ms`, switching to
+/// [`AcquisitionCue::TimedOut`] once `timeout_ms` have passed without a fix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixAcquisition {
+    interval_ms: u32,
+    timeout_ms: u32,
+    started_ms: u32,
+    last_cue_ms: u32,
+    acquired: bool,
+}
+
+impl FixAcquisition {
+    /// Start timing acquisition at `now_ms`.
+    ///
+    /// * `interval_ms` – time between cues
+    /// * `timeout_ms` – time after which cues escalate to [`AcquisitionCue::TimedOut`]
+    pub const fn new(now_ms: u32, interval_ms: u32, timeout_ms: u32) -> Self {
+        Self {
+            interval_ms,
+            timeout_ms,
+            started_ms: now_ms,
+            last_cue_ms: now_ms,
+            acquired: false,
+        }
+    }
+
+    /// Poll with the current fix state; returns the cue to play, if one is due.
+    ///
+    /// Once `has_fix` is seen the tracker goes quiet until [`restart`](Self::restart).
+    pub fn update<T: TickSource>(&mut self, ticks: &T, has_fix: bool) -> Option<AcquisitionCue> {
+        if has_fix {
+            self.acquired = true;
+        }
+        if self.acquired {
+            return None;
+        }
+        let now = ticks.now_ms();
+        if now.wrapping_sub(self.last_cue_ms) < self.interval_ms {
+            return None;
+        }
+        self.last_cue_ms = now;
+        if now.wrapping_sub(self.started_ms) >= self.timeout_ms {
+            Some(AcquisitionCue::TimedOut)
+        } else {
+            Some(AcquisitionCue::Searching)
+        }
+    }
+
+    /// Milliseconds spent searching so far.
+    pub fn elapsed_ms<T: TickSource>(&self, ticks: &T) -> u32 {
+        ticks.now_ms().wrapping_sub(self.started_ms)
+    }
+
+    /// `true` once a fix has been reported.
+    pub fn is_acquired(&self) -> bool {
+        self.acquired
+    }
+
+    /// Start timing a new acquisition at `now_ms`, e.g. after losing the fix.
+    pub fn restart(&mut self, now_ms: u32) {
+        *self = Self::new(now_ms, self.interval_ms, self.timeout_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::ManualTicks;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_cues_escalate_after_timeout() {
+        let start = u32::MAX - 5_000;
+        let ticks = ManualTicks(Cell::new(start));
+        let mut acq = FixAcquisition::new(start, 10_000, 30_000);
+
+        let mut cues = Vec::new();
+        for step in 1..=80 {
+            ticks.0.set(start.wrapping_add(step * 500));
+            if let Some(cue) = acq.update(&ticks, false) {
+                cues.push((step * 500, cue));
+            }
+        }
+        assert_eq!(
+            cues,
+            [
+                (10_000, AcquisitionCue::Searching),
+                (20_000, AcquisitionCue::Searching),
+                (30_000, AcquisitionCue::TimedOut),
+                (40_000, AcquisitionCue::TimedOut),
+            ]
+        );
+        assert_eq!(acq.elapsed_ms(&ticks), 40_000);
+    }
+
+    #[test]
+    fn test_fix_silences_until_restart() {
+        let ticks = ManualTicks(Cell::new(0));
+        let mut acq = FixAcquisition::new(0, 1_000, 5_000);
+        ticks.0.set(1_000);
+        assert_eq!(acq.update(&ticks, true), None);
+        ticks.0.set(9_000);
+        assert_eq!(acq.update(&ticks, false), None);
+        assert!(acq.is_acquired());
+
+        acq.restart(9_000);
+        ticks.0.set(10_000);
+        assert_eq!(acq.update(&ticks, false), Some(AcquisitionCue::Searching));
+    }
+}
@@ -0,0 +1,106 @@
+//! Audible NMEA error feedback, for bench-testing a flaky receiver.
+//!
+//! Enabled by the `debug-tones` feature. Each kind of bad line gets its own
+//! short beep, so the failure can be heard without a serial console: a
+//! steady run of one pitch points at wiring or baud (framing), noise on the
+//! line (checksum), a receiver quirk (field) or a sentence this crate does
+//! not parse (unknown).
+
+use embedded_hal::{delay::DelayNs, digital::OutputPin, pwm::SetDutyCycle};
+
+use super::{NmeaError, Sentence};
+use crate::drivers::buzzer::{Buzzer, SetFrequency};
+
+/// Pitches and length of the cues played by [`NmeaErrorTones`].
+///
+/// A pitch of 0 silences that category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NmeaErrorTones {
+    /// [`NmeaError::Framing`] and [`NmeaError::Overflow`]: not a sentence.
+    pub framing_hz: u32,
+    /// [`NmeaError::Checksum`].
+    pub checksum_hz: u32,
+    /// [`NmeaError::Field`].
+    pub field_hz: u32,
+    /// [`Sentence::Unknown`] and [`NmeaError::SentenceType`].
+    pub unknown_hz: u32,
+    /// Length of each cue.
+    pub duration_ms: u32,
+}
+
+impl Default for NmeaErrorTones {
+    fn default() -> Self {
+        Self {
+            framing_hz: 300,
+            checksum_hz: 600,
+            field_hz: 1_200,
+            unknown_hz: 2_400,
+            duration_ms: 30,
+        }
+    }
+}
+
+impl NmeaErrorTones {
+    /// Pitch for the outcome of parsing one line, or `None` for a sentence
+    /// that parsed cleanly.
+    pub fn frequency_hz(&self, parsed: &Result<Sentence, NmeaError>) -> Option<u32> {
+        let hz = match parsed {
+            Ok(Sentence::Unknown) | Err(NmeaError::SentenceType) => self.unknown_hz,
+            Ok(_) => return None,
+            Err(NmeaError::Framing | NmeaError::Overflow) => self.framing_hz,
+            Err(NmeaError::Checksum) => self.checksum_hz,
+            Err(NmeaError::Field) => self.field_hz,
+        };
+        (hz != 0).then_some(hz)
+    }
+
+    /// Beep the cue for `parsed` on `buzzer`, if it has one.
+    pub fn play<PWM, D, EN>(
+        &self,
+        buzzer: &mut Buzzer<PWM, D, EN>,
+        parsed: &Result<Sentence, NmeaError>,
+    ) -> Result<(), <PWM as SetFrequency>::Error>
+    where
+        PWM: SetDutyCycle + SetFrequency,
+        D: DelayNs,
+        EN: OutputPin,
+    {
+        match self.frequency_hz(parsed) {
+            Some(hz) => buzzer.tone(hz, 50, self.duration_ms),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drivers::gps::parse_any;
+
+    #[test]
+    fn test_each_error_kind_has_its_own_pitch() {
+        let tones = NmeaErrorTones::default();
+        let cues = [
+            parse_any(b"GPGGA,123519"),
+            parse_any(b"$GPGGA,123519*00"),
+            parse_any(b"$GPGGA,123519,4807.038,N,01131.000,E,x,08,0.9,545.4,M,46.9,M,,*0E"),
+            parse_any(b"$GPXTE,A,A,0.67,L,N*6F"),
+        ]
+        .map(|parsed| tones.frequency_hz(&parsed));
+        assert_eq!(cues, [Some(300), Some(600), Some(1_200), Some(2_400)]);
+
+        assert_eq!(tones.frequency_hz(&Err(NmeaError::Overflow)), Some(300));
+        assert_eq!(
+            tones.frequency_hz(&Err(NmeaError::SentenceType)),
+            Some(2_400)
+        );
+        let gga = b"$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        assert_eq!(tones.frequency_hz(&parse_any(gga)), None);
+
+        let quiet = NmeaErrorTones {
+            unknown_hz: 0,
+            ..tones
+        };
+        assert_eq!(quiet.frequency_hz(&Ok(Sentence::Unknown)), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! GPS receiver interface and serial helpers.
+
+use embedded_hal_nb::serial::Read;
+
+use super::tick::TickSource;
+use crate::nav::Coord;
+
+pub mod acquisition;
+#[cfg(feature = "debug-tones")]
+pub mod debug_tones;
+pub mod nmea;
+pub mod pmtk;
+pub mod sentence;
+pub mod sky;
+pub mod ubx;
+
+pub use acquisition::{AcquisitionCue, FixAcquisition};
+#[cfg(feature = "debug-tones")]
+pub use debug_tones::NmeaErrorTones;
+pub use nmea::{Fields, NmeaError, NmeaReader, checksum, validate};
+pub use pmtk::{NmeaOutput, StartMode, restart, send_pmtk, set_nmea_output, set_update_rate_ms};
+pub use sentence::{
+    GgaFix, GsvInfo, RmcFix, SatelliteInfo, Sentence, UtcTime, parse_any, parse_gga, parse_gsv,
+    parse_rmc,
+};
+pub use sky::{SkySummary, SkyView};
+pub use ubx::{PvtFix, UbxError, UbxReader, parse_nav_pvt};
+
+/// Longest NMEA sentence allowed by the standard, including `$` and `\r\n`.
+pub const MAX_SENTENCE_LEN: usize = 82;
+
+/// Source of position fixes, e.g. an NMEA receiver on a serial port.
+pub trait FixSource {
+    /// Return the most recent valid fix, or `None` if the receiver has no fix.
+    fn latest_fix(&mut self) -> Option<Coord>;
+}
+
+/// Reconfigure the baud rate of a serial port at runtime.
+pub trait SetBaudRate {
+    /// Error type returned when the baud rate cannot be applied.
+    ///
+    /// Use [`core::convert::Infallible`] if the operation cannot fail.
+    type Error;
+
+    /// Switch the port to `baud` bits per second.
+    fn set_baud_rate(&mut self, baud: u32) -> Result<(), Self::Error>;
+}
+
+/// Find the baud rate a GPS module is transmitting at.
+///
+/// Tries each of `candidates` in order (e.g. `&[9600, 4800, 38400]`): the port
+/// is switched to that rate and listened to for up to `timeout_ms`. The first
+/// rate at which a complete sentence with a valid checksum arrives is
+/// returned, leaving the port configured at that rate.
+///
+/// Returns `None` if no candidate produced a valid sentence. Pick
+/// `timeout_ms` above the module's update interval (≥ 1 s for 1 Hz modules).
+pub fn autobaud<S, T>(usart: &mut S, candidates: &[u32], ticks: &T, timeout_ms: u32) -> Option<u32>
+where
+    S: Read<u8> + SetBaudRate,
+    T: TickSource,
+{
+    candidates.iter().copied().find(|&baud| {
+        usart.set_baud_rate(baud).is_ok() && receives_valid_sentence(usart, ticks, timeout_ms)
+    })
+}
+
+fn receives_valid_sentence<S, T>(usart: &mut S, ticks: &T, timeout_ms: u32) -> bool
+where
+    S: Read<u8>,
+    T: TickSource,
+{
+    let start = ticks.now_ms();
+    let mut line = [0u8; MAX_SENTENCE_LEN];
+    let mut len = 0;
+    let mut in_sentence = false;
+
+    while ticks.now_ms().wrapping_sub(start) < timeout_ms {
+        let byte = match usart.read() {
+            Ok(byte) => byte,
+            Err(nb::Error::WouldBlock) => continue,
+            // Framing/overrun errors are the norm at the wrong baud; drop the line.
+            Err(nb::Error::Other(_)) => {
+                in_sentence = false;
+                continue;
+            }
+        };
+        match byte {
+            b'$' => {
+                line[0] = byte;
+                len = 1;
+                in_sentence = true;
+            }
+            b'\n' if in_sentence => {
+                if validate(&line[..len]).is_ok() {
+                    return true;
+                }
+                in_sentence = false;
+            }
+            _ if in_sentence => {
+                if len == line.len() {
+                    in_sentence = false;
+                } else {
+                    line[len] = byte;
+                    len += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::SteppingTicks;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+    use embedded_hal_nb::serial::{ErrorKind, ErrorType};
+
+    /// Serial port that only produces readable NMEA at `good_baud`.
+    struct MockGpsSerial {
+        baud: u32,
+        good_baud: u32,
+        pos: usize,
+        tried: Vec<u32>,
+    }
+    impl ErrorType for MockGpsSerial {
+        type Error = ErrorKind;
+    }
+    impl Read<u8> for MockGpsSerial {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            const GOOD: &[u8] = b"XX,4.0*1F\r\n$GPGSA,A,3,,,,,,,,,,,,,2.5,1.3,2.1*34\r\n";
+            const GARBAGE: &[u8] = b"$\xe6\x1c*x\x98\n\x00$G\x7f*0\n";
+            self.pos += 1;
+            if self.baud == self.good_baud {
+                Ok(GOOD[self.pos % GOOD.len()])
+            } else if self.pos.is_multiple_of(7) {
+                Err(nb::Error::Other(ErrorKind::FrameFormat))
+            } else {
+                Ok(GARBAGE[self.pos % GARBAGE.len()])
+            }
+        }
+    }
+    impl SetBaudRate for MockGpsSerial {
+        type Error = Infallible;
+        fn set_baud_rate(&mut self, baud: u32) -> Result<(), Infallible> {
+            self.baud = baud;
+            self.tried.push(baud);
+            Ok(())
+        }
+    }
+
+    fn serial(good_baud: u32) -> MockGpsSerial {
+        MockGpsSerial {
+            baud: 0,
+            good_baud,
+            pos: 0,
+            tried: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_autobaud_finds_the_only_valid_rate() {
+        let mut usart = serial(38_400);
+        let ticks = SteppingTicks(Cell::new(0));
+        let detected = autobaud(&mut usart, &[4_800, 9_600, 38_400], &ticks, 500);
+        assert_eq!(detected, Some(38_400));
+        assert_eq!(usart.tried, [4_800, 9_600, 38_400]);
+    }
+
+    #[test]
+    fn test_autobaud_returns_none_without_valid_sentences() {
+        let mut usart = serial(115_200);
+        let ticks = SteppingTicks(Cell::new(u32::MAX - 100));
+        assert_eq!(autobaud(&mut usart, &[4_800, 9_600], &ticks, 500), None);
+    }
+}
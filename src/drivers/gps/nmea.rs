@@ -0,0 +1,241 @@
+//! NMEA 0183 sentence framing and checksum helpers.
+
+use embedded_hal_nb::serial::Read;
+
+use super::MAX_SENTENCE_LEN;
+
+/// Error produced while validating or parsing an NMEA sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NmeaError {
+    /// The line does not look like `$<body>*HH`.
+    Framing,
+    /// The `*HH` checksum does not match the sentence body.
+    Checksum,
+    /// A field is missing or not a valid number.
+    Field,
+    /// The sentence is valid but not of the type that was asked for.
+    SentenceType,
+    /// The line is longer than the reader's buffer.
+    Overflow,
+}
+
+/// Error returned by [`NmeaReader::read`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError<E> {
+    /// The serial port reported an error; the partial line was dropped.
+    Serial(E),
+    /// A line was dropped: [`NmeaError::Overflow`] if it did not fit the
+    /// buffer, otherwise the reason [`validate`] rejected it.
+    Sentence(NmeaError),
+}
+
+/// Assembles NMEA lines from a serial port, one byte at a time.
+///
+/// `N` is the line capacity including `$` and `\r\n`; the default of
+/// [`MAX_SENTENCE_LEN`] fits any standard sentence, but some receivers send
+/// longer proprietary ones.
+pub struct NmeaReader<R, const N: usize = MAX_SENTENCE_LEN> {
+    serial: R,
+    line: heapless::Vec<u8, N>,
+    in_sentence: bool,
+}
+
+impl<R: Read<u8>, const N: usize> NmeaReader<R, N> {
+    /// Wrap `serial`.
+    pub fn new(serial: R) -> Self {
+        Self {
+            serial,
+            line: heapless::Vec::new(),
+            in_sentence: false,
+        }
+    }
+
+    /// Read available bytes until a line with a valid checksum completes.
+    ///
+    /// Returns the whole line, ready for [`parse_any`](super::parse_any), or
+    /// `WouldBlock` when the port runs dry mid-line; the partial line is kept
+    /// for the next call. After an error, bytes are skipped until the next
+    /// `$`, and a `$` in the middle of a line always starts a new one.
+    pub fn read(&mut self) -> nb::Result<&[u8], ReadError<R::Error>> {
+        loop {
+            let byte = match self.serial.read() {
+                Ok(byte) => byte,
+                Err(nb::Error::WouldBlock) => return Err(nb::Error::WouldBlock),
+                Err(nb::Error::Other(e)) => {
+                    self.in_sentence = false;
+                    return Err(nb::Error::Other(ReadError::Serial(e)));
+                }
+            };
+            if byte == b'$' {
+                self.line.clear();
+                self.in_sentence = true;
+            } else if !self.in_sentence {
+                continue;
+            }
+            if self.line.push(byte).is_err() {
+                self.in_sentence = false;
+                crate::log_warn!("nmea: line over {} bytes dropped", N);
+                return Err(nb::Error::Other(ReadError::Sentence(NmeaError::Overflow)));
+            }
+            if byte == b'\n' {
+                self.in_sentence = false;
+                return match validate(&self.line) {
+                    Ok(_) => Ok(&self.line),
+                    Err(e) => {
+                        crate::log_warn!("nmea: {:?} in {}-byte line", e, self.line.len());
+                        Err(nb::Error::Other(ReadError::Sentence(e)))
+                    }
+                };
+            }
+        }
+    }
+
+    /// Give back the serial port.
+    pub fn release(self) -> R {
+        self.serial
+    }
+}
+
+/// XOR checksum of an NMEA sentence body (the bytes between `$` and `*`).
+pub fn checksum(body: &[u8]) -> u8 {
+    body.iter().fold(0, |acc, &b| acc ^ b)
+}
+
+/// Check the framing and checksum of `line` and return its body.
+///
+/// `line` is a complete sentence such as `$GPGGA,...*47`, optionally followed
+/// by `\r\n`. The returned body excludes the leading `$` and the `*HH` suffix.
+pub fn validate(line: &[u8]) -> Result<&[u8], NmeaError> {
+    let line = line.trim_ascii_end();
+    let rest = line.strip_prefix(b"$").ok_or(NmeaError::Framing)?;
+    let star = rest
+        .iter()
+        .rposition(|&b| b == b'*')
+        .ok_or(NmeaError::Framing)?;
+    let (body, suffix) = (&rest[..star], &rest[star + 1..]);
+    let expected = match suffix {
+        [hi, lo] => (hex_value(*hi)? << 4) | hex_value(*lo)?,
+        _ => return Err(NmeaError::Framing),
+    };
+    if checksum(body) != expected {
+        return Err(NmeaError::Checksum);
+    }
+    Ok(body)
+}
+
+/// Iterator over the comma-separated fields of an NMEA sentence body.
+///
+/// Empty fields (`,,`) are yielded as empty slices, including a trailing one
+/// after a final comma. Iteration stops at `*`, so a body that still carries
+/// its `*HH` checksum can be passed as-is.
+#[derive(Debug, Clone)]
+pub struct Fields<'a> {
+    inner: core::slice::Split<'a, u8, fn(&u8) -> bool>,
+}
+
+impl<'a> Fields<'a> {
+    /// Split `body` (without the leading `$`) into fields.
+    pub fn new(body: &'a [u8]) -> Self {
+        let end = body.iter().position(|&b| b == b'*').unwrap_or(body.len());
+        let is_comma: fn(&u8) -> bool = |&b| b == b',';
+        Self {
+            inner: body[..end].split(is_comma),
+        }
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+fn hex_value(digit: u8) -> Result<u8, NmeaError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        _ => Err(NmeaError::Framing),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use embedded_hal_nb::serial::ErrorType;
+
+    /// Serial port that plays back each chunk in turn, running dry (one
+    /// `WouldBlock`) after every chunk.
+    struct Playback<'a>(Vec<&'a [u8]>);
+    impl ErrorType for Playback<'_> {
+        type Error = Infallible;
+    }
+    impl Read<u8> for Playback<'_> {
+        fn read(&mut self) -> nb::Result<u8, Infallible> {
+            let chunk = self.0.first_mut().ok_or(nb::Error::WouldBlock)?;
+            match chunk.split_first() {
+                Some((&first, rest)) => {
+                    *chunk = rest;
+                    Ok(first)
+                }
+                None => {
+                    self.0.remove(0);
+                    Err(nb::Error::WouldBlock)
+                }
+            }
+        }
+    }
+
+    const GGA: &[u8] = b"$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n";
+
+    #[test]
+    fn test_validate_accepts_good_and_rejects_bad_sentences() {
+        assert_eq!(validate(GGA), Ok(&GGA[1..GGA.len() - 5]));
+        assert_eq!(validate(b"$GPGGA,123519*00"), Err(NmeaError::Checksum));
+        assert_eq!(validate(b"GPGGA,123519*47"), Err(NmeaError::Framing));
+        assert_eq!(validate(b"$GPGGA,123519"), Err(NmeaError::Framing));
+    }
+
+    #[test]
+    fn test_fields_counts_empty_and_trailing_fields() {
+        let body = validate(GGA).unwrap();
+        let fields: Vec<&[u8]> = Fields::new(body).collect();
+        assert_eq!(fields.len(), 15);
+        assert_eq!(fields[0], b"GPGGA");
+        assert_eq!(fields[9], b"545.4");
+        assert_eq!(fields[13], b"");
+        assert_eq!(fields[14], b"");
+
+        assert_eq!(Fields::new(b"GPRMC,,V,*33").count(), 4);
+        assert_eq!(Fields::new(b"").count(), 1);
+    }
+
+    #[test]
+    fn test_reader_rejects_overlong_line_and_recovers_at_next_dollar() {
+        let mut stream = b"$GPTXT,".to_vec();
+        stream.resize(98, b'A');
+        stream.extend_from_slice(b"\r\n");
+        assert_eq!(stream.len(), 100);
+        stream.extend_from_slice(GGA);
+
+        let mut reader: NmeaReader<_> = NmeaReader::new(Playback(vec![&stream]));
+        assert_eq!(
+            reader.read(),
+            Err(nb::Error::Other(ReadError::Sentence(NmeaError::Overflow)))
+        );
+        assert_eq!(reader.read(), Ok(GGA));
+        assert_eq!(reader.read(), Err(nb::Error::WouldBlock));
+    }
+
+    #[test]
+    fn test_reader_keeps_partial_line_across_would_block() {
+        let chunks = vec![&b"\x00\x17"[..], &GGA[..20], &GGA[20..]];
+        let mut reader = NmeaReader::<_, 96>::new(Playback(chunks));
+        assert_eq!(reader.read(), Err(nb::Error::WouldBlock));
+        assert_eq!(reader.read(), Err(nb::Error::WouldBlock));
+        assert_eq!(reader.read(), Ok(GGA));
+    }
+}
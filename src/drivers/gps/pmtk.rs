@@ -0,0 +1,222 @@
+//! PMTK configuration commands for MediaTek (MTK) GPS modules.
+
+use embedded_hal_nb::serial::Write;
+
+use super::nmea::checksum;
+use crate::util::fmt::fmt_u32;
+
+/// Longest command body built by the typed helpers: `PMTK314` with 19
+/// one-digit fields is 45 bytes.
+const MAX_BODY_LEN: usize = 48;
+
+/// Highest rate [`set_nmea_output`] sends for a sentence.
+pub const MAX_NMEA_RATE: u8 = 5;
+
+/// How often each NMEA sentence is emitted, in multiples of the fix interval.
+///
+/// `0` disables the sentence, `1` emits it every fix, `5` every fifth fix.
+/// Values above [`MAX_NMEA_RATE`] are clamped to it before sending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NmeaOutput {
+    /// Geographic position (`GLL`).
+    pub gll: u8,
+    /// Recommended minimum data (`RMC`).
+    pub rmc: u8,
+    /// Course and speed (`VTG`).
+    pub vtg: u8,
+    /// Fix data (`GGA`).
+    pub gga: u8,
+    /// DOP and active satellites (`GSA`).
+    pub gsa: u8,
+    /// Satellites in view (`GSV`).
+    pub gsv: u8,
+}
+
+/// How much receiver state a restart keeps (`PMTK101`..`PMTK104`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartMode {
+    /// Keep time, position, almanac and ephemeris: fastest re-fix.
+    Hot,
+    /// Discard ephemeris; needs about 30 s of clear sky to re-download it.
+    Warm,
+    /// Discard time, position, almanac and ephemeris.
+    Cold,
+    /// Cold start that also resets the module's configuration to defaults.
+    FullCold,
+}
+
+/// Send a PMTK command, framing it as `$<cmd>*HH\r\n`.
+///
+/// `cmd` is the body without `$` or checksum, e.g. `b"PMTK220,200"`. Blocks
+/// until every byte has been written and the port is flushed.
+pub fn send_pmtk<W>(usart: &mut W, cmd: &[u8]) -> Result<(), W::Error>
+where
+    W: Write<u8>,
+{
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let sum = checksum(cmd);
+
+    nb::block!(usart.write(b'$'))?;
+    for &byte in cmd {
+        nb::block!(usart.write(byte))?;
+    }
+    for byte in [
+        b'*',
+        HEX[usize::from(sum >> 4)],
+        HEX[usize::from(sum & 0x0F)],
+        b'\r',
+        b'\n',
+    ] {
+        nb::block!(usart.write(byte))?;
+    }
+    nb::block!(usart.flush())
+}
+
+/// Set the position fix interval in milliseconds (`PMTK220`).
+///
+/// `200` gives 5 Hz updates, `1000` the default 1 Hz. Faster rates may also
+/// need a higher baud rate to fit all enabled sentences.
+pub fn set_update_rate_ms<W>(usart: &mut W, ms: u16) -> Result<(), W::Error>
+where
+    W: Write<u8>,
+{
+    let mut body = Body::new(b"PMTK220");
+    body.field(u32::from(ms));
+    send_pmtk(usart, body.as_bytes())
+}
+
+/// Restart the receiver's fix engine (`PMTK101`..`PMTK104`).
+///
+/// A cold start can help a module that was moved a long way while powered
+/// off and keeps searching with a stale almanac, at the cost of a longer
+/// time to first fix. [`StartMode::FullCold`] also undoes any
+/// [`set_update_rate_ms`] or [`set_nmea_output`] settings.
+pub fn restart<W>(usart: &mut W, mode: StartMode) -> Result<(), W::Error>
+where
+    W: Write<u8>,
+{
+    let cmd: &[u8] = match mode {
+        StartMode::Hot => b"PMTK101",
+        StartMode::Warm => b"PMTK102",
+        StartMode::Cold => b"PMTK103",
+        StartMode::FullCold => b"PMTK104",
+    };
+    send_pmtk(usart, cmd)
+}
+
+/// Select which NMEA sentences the module emits (`PMTK314`).
+pub fn set_nmea_output<W>(usart: &mut W, output: NmeaOutput) -> Result<(), W::Error>
+where
+    W: Write<u8>,
+{
+    let mut body = Body::new(b"PMTK314");
+    let rates = [
+        output.gll, output.rmc, output.vtg, output.gga, output.gsa, output.gsv,
+    ];
+    for rate in rates {
+        body.field(u32::from(rate.min(MAX_NMEA_RATE)));
+    }
+    // Remaining 13 fields are reserved/unused sentence types.
+    for _ in 0..13 {
+        body.field(0);
+    }
+    send_pmtk(usart, body.as_bytes())
+}
+
+/// Fixed-capacity command body builder.
+struct Body {
+    buf: [u8; MAX_BODY_LEN],
+    len: usize,
+}
+
+impl Body {
+    fn new(name: &[u8]) -> Self {
+        let mut body = Self {
+            buf: [0; MAX_BODY_LEN],
+            len: 0,
+        };
+        body.push(name);
+        body
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    /// Append `,<value>` in decimal.
+    fn field(&mut self, value: u32) {
+        self.push(b",");
+        self.len += fmt_u32(&mut self.buf[self.len..], value);
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::eh1::serial::{Mock as SerialMock, Transaction as SerialTxn};
+
+    #[test]
+    fn test_update_rate_command_has_correct_checksum() {
+        let expectations = [
+            SerialTxn::write_many(b"$PMTK220,200*2C\r\n"),
+            SerialTxn::flush(),
+        ];
+        let mut usart = SerialMock::new(&expectations);
+        set_update_rate_ms(&mut usart, 200).unwrap();
+        usart.done();
+    }
+
+    #[test]
+    fn test_nmea_output_rmc_and_gga_only() {
+        let output = NmeaOutput {
+            rmc: 1,
+            gga: 1,
+            ..NmeaOutput::default()
+        };
+        let expectations = [
+            SerialTxn::write_many(b"$PMTK314,0,1,0,1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0*28\r\n"),
+            SerialTxn::flush(),
+        ];
+        let mut usart = SerialMock::new(&expectations);
+        set_nmea_output(&mut usart, output).unwrap();
+        usart.done();
+    }
+
+    #[test]
+    fn test_nmea_output_clamps_rates_to_five() {
+        let output = NmeaOutput {
+            gll: 10,
+            rmc: 10,
+            vtg: 10,
+            gga: 10,
+            gsa: 10,
+            gsv: u8::MAX,
+        };
+        let expectations = [
+            SerialTxn::write_many(b"$PMTK314,5,5,5,5,5,5,0,0,0,0,0,0,0,0,0,0,0,0,0*28\r\n"),
+            SerialTxn::flush(),
+        ];
+        let mut usart = SerialMock::new(&expectations);
+        set_nmea_output(&mut usart, output).unwrap();
+        usart.done();
+    }
+
+    #[test]
+    fn test_restart_commands() {
+        let expectations = [
+            SerialTxn::write_many(b"$PMTK103*30\r\n"),
+            SerialTxn::flush(),
+            SerialTxn::write_many(b"$PMTK102*31\r\n"),
+            SerialTxn::flush(),
+        ];
+        let mut usart = SerialMock::new(&expectations);
+        restart(&mut usart, StartMode::Cold).unwrap();
+        restart(&mut usart, StartMode::Warm).unwrap();
+        usart.done();
+    }
+}
@@ -0,0 +1,540 @@
+//! Typed parsers for the NMEA sentences used by the navigator.
+//!
+//! Numbers are parsed with integer arithmetic only (no `f32::from_str`), which
+//! keeps the decimal-to-float tables out of the AVR image.
+
+use super::nmea::{Fields, NmeaError, validate};
+use crate::nav::{Coord, FixedCoord};
+
+/// UTC time of day from an `hhmmss.sss` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UtcTime {
+    /// Hour, `0..24`.
+    pub hour: u8,
+    /// Minute, `0..60`.
+    pub minute: u8,
+    /// Second, `0..=60` (leap second).
+    pub second: u8,
+    /// Fractional second in milliseconds.
+    pub millis: u16,
+}
+
+impl UtcTime {
+    /// Milliseconds since midnight.
+    pub fn millis_of_day(&self) -> u32 {
+        ((u32::from(self.hour) * 60 + u32::from(self.minute)) * 60 + u32::from(self.second)) * 1_000
+            + u32::from(self.millis)
+    }
+}
+
+/// Position fix from a `GGA` sentence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GgaFix {
+    /// Time of the fix.
+    pub time: Option<UtcTime>,
+    /// Position, `None` while the receiver has no fix.
+    pub position: Option<Coord>,
+    /// Fix quality: 0 = invalid, 1 = GPS, 2 = DGPS, 4/5 = RTK, 6 = estimated.
+    pub fix_quality: u8,
+    /// Number of satellites used in the fix.
+    pub satellites: u8,
+    /// Horizontal dilution of precision.
+    pub hdop: Option<f32>,
+    /// Altitude above mean sea level in metres.
+    pub altitude_m: Option<f32>,
+}
+
+/// Recommended minimum data from an `RMC` sentence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RmcFix {
+    /// Time of the fix.
+    pub time: Option<UtcTime>,
+    /// Raw status byte: `b'A'` (active) or `b'V'` (void).
+    pub status: u8,
+    /// Position as reported by the receiver.
+    ///
+    /// Void fixes often repeat the last known (stale) coordinates; use
+    /// [`RmcFix::valid_position`] for anything that steers the user.
+    pub position: Option<Coord>,
+    /// Speed over ground in knots.
+    pub speed_knots: Option<f32>,
+    /// Course over ground in degrees from true north.
+    pub course_deg: Option<f32>,
+}
+
+impl RmcFix {
+    /// `true` if the receiver marked this fix active (`A`).
+    pub fn is_valid(&self) -> bool {
+        self.status == b'A'
+    }
+
+    /// Position, or `None` for void fixes regardless of the coordinates sent.
+    pub fn valid_position(&self) -> Option<Coord> {
+        self.position.filter(|_| self.is_valid())
+    }
+}
+
+/// One satellite entry of a `GSV` sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SatelliteInfo {
+    /// Satellite PRN number.
+    pub prn: u8,
+    /// Elevation in degrees, `0..=90`.
+    pub elevation_deg: Option<u8>,
+    /// Azimuth in degrees from true north, `0..360`.
+    pub azimuth_deg: Option<u16>,
+    /// Signal-to-noise ratio in dB-Hz, `None` when not tracking.
+    pub snr_db: Option<u8>,
+}
+
+/// One message of a `GSV` (satellites in view) set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GsvInfo {
+    /// Talker ID, e.g. `*b"GP"` for GPS or `*b"GL"` for GLONASS.
+    pub talker: [u8; 2],
+    /// Number of messages in this set.
+    pub message_count: u8,
+    /// Index of this message within the set, starting at 1.
+    pub message_number: u8,
+    /// Total satellites in view for this constellation.
+    pub satellites_in_view: u8,
+    /// Up to four satellites described by this message.
+    pub satellites: [Option<SatelliteInfo>; 4],
+}
+
+/// Any sentence understood by [`parse_any`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sentence {
+    /// Fix data.
+    Gga(GgaFix),
+    /// Recommended minimum data.
+    Rmc(RmcFix),
+    /// Satellites in view.
+    Gsv(GsvInfo),
+    /// A well-formed sentence of a type this crate does not parse.
+    Unknown,
+}
+
+impl Sentence {
+    /// Position usable for guidance, or `None` if this sentence carries no
+    /// fix: GGA with fix quality 0, void RMC, or any other sentence type.
+    ///
+    /// Treat `None` from a fix sentence as "no fix" rather than falling back
+    /// to its raw coordinates.
+    pub fn valid_position(&self) -> Option<Coord> {
+        match self {
+            Sentence::Gga(fix) if fix.fix_quality != 0 => fix.position,
+            Sentence::Rmc(fix) => fix.valid_position(),
+            _ => None,
+        }
+    }
+}
+
+/// Same as [`parse_any`].
+impl TryFrom<&[u8]> for Sentence {
+    type Error = NmeaError;
+
+    fn try_from(line: &[u8]) -> Result<Self, NmeaError> {
+        parse_any(line)
+    }
+}
+
+/// Same as [`parse_gga`].
+impl TryFrom<&[u8]> for GgaFix {
+    type Error = NmeaError;
+
+    fn try_from(line: &[u8]) -> Result<Self, NmeaError> {
+        parse_gga(line)
+    }
+}
+
+/// Same as [`parse_rmc`].
+impl TryFrom<&[u8]> for RmcFix {
+    type Error = NmeaError;
+
+    fn try_from(line: &[u8]) -> Result<Self, NmeaError> {
+        parse_rmc(line)
+    }
+}
+
+/// Same as [`parse_gsv`].
+impl TryFrom<&[u8]> for GsvInfo {
+    type Error = NmeaError;
+
+    fn try_from(line: &[u8]) -> Result<Self, NmeaError> {
+        parse_gsv(line)
+    }
+}
+
+/// Validate `line` and parse it into the matching [`Sentence`] variant.
+///
+/// Sentences with a valid checksum but an unsupported type (`GSA`, `VTG`,
+/// `PMTK` replies, ...) return `Ok(Sentence::Unknown)` so a streaming reader
+/// can skip them without treating them as errors.
+pub fn parse_any(line: &[u8]) -> Result<Sentence, NmeaError> {
+    let body = validate(line)?;
+    let mut fields = Fields::new(body);
+    let kind = fields.next().ok_or(NmeaError::Field)?;
+    match sentence_type(kind) {
+        Some(b"GGA") => gga_fields(fields).map(Sentence::Gga),
+        Some(b"RMC") => rmc_fields(fields).map(Sentence::Rmc),
+        Some(b"GSV") => gsv_fields(talker(kind), fields).map(Sentence::Gsv),
+        _ => Ok(Sentence::Unknown),
+    }
+}
+
+/// Validate and parse a `GGA` sentence from any talker (`GP`, `GN`, ...).
+pub fn parse_gga(line: &[u8]) -> Result<GgaFix, NmeaError> {
+    let fields = typed_fields(line, b"GGA")?;
+    gga_fields(fields)
+}
+
+/// Validate and parse an `RMC` sentence from any talker.
+pub fn parse_rmc(line: &[u8]) -> Result<RmcFix, NmeaError> {
+    let fields = typed_fields(line, b"RMC")?;
+    rmc_fields(fields)
+}
+
+/// Validate and parse a `GSV` sentence from any talker.
+pub fn parse_gsv(line: &[u8]) -> Result<GsvInfo, NmeaError> {
+    let body = validate(line)?;
+    let mut fields = Fields::new(body);
+    let kind = fields.next().ok_or(NmeaError::Field)?;
+    if sentence_type(kind) != Some(b"GSV") {
+        return Err(NmeaError::SentenceType);
+    }
+    gsv_fields(talker(kind), fields)
+}
+
+fn typed_fields<'a>(line: &'a [u8], expected: &[u8; 3]) -> Result<Fields<'a>, NmeaError> {
+    let body = validate(line)?;
+    let mut fields = Fields::new(body);
+    let kind = fields.next().ok_or(NmeaError::Field)?;
+    if sentence_type(kind) != Some(expected) {
+        return Err(NmeaError::SentenceType);
+    }
+    Ok(fields)
+}
+
+/// `GGA` from `GPGGA`, or `None` for proprietary/short addresses.
+fn sentence_type(address: &[u8]) -> Option<&[u8; 3]> {
+    match address {
+        [_, _, rest @ ..] if address[0] != b'P' => rest.try_into().ok(),
+        _ => None,
+    }
+}
+
+fn talker(address: &[u8]) -> [u8; 2] {
+    [address[0], address[1]]
+}
+
+fn gga_fields(mut f: Fields<'_>) -> Result<GgaFix, NmeaError> {
+    let time = utc_time(next(&mut f)?)?;
+    let lat = next(&mut f)?;
+    let ns = next(&mut f)?;
+    let lon = next(&mut f)?;
+    let ew = next(&mut f)?;
+    let fix_quality = integer(next(&mut f)?)?.unwrap_or(0) as u8;
+    let satellites = integer(next(&mut f)?)?.unwrap_or(0) as u8;
+    let hdop = decimal(next(&mut f)?)?;
+    let altitude_m = decimal(next(&mut f)?)?;
+    Ok(GgaFix {
+        time,
+        position: position(lat, ns, lon, ew)?,
+        fix_quality,
+        satellites,
+        hdop,
+        altitude_m,
+    })
+}
+
+fn rmc_fields(mut f: Fields<'_>) -> Result<RmcFix, NmeaError> {
+    let time = utc_time(next(&mut f)?)?;
+    let status = match next(&mut f)? {
+        [status] => *status,
+        _ => return Err(NmeaError::Field),
+    };
+    let lat = next(&mut f)?;
+    let ns = next(&mut f)?;
+    let lon = next(&mut f)?;
+    let ew = next(&mut f)?;
+    let speed_knots = decimal(next(&mut f)?)?;
+    let course_deg = decimal(next(&mut f)?)?;
+    Ok(RmcFix {
+        time,
+        status,
+        position: position(lat, ns, lon, ew)?,
+        speed_knots,
+        course_deg,
+    })
+}
+
+fn gsv_fields(talker: [u8; 2], mut f: Fields<'_>) -> Result<GsvInfo, NmeaError> {
+    let message_count = required_u8(next(&mut f)?)?;
+    let message_number = required_u8(next(&mut f)?)?;
+    let satellites_in_view = required_u8(next(&mut f)?)?;
+    let mut satellites = [None; 4];
+    for slot in satellites.iter_mut() {
+        let Some(prn) = f.next() else { break };
+        let elevation = f.next().unwrap_or(b"");
+        let azimuth = f.next().unwrap_or(b"");
+        let snr = f.next().unwrap_or(b"");
+        if prn.is_empty() {
+            continue;
+        }
+        *slot = Some(SatelliteInfo {
+            prn: required_u8(prn)?,
+            elevation_deg: integer(elevation)?.map(|v| v as u8),
+            azimuth_deg: integer(azimuth)?.map(|v| v as u16),
+            snr_db: integer(snr)?.map(|v| v as u8),
+        });
+    }
+    Ok(GsvInfo {
+        talker,
+        message_count,
+        message_number,
+        satellites_in_view,
+        satellites,
+    })
+}
+
+fn next<'a>(fields: &mut Fields<'a>) -> Result<&'a [u8], NmeaError> {
+    fields.next().ok_or(NmeaError::Field)
+}
+
+/// Parse an unsigned decimal integer; empty fields are `None`.
+fn integer(field: &[u8]) -> Result<Option<u32>, NmeaError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    field
+        .iter()
+        .try_fold(0u32, |acc, &b| {
+            let digit = b.checked_sub(b'0').filter(|d| *d <= 9)?;
+            acc.checked_mul(10)?.checked_add(u32::from(digit))
+        })
+        .map(Some)
+        .ok_or(NmeaError::Field)
+}
+
+fn required_u8(field: &[u8]) -> Result<u8, NmeaError> {
+    integer(field)?
+        .and_then(|v| u8::try_from(v).ok())
+        .ok_or(NmeaError::Field)
+}
+
+/// Most fractional digits [`fixed`] keeps. Receivers send at most six;
+/// nine keeps `10^scale` and the angle maths below well inside `i64`.
+const MAX_FRACTION_DIGITS: u32 = 9;
+
+/// Parse `[-]digits[.digits]` into `(mantissa, fractional digits)`.
+///
+/// Fractional digits beyond [`MAX_FRACTION_DIGITS`] are checked but ignored,
+/// so a run of zeros from a noisy UART cannot overflow the scale.
+fn fixed(field: &[u8]) -> Result<Option<(i64, u32)>, NmeaError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    let (negative, digits) = match field.strip_prefix(b"-") {
+        Some(rest) => (true, rest),
+        None => (false, field),
+    };
+    let mut mantissa = 0i64;
+    let mut scale = None;
+    for &b in digits {
+        match b {
+            b'0'..=b'9' if scale == Some(MAX_FRACTION_DIGITS) => {}
+            b'0'..=b'9' if mantissa < i64::MAX / 100 => {
+                mantissa = mantissa * 10 + i64::from(b - b'0');
+                scale = scale.map(|s: u32| s + 1);
+            }
+            b'.' if scale.is_none() => scale = Some(0),
+            _ => return Err(NmeaError::Field),
+        }
+    }
+    let mantissa = if negative { -mantissa } else { mantissa };
+    Ok(Some((mantissa, scale.unwrap_or(0))))
+}
+
+fn decimal(field: &[u8]) -> Result<Option<f32>, NmeaError> {
+    Ok(fixed(field)?.map(|(m, s)| m as f32 / 10i64.pow(s) as f32))
+}
+
+fn utc_time(field: &[u8]) -> Result<Option<UtcTime>, NmeaError> {
+    let Some((value, scale)) = fixed(field)? else {
+        return Ok(None);
+    };
+    if field.len() < 6 || value < 0 {
+        return Err(NmeaError::Field);
+    }
+    let unit = 10i64.pow(scale);
+    let whole = value / unit;
+    let millis = (value % unit) * 1_000 / unit;
+    Ok(Some(UtcTime {
+        hour: (whole / 10_000) as u8,
+        minute: (whole / 100 % 100) as u8,
+        second: (whole % 100) as u8,
+        millis: millis as u16,
+    }))
+}
+
+/// Convert `dddmm.mmmm` plus a hemisphere letter into 10⁻⁷ degrees.
+fn angle_e7(field: &[u8], hemisphere: &[u8]) -> Result<Option<i32>, NmeaError> {
+    let Some((value, scale)) = fixed(field)? else {
+        return Ok(None);
+    };
+    if value < 0 {
+        return Err(NmeaError::Field);
+    }
+    let unit = 10i64.pow(scale);
+    let degrees = value / (100 * unit);
+    let minutes_scaled = value % (100 * unit);
+    let e7 = degrees * 10_000_000 + minutes_scaled * 10_000_000 / (60 * unit);
+    let e7 = match hemisphere {
+        b"N" | b"E" => e7,
+        b"S" | b"W" => -e7,
+        _ => return Err(NmeaError::Field),
+    };
+    Ok(Some(e7 as i32))
+}
+
+fn position(lat: &[u8], ns: &[u8], lon: &[u8], ew: &[u8]) -> Result<Option<Coord>, NmeaError> {
+    match (angle_e7(lat, ns)?, angle_e7(lon, ew)?) {
+        (Some(lat), Some(lon)) => Ok(Some(FixedCoord::new(lat, lon).into())),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GGA: &[u8] = b"$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n";
+    const RMC: &[u8] = b"$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r\n";
+    const GSV: &[u8] = b"$GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75\r\n";
+
+    #[test]
+    fn test_parse_any_dispatches_gga() {
+        let Ok(Sentence::Gga(fix)) = parse_any(GGA) else {
+            panic!("expected GGA");
+        };
+        let pos = fix.position.unwrap();
+        assert!(pos.approx_eq(&Coord::new(48.117_3, 11.516_667), 1e-4));
+        assert_eq!(fix.fix_quality, 1);
+        assert_eq!(fix.satellites, 8);
+        assert_eq!(fix.hdop, Some(0.9));
+        assert_eq!(
+            fix.time,
+            Some(UtcTime {
+                hour: 12,
+                minute: 35,
+                second: 19,
+                millis: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_any_dispatches_rmc() {
+        let Ok(Sentence::Rmc(fix)) = parse_any(RMC) else {
+            panic!("expected RMC");
+        };
+        assert_eq!(fix.status, b'A');
+        assert!(fix.is_valid());
+        assert_eq!(fix.speed_knots, Some(22.4));
+        assert_eq!(fix.course_deg, Some(84.4));
+        assert!(fix.position.is_some());
+    }
+
+    #[test]
+    fn test_try_from_matches_parse_functions() {
+        fn gga(line: &[u8]) -> Result<u8, NmeaError> {
+            let fix = GgaFix::try_from(line)?;
+            Ok(fix.satellites)
+        }
+        assert_eq!(gga(GGA), Ok(8));
+        assert_eq!(gga(RMC), Err(NmeaError::SentenceType));
+
+        assert_eq!(RmcFix::try_from(RMC), parse_rmc(RMC));
+        assert_eq!(GsvInfo::try_from(GSV), parse_gsv(GSV));
+        assert_eq!(Sentence::try_from(GGA), parse_any(GGA));
+
+        let mut corrupt = RMC.to_vec();
+        corrupt[10] = b'9';
+        assert_eq!(RmcFix::try_from(&corrupt[..]), Err(NmeaError::Checksum));
+        assert_eq!(
+            Sentence::try_from(&b"GPGGA,123519"[..]),
+            Err(NmeaError::Framing)
+        );
+    }
+
+    #[test]
+    fn test_void_rmc_has_no_valid_position() {
+        const VOID: &[u8] =
+            b"$GPRMC,123519,V,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*7D\r\n";
+        let fix = parse_rmc(VOID).unwrap();
+        assert!(!fix.is_valid());
+        // The stale coordinates are still reported, but never as a usable fix.
+        assert!(fix.position.is_some());
+        assert_eq!(fix.valid_position(), None);
+        assert_eq!(parse_any(VOID).unwrap().valid_position(), None);
+        assert!(parse_any(RMC).unwrap().valid_position().is_some());
+    }
+
+    #[test]
+    fn test_parse_any_dispatches_gsv() {
+        let Ok(Sentence::Gsv(info)) = parse_any(GSV) else {
+            panic!("expected GSV");
+        };
+        assert_eq!(info.talker, *b"GP");
+        assert_eq!(
+            (
+                info.message_count,
+                info.message_number,
+                info.satellites_in_view
+            ),
+            (2, 1, 8)
+        );
+        assert_eq!(
+            info.satellites[3],
+            Some(SatelliteInfo {
+                prn: 14,
+                elevation_deg: Some(22),
+                azimuth_deg: Some(228),
+                snr_db: Some(45)
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_any_unknown_and_errors() {
+        assert_eq!(
+            parse_any(b"$GPGSA,A,3,,,,,,,,,,,,,2.5,1.3,2.1*34"),
+            Ok(Sentence::Unknown)
+        );
+        assert_eq!(parse_any(b"$PMTK001,220,3*30"), Ok(Sentence::Unknown));
+        assert_eq!(parse_any(b"$GPGGA,1*00"), Err(NmeaError::Checksum));
+        assert_eq!(parse_gga(RMC), Err(NmeaError::SentenceType));
+    }
+
+    #[test]
+    fn test_long_fractions_are_truncated_not_overflowed() {
+        assert_eq!(fixed(b"0.0000000000000000000"), Ok(Some((0, 9))));
+        assert_eq!(decimal(b"0.0000000000000000000"), Ok(Some(0.0)));
+        assert_eq!(fixed(b"1.2345678901234"), Ok(Some((1_234_567_890, 9))));
+        assert_eq!(fixed(b"1.00000000000x"), Err(NmeaError::Field));
+        assert_eq!(
+            utc_time(b"123519.1239999999999"),
+            Ok(Some(UtcTime {
+                hour: 12,
+                minute: 35,
+                second: 19,
+                millis: 123,
+            }))
+        );
+        let e7 = angle_e7(b"4807.0380000000000000000", b"N")
+            .unwrap()
+            .unwrap();
+        assert_eq!(e7, 481_173_000);
+    }
+}
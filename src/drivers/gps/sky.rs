@@ -0,0 +1,141 @@
+//! Satellites in view merged across constellations.
+
+use super::sentence::{GsvInfo, Sentence};
+
+/// Most constellations [`SkyView`] tracks at once (GPS, GLONASS, Galileo,
+/// BeiDou, QZSS, NavIC).
+pub const MAX_CONSTELLATIONS: usize = 6;
+
+/// Satellites seen during one update cycle, see [`SkyView`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkySummary {
+    /// Satellites in view across every constellation.
+    pub satellites_in_view: u8,
+    /// Satellites reported with a signal-to-noise ratio, i.e. tracked.
+    pub tracked: u8,
+    /// Mean SNR of the tracked satellites in dB-Hz, `None` if none is tracked.
+    pub mean_snr_db: Option<u8>,
+}
+
+/// Merges the `GSV` sets of several constellations into one sky summary per
+/// update cycle.
+///
+/// Receivers that combine constellations send a `GN` fix but keep `GSV`
+/// separate per system (`GPGSV`, `GLGSV`, ...). Feed every parsed sentence
+/// to [`SkyView::update`]; the `GSV` messages are accumulated, and the next
+/// `GGA`, which starts each cycle, closes it and returns the totals, e.g.
+/// for a signal-quality cue.
+#[derive(Debug, Clone)]
+pub struct SkyView {
+    in_view: heapless::Vec<([u8; 2], u8), MAX_CONSTELLATIONS>,
+    snr_sum: u32,
+    tracked: u8,
+}
+
+impl SkyView {
+    /// Create an empty aggregator.
+    pub const fn new() -> Self {
+        Self {
+            in_view: heapless::Vec::new(),
+            snr_sum: 0,
+            tracked: 0,
+        }
+    }
+
+    /// Feed the next sentence; returns the summary of the cycle a `GGA`
+    /// closes.
+    pub fn update(&mut self, sentence: &Sentence) -> Option<SkySummary> {
+        match sentence {
+            Sentence::Gsv(info) => {
+                self.add(info);
+                None
+            }
+            Sentence::Gga(_) => {
+                let summary = self.summary();
+                *self = Self::new();
+                Some(summary)
+            }
+            _ => None,
+        }
+    }
+
+    /// Add one `GSV` message to the current cycle.
+    ///
+    /// Constellations beyond [`MAX_CONSTELLATIONS`] are ignored.
+    pub fn add(&mut self, info: &GsvInfo) {
+        match self.in_view.iter_mut().find(|(t, _)| *t == info.talker) {
+            Some((_, count)) => *count = info.satellites_in_view,
+            None => {
+                let _ = self.in_view.push((info.talker, info.satellites_in_view));
+            }
+        }
+        for snr in info.satellites.iter().flatten().filter_map(|s| s.snr_db) {
+            self.snr_sum += u32::from(snr);
+            self.tracked = self.tracked.saturating_add(1);
+        }
+    }
+
+    /// Totals of the cycle so far.
+    pub fn summary(&self) -> SkySummary {
+        let in_view = self.in_view.iter().map(|&(_, n)| u32::from(n)).sum::<u32>();
+        SkySummary {
+            satellites_in_view: in_view.min(u32::from(u8::MAX)) as u8,
+            tracked: self.tracked,
+            mean_snr_db: (self.tracked > 0).then(|| {
+                let n = u32::from(self.tracked);
+                ((self.snr_sum + n / 2) / n) as u8
+            }),
+        }
+    }
+}
+
+impl Default for SkyView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drivers::gps::parse_any;
+
+    const GGA: &[u8] = b"$GNGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*59\r\n";
+    const GP1: &[u8] = b"$GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75\r\n";
+    const GP2: &[u8] = b"$GPGSV,2,2,08,15,10,100,,17,50,200,30,19,05,300,,21,60,040,38*73\r\n";
+    const GL1: &[u8] = b"$GLGSV,1,1,03,65,30,100,35,66,45,200,,70,10,300,25*50\r\n";
+
+    #[test]
+    fn test_merges_two_constellations_until_next_gga() {
+        let mut sky = SkyView::new();
+        for line in [GP1, GP2, GL1] {
+            assert_eq!(sky.update(&parse_any(line).unwrap()), None);
+        }
+        // 8 GPS + 3 GLONASS; SNRs 46 41 39 45 30 38 35 25 average 37.4.
+        let expected = SkySummary {
+            satellites_in_view: 11,
+            tracked: 8,
+            mean_snr_db: Some(37),
+        };
+        assert_eq!(sky.update(&parse_any(GGA).unwrap()), Some(expected));
+
+        // The GGA started a new cycle.
+        assert_eq!(sky.update(&parse_any(GL1).unwrap()), None);
+        assert_eq!(
+            sky.update(&parse_any(GGA).unwrap()),
+            Some(SkySummary {
+                satellites_in_view: 3,
+                tracked: 2,
+                mean_snr_db: Some(30),
+            })
+        );
+        assert_eq!(
+            sky.update(&parse_any(GGA).unwrap()),
+            Some(SkySummary {
+                satellites_in_view: 0,
+                tracked: 0,
+                mean_snr_db: None,
+            })
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! u-blox UBX binary protocol.
+//!
+//! A frame is `B5 62 <class> <id> <len:u16 LE> <payload> <ck_a> <ck_b>`, with
+//! an 8-bit Fletcher checksum over everything between the preamble and the
+//! checksum bytes. All multi-byte fields are little-endian.
+
+use embedded_hal_nb::serial::Read;
+
+use crate::nav::FixedCoord;
+
+/// The two sync bytes that start every UBX frame.
+pub const PREAMBLE: [u8; 2] = [0xB5, 0x62];
+/// Bytes around the payload: preamble, class, id, length and checksum.
+pub const FRAME_OVERHEAD: usize = 8;
+
+/// `NAV` message class.
+pub const CLASS_NAV: u8 = 0x01;
+/// `NAV-PVT` message id.
+pub const ID_NAV_PVT: u8 = 0x07;
+/// Payload length of `NAV-PVT` (protocol 15+).
+pub const NAV_PVT_LEN: usize = 92;
+
+/// Error produced while validating or parsing a UBX frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UbxError {
+    /// Missing preamble or a frame shorter than its length field.
+    Framing,
+    /// The Fletcher checksum does not match.
+    Checksum,
+    /// The frame is valid but not of the class/id that was asked for.
+    MessageType,
+    /// The payload length is wrong for the message type.
+    Length,
+}
+
+/// Error returned by [`UbxReader::read`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError<E> {
+    /// The serial port reported an error; the partial frame was dropped.
+    Serial(E),
+    /// A frame was dropped: [`UbxError::Checksum`] for corruption, or
+    /// [`UbxError::Length`] if it would not fit the reader's buffer.
+    Frame(UbxError),
+}
+
+/// 8-bit Fletcher checksum `(ck_a, ck_b)` over `bytes` (class through payload).
+pub fn checksum(bytes: &[u8]) -> (u8, u8) {
+    bytes.iter().fold((0u8, 0u8), |(a, b), &byte| {
+        let a = a.wrapping_add(byte);
+        (a, b.wrapping_add(a))
+    })
+}
+
+/// Check the framing and checksum of `frame` and return
+/// `(class, id, payload)`.
+///
+/// Bytes after the checksum are ignored.
+pub fn validate(frame: &[u8]) -> Result<(u8, u8, &[u8]), UbxError> {
+    let rest = frame.strip_prefix(&PREAMBLE).ok_or(UbxError::Framing)?;
+    let [class, id, lo, hi, ..] = *rest else {
+        return Err(UbxError::Framing);
+    };
+    let len = usize::from(u16::from_le_bytes([lo, hi]));
+    let covered = rest.get(..4 + len).ok_or(UbxError::Framing)?;
+    let expected = rest.get(4 + len..4 + len + 2).ok_or(UbxError::Framing)?;
+    if checksum(covered) != (expected[0], expected[1]) {
+        return Err(UbxError::Checksum);
+    }
+    Ok((class, id, &covered[4..]))
+}
+
+/// Position, velocity and time solution from a `NAV-PVT` message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PvtFix {
+    /// GPS time of week of the solution in milliseconds.
+    pub itow_ms: u32,
+    /// Fix type: 0 = none, 1 = dead reckoning, 2 = 2D, 3 = 3D,
+    /// 4 = GNSS + dead reckoning, 5 = time only.
+    pub fix_type: u8,
+    /// The receiver's `gnssFixOK` flag: the fix is within its DOP and
+    /// accuracy masks.
+    pub fix_ok: bool,
+    /// Number of satellites used in the solution.
+    pub satellites: u8,
+    /// Position in 10⁻⁷ degrees.
+    pub position: FixedCoord,
+    /// Height above mean sea level in millimetres.
+    pub height_msl_mm: i32,
+    /// Horizontal accuracy estimate in millimetres.
+    pub h_acc_mm: u32,
+    /// Ground speed in millimetres per second.
+    pub ground_speed_mm_s: i32,
+    /// Heading of motion in 10⁻⁵ degrees from true north.
+    pub heading_e5: i32,
+}
+
+impl PvtFix {
+    /// `true` for a 2D/3D (or GNSS + dead reckoning) fix the receiver
+    /// flagged as OK.
+    pub fn is_valid(&self) -> bool {
+        self.fix_ok && matches!(self.fix_type, 2..=4)
+    }
+
+    /// Position, or `None` unless [`PvtFix::is_valid`].
+    pub fn valid_position(&self) -> Option<FixedCoord> {
+        self.is_valid().then_some(self.position)
+    }
+}
+
+/// Validate and parse a complete `NAV-PVT` frame, preamble to checksum.
+pub fn parse_nav_pvt(frame: &[u8]) -> Result<PvtFix, UbxError> {
+    let (class, id, payload) = validate(frame)?;
+    if (class, id) != (CLASS_NAV, ID_NAV_PVT) {
+        return Err(UbxError::MessageType);
+    }
+    if payload.len() != NAV_PVT_LEN {
+        return Err(UbxError::Length);
+    }
+    let u32_at = |at: usize| {
+        u32::from_le_bytes([
+            payload[at],
+            payload[at + 1],
+            payload[at + 2],
+            payload[at + 3],
+        ])
+    };
+    let i32_at = |at: usize| u32_at(at) as i32;
+    Ok(PvtFix {
+        itow_ms: u32_at(0),
+        fix_type: payload[20],
+        fix_ok: payload[21] & 0x01 != 0,
+        satellites: payload[23],
+        position: FixedCoord::new(i32_at(28), i32_at(24)),
+        height_msl_mm: i32_at(36),
+        h_acc_mm: u32_at(40),
+        ground_speed_mm_s: i32_at(60),
+        heading_e5: i32_at(64),
+    })
+}
+
+/// Streaming UBX frame reader for a serial port.
+///
+/// Bytes are consumed until a complete, checksum-verified frame is buffered;
+/// the frame (preamble to checksum, ready for [`parse_nav_pvt`] or
+/// [`validate`]) is then returned. Anything that is not a frame, including
+/// interleaved NMEA, is skipped by re-searching for the `B5 62` preamble.
+///
+/// `N` is the largest frame accepted, overhead included; the default fits
+/// `NAV-PVT`. It must be at least [`FRAME_OVERHEAD`], the size of a frame
+/// with an empty payload, or [`UbxReader::new`] fails to compile:
+///
+/// ```compile_fail
+/// use core::convert::Infallible;
+/// use embedded_hal_nb::serial::{ErrorType, Read};
+/// use gps::drivers::gps::ubx::UbxReader;
+///
+/// struct Idle;
+/// impl ErrorType for Idle {
+///     type Error = Infallible;
+/// }
+/// impl Read<u8> for Idle {
+///     fn read(&mut self) -> nb::Result<u8, Infallible> {
+///         Err(nb::Error::WouldBlock)
+///     }
+/// }
+///
+/// let _reader = UbxReader::<_, 4>::new(Idle);
+/// ```
+#[derive(Debug)]
+pub struct UbxReader<R, const N: usize = { NAV_PVT_LEN + FRAME_OVERHEAD }> {
+    serial: R,
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<R: Read<u8>, const N: usize> UbxReader<R, N> {
+    /// Wrap `serial`.
+    pub fn new(serial: R) -> Self {
+        const {
+            assert!(
+                N >= FRAME_OVERHEAD,
+                "UbxReader buffer is smaller than a UBX frame"
+            )
+        };
+        Self {
+            serial,
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Read available bytes until a frame completes.
+    ///
+    /// Returns `WouldBlock` when the port runs dry mid-frame; the partial
+    /// frame is kept for the next call. After an error the reader resyncs on
+    /// the next preamble by itself.
+    pub fn read(&mut self) -> nb::Result<&[u8], ReadError<R::Error>> {
+        loop {
+            let byte = match self.serial.read() {
+                Ok(byte) => byte,
+                Err(nb::Error::WouldBlock) => return Err(nb::Error::WouldBlock),
+                Err(nb::Error::Other(e)) => {
+                    self.len = 0;
+                    return Err(nb::Error::Other(ReadError::Serial(e)));
+                }
+            };
+            match self.push(byte) {
+                None => {}
+                Some(Ok(total)) => {
+                    return match validate(&self.buf[..total]) {
+                        Ok(_) => Ok(&self.buf[..total]),
+                        Err(e) => Err(nb::Error::Other(ReadError::Frame(e))),
+                    };
+                }
+                Some(Err(e)) => return Err(nb::Error::Other(ReadError::Frame(e))),
+            }
+        }
+    }
+
+    /// Give back the serial port.
+    pub fn release(self) -> R {
+        self.serial
+    }
+
+    /// Add `byte` to the frame being assembled; returns the frame length once
+    /// complete, or an error if the frame is too long for `N`.
+    fn push(&mut self, byte: u8) -> Option<Result<usize, UbxError>> {
+        match self.len {
+            0 if byte != PREAMBLE[0] => return None,
+            // A repeated first sync byte may still start the real preamble.
+            1 if byte == PREAMBLE[0] => return None,
+            1 if byte != PREAMBLE[1] => {
+                self.len = 0;
+                return None;
+            }
+            _ => {}
+        }
+        self.buf[self.len] = byte;
+        self.len += 1;
+        if self.len < 6 {
+            return None;
+        }
+        let total = usize::from(u16::from_le_bytes([self.buf[4], self.buf[5]])) + FRAME_OVERHEAD;
+        if total > N {
+            self.len = 0;
+            return Some(Err(UbxError::Length));
+        }
+        if self.len < total {
+            return None;
+        }
+        self.len = 0;
+        Some(Ok(total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3D fix at 48.1173° N, 11.516667° E doing 1.234 m/s on 84.4°.
+    #[rustfmt::skip]
+    const NAV_PVT: [u8; 100] = [
+        0xB5, 0x62, 0x01, 0x07, 0x5C, 0x00, 0x00, 0x9E, 0x2C, 0x17, 0xE8, 0x07, 0x06, 0x01, 0x0C, 0x00,
+        0x00, 0x37, 0x19, 0x00, 0x00, 0x00, 0xC7, 0xCF, 0xFF, 0xFF, 0x03, 0x01, 0xEA, 0x0C, 0xCE, 0x4D,
+        0xDD, 0x06, 0x08, 0x1E, 0xAE, 0x1C, 0xB0, 0x00, 0x09, 0x00, 0x78, 0x52, 0x08, 0x00, 0xDC, 0x05,
+        0x00, 0x00, 0xFC, 0x08, 0x00, 0x00, 0x78, 0x00, 0x00, 0x00, 0xCC, 0x04, 0x00, 0x00, 0xFB, 0xFF,
+        0xFF, 0xFF, 0xD2, 0x04, 0x00, 0x00, 0xC0, 0xC8, 0x80, 0x00, 0x2C, 0x01, 0x00, 0x00, 0x80, 0x84,
+        0x1E, 0x00, 0x82, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x72, 0x65,
+    ];
+
+    #[test]
+    fn test_parse_nav_pvt() {
+        let fix = parse_nav_pvt(&NAV_PVT).unwrap();
+        assert_eq!(fix.itow_ms, 388_800_000);
+        assert_eq!(fix.fix_type, 3);
+        assert!(fix.is_valid());
+        assert_eq!(fix.satellites, 12);
+        assert_eq!(fix.position, FixedCoord::new(481_173_000, 115_166_670));
+        assert_eq!(fix.height_msl_mm, 545_400);
+        assert_eq!(fix.h_acc_mm, 1_500);
+        assert_eq!(fix.ground_speed_mm_s, 1_234);
+        assert_eq!(fix.heading_e5, 8_440_000);
+    }
+
+    /// Serial port that replays `bytes` and then reports `WouldBlock`.
+    struct Replay<'a> {
+        bytes: &'a [u8],
+    }
+    impl embedded_hal_nb::serial::ErrorType for Replay<'_> {
+        type Error = embedded_hal_nb::serial::ErrorKind;
+    }
+    impl Read<u8> for Replay<'_> {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            let (&first, rest) = self.bytes.split_first().ok_or(nb::Error::WouldBlock)?;
+            self.bytes = rest;
+            Ok(first)
+        }
+    }
+
+    #[test]
+    fn test_reader_skips_junk_before_frame() {
+        let mut stream = Vec::from(&b"$GPGGA,1*00\r\n\xB5\x00\xB5"[..]);
+        stream.extend_from_slice(&NAV_PVT);
+        let mut reader: UbxReader<_> = UbxReader::new(Replay { bytes: &stream });
+
+        let frame = reader.read().unwrap();
+        assert_eq!(frame, NAV_PVT);
+        assert_eq!(parse_nav_pvt(frame).unwrap().satellites, 12);
+        assert_eq!(reader.read(), Err(nb::Error::WouldBlock));
+    }
+
+    #[test]
+    fn test_reader_resyncs_after_bad_frames() {
+        let mut corrupt = NAV_PVT;
+        corrupt[40] ^= 0xFF;
+        let mut stream = Vec::from(&corrupt[..]);
+        // Longer than the 16-byte buffer below.
+        stream.extend_from_slice(&[0xB5, 0x62, 0x01, 0x07, 0x5C, 0x00]);
+        let ack = [0xB5, 0x62, 0x05, 0x01, 0x02, 0x00, 0x06, 0x00, 0x0E, 0x37];
+        stream.extend_from_slice(&ack);
+
+        let mut reader: UbxReader<_> = UbxReader::new(Replay { bytes: &stream });
+        assert_eq!(
+            reader.read(),
+            Err(nb::Error::Other(ReadError::Frame(UbxError::Checksum)))
+        );
+        let mut small: UbxReader<_, 16> = UbxReader::new(Replay {
+            bytes: &stream[NAV_PVT.len()..],
+        });
+        assert_eq!(
+            small.read(),
+            Err(nb::Error::Other(ReadError::Frame(UbxError::Length)))
+        );
+        assert_eq!(small.read(), Ok(&ack[..]));
+    }
+
+    #[test]
+    fn test_parse_nav_pvt_errors() {
+        let mut corrupt = NAV_PVT;
+        corrupt[30] ^= 0x01;
+        assert_eq!(parse_nav_pvt(&corrupt), Err(UbxError::Checksum));
+        assert_eq!(parse_nav_pvt(&NAV_PVT[..60]), Err(UbxError::Framing));
+        assert_eq!(parse_nav_pvt(&NAV_PVT[1..]), Err(UbxError::Framing));
+
+        // ACK-ACK for CFG-PRT: valid frame, wrong message.
+        let ack = [0xB5, 0x62, 0x05, 0x01, 0x02, 0x00, 0x06, 0x00, 0x0E, 0x37];
+        assert_eq!(parse_nav_pvt(&ack), Err(UbxError::MessageType));
+    }
+}
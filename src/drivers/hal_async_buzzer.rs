@@ -0,0 +1,186 @@
+//! Buzzer driver for async executors, built on `embedded-hal-async`.
+//!
+//! Enabled by the `async` feature. Wraps the blocking
+//! [`Buzzer`] for everything but the wait, so tones
+//! get the same frequency limits, standby handling and retune cache, but
+//! await an async [`DelayNs`] so other tasks run while a tone plays. Not to
+//! be confused with [`AsyncBuzzer`](super::async_buzzer::AsyncBuzzer), which
+//! plays beep patterns polled from a main loop. Not used by the AVR
+//! firmware; it lets the driver be reused on MCUs with an async HAL.
+
+use embedded_hal::pwm::SetDutyCycle;
+use embedded_hal_async::delay::DelayNs;
+
+use super::buzzer::{Buzzer, SetFrequency};
+
+/// Blocking delay for the wrapped [`Buzzer`], which never needs one: there
+/// is no enable pin to settle, and tones are timed by the async delay.
+struct NoWait;
+
+impl embedded_hal::delay::DelayNs for NoWait {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Buzzer whose tones await an async delay instead of blocking.
+pub struct HalAsyncBuzzer<PWM, D>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+{
+    buzzer: Buzzer<PWM, NoWait>,
+    delay: D,
+}
+
+impl<PWM, D> HalAsyncBuzzer<PWM, D>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+{
+    /// Create a new [`HalAsyncBuzzer`], ensuring the PWM starts at 0% duty.
+    ///
+    /// * `pwm`   – PWM channel implementing [`SetDutyCycle`] and [`SetFrequency`]
+    /// * `delay` – async delay provider implementing [`DelayNs`]
+    pub fn new(pwm: PWM, delay: D) -> Self {
+        Self::with_min_max_freq(pwm, delay, 0, u32::MAX)
+    }
+
+    /// Create a new [`HalAsyncBuzzer`] whose tones are clamped to
+    /// `min_hz..=max_hz`, like [`Buzzer::with_min_max_freq`].
+    pub fn with_min_max_freq(pwm: PWM, delay: D, min_hz: u32, max_hz: u32) -> Self {
+        Self {
+            buzzer: Buzzer::with_min_max_freq(pwm, NoWait, min_hz, max_hz),
+            delay,
+        }
+    }
+
+    /// Consume the driver and return the PWM channel and delay provider.
+    pub fn release(self) -> (PWM, D) {
+        (self.buzzer.release().0, self.delay)
+    }
+
+    /// See [`Buzzer::set_frequency_limits`].
+    pub fn set_frequency_limits(&mut self, min_hz: u32, max_hz: u32) {
+        self.buzzer.set_frequency_limits(min_hz, max_hz);
+    }
+
+    /// See [`Buzzer::frequency_hz`].
+    pub fn frequency_hz(&self) -> Option<u32> {
+        self.buzzer.frequency_hz()
+    }
+
+    /// Play a tone at `duty_percent` for `duration_ms` milliseconds, like
+    /// [`Buzzer::tone`] without its edge fade or dithering.
+    ///
+    /// A `frequency_hz` of 0 is played as a [`HalAsyncBuzzer::rest`].
+    pub async fn tone(
+        &mut self,
+        frequency_hz: u32,
+        duty_percent: u8,
+        duration_ms: u32,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        if frequency_hz == 0 {
+            self.rest(duration_ms).await;
+            return Ok(());
+        }
+        self.buzzer.start_tone(frequency_hz, duty_percent)?;
+        self.delay.delay_ms(duration_ms).await;
+        self.buzzer.stop_tone();
+        Ok(())
+    }
+
+    /// Stay silent for `duration_ms` milliseconds.
+    pub async fn rest(&mut self, duration_ms: u32) {
+        self.buzzer.stop_tone();
+        self.delay.delay_ms(duration_ms).await;
+    }
+
+    /// Play a sequence of `(frequency_hz, duty_percent, duration_ms)` notes,
+    /// like [`Buzzer::melody`].
+    pub async fn melody(
+        &mut self,
+        notes: impl IntoIterator<Item = (u32, u8, u32)>,
+    ) -> Result<(), <PWM as SetFrequency>::Error> {
+        for (frequency_hz, duty_percent, duration_ms) in notes {
+            self.tone(frequency_hz, duty_percent, duration_ms).await?;
+        }
+        Ok(())
+    }
+
+    /// Silence the output and put the PWM into standby, like
+    /// [`Buzzer::idle`]; the next tone resumes it.
+    pub fn idle(&mut self) {
+        self.buzzer.idle();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::RecordingPwm;
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    /// Poll `future` to completion, spinning on `Pending`.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    /// Delay that is pending once before completing, and records each wait.
+    #[derive(Default)]
+    struct YieldingDelay {
+        waits_ns: Vec<u32>,
+    }
+    impl DelayNs for YieldingDelay {
+        async fn delay_ns(&mut self, ns: u32) {
+            let mut yielded = false;
+            core::future::poll_fn(|_| {
+                if yielded {
+                    Poll::Ready(())
+                } else {
+                    yielded = true;
+                    Poll::Pending
+                }
+            })
+            .await;
+            self.waits_ns.push(ns);
+        }
+    }
+
+    #[test]
+    fn test_async_melody_sets_tones_and_awaits_each_note() {
+        let mut buzzer = HalAsyncBuzzer::new(RecordingPwm::default(), YieldingDelay::default());
+        block_on(buzzer.melody([(440, 50, 100), (0, 0, 20), (880, 25, 50)])).unwrap();
+
+        let (pwm, delay) = buzzer.release();
+        assert_eq!(pwm.frequencies, [440, 880]);
+        assert_eq!(pwm.duties, [0, 50, 0, 0, 25, 0]);
+        assert_eq!(delay.waits_ns, [100_000_000, 20_000_000, 50_000_000]);
+    }
+
+    #[test]
+    fn test_async_tone_shares_clamp_cache_and_standby() {
+        let mut buzzer = HalAsyncBuzzer::with_min_max_freq(
+            RecordingPwm::default(),
+            YieldingDelay::default(),
+            500,
+            2_000,
+        );
+        block_on(buzzer.melody([(100, 50, 10), (300, 50, 10), (5_000, 50, 10)])).unwrap();
+        assert_eq!(buzzer.frequency_hz(), Some(2_000));
+        buzzer.idle();
+        block_on(buzzer.tone(2_000, 50, 10)).unwrap();
+
+        let (pwm, _) = buzzer.release();
+        assert_eq!(pwm.frequencies, [500, 2_000]);
+        assert_eq!((pwm.standbys, pwm.resumes), (1, 1));
+    }
+}
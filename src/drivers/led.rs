@@ -0,0 +1,84 @@
+//! Dimmable status LED on a PWM channel.
+
+use embedded_hal::pwm::SetDutyCycle;
+
+use super::util::duty_from_percent;
+
+/// Status LED whose brightness is set as a percentage.
+///
+/// Uses the same duty rounding as [`Buzzer::tone`](super::buzzer::Buzzer::tone),
+/// so any non-zero brightness stays visibly lit even on a coarse PWM.
+/// Duty-cycle errors are ignored, as in the buzzer driver. Pair with a
+/// [`LightSensor`](super::light::LightSensor) to follow the daylight.
+pub struct PwmLed<PWM: SetDutyCycle> {
+    pwm: PWM,
+    brightness: u8,
+}
+
+impl<PWM: SetDutyCycle> PwmLed<PWM> {
+    /// Create a new [`PwmLed`], switched off.
+    pub fn new(mut pwm: PWM) -> Self {
+        let _ = pwm.set_duty_cycle(0);
+        Self { pwm, brightness: 0 }
+    }
+
+    /// Set the brightness in percent; values above 100 count as 100.
+    pub fn set_brightness(&mut self, percent: u8) {
+        let percent = percent.min(100);
+        let max = self.pwm.max_duty_cycle();
+        let _ = self.pwm.set_duty_cycle(duty_from_percent(max, percent));
+        self.brightness = percent;
+    }
+
+    /// Brightness last set, in percent.
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Switch the LED off; same as a brightness of 0.
+    pub fn off(&mut self) {
+        self.set_brightness(0);
+    }
+
+    /// Consume the driver and return the PWM channel.
+    pub fn release(self) -> PWM {
+        self.pwm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        drivers::buzzer::Buzzer,
+        firmware::shared::{delay::NopDelay, fixtures::CoarsePwm},
+    };
+
+    #[test]
+    fn test_brightness_maps_to_rounded_duty() {
+        let mut led = PwmLed::new(CoarsePwm::new(30));
+        for percent in [1, 10, 50, 100, 150, 0] {
+            led.set_brightness(percent);
+        }
+        assert_eq!(led.brightness(), 0);
+        // 1% of 30 is 0.3, kept on; 50% is 15; 150% is clamped.
+        assert_eq!(led.release().duties, [0, 1, 3, 15, 30, 30, 0]);
+    }
+
+    #[test]
+    fn test_led_and_buzzer_share_duty_rounding() {
+        let percents = [1, 5, 33, 50, 99];
+        let mut led = PwmLed::new(CoarsePwm::new(30));
+        let mut buzzer = Buzzer::new(CoarsePwm::new(30), NopDelay::new());
+        for percent in percents {
+            led.set_brightness(percent);
+            buzzer.tone(1_000, percent, 1).unwrap();
+        }
+        let led_duties = &led.release().duties[1..];
+        let (pwm, _) = buzzer.release();
+        let tone_duties: Vec<_> = pwm.duties.iter().copied().filter(|&d| d != 0).collect();
+        assert_eq!(led_duties, tone_duties);
+        let expected: Vec<_> = percents.map(|p| duty_from_percent(30, p)).into();
+        assert_eq!(led_duties, expected);
+    }
+}
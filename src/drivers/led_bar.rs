@@ -0,0 +1,145 @@
+//! Bar-graph gauge made of `N` LEDs, e.g. for distance remaining.
+
+use embedded_hal::digital::OutputPin;
+
+use crate::util::math::round_u32;
+
+/// Row of `N` active-high LEDs lit from index 0 upwards like a bar graph.
+///
+/// Map any quantity onto the bar with [`LedBar::show_fraction`]; for
+/// distance remaining on a leg, pass `remaining_m / leg_length_m`.
+pub struct LedBar<P, const N: usize>
+where
+    P: OutputPin,
+{
+    leds: [P; N],
+    lit: usize,
+}
+
+impl<P, const N: usize> LedBar<P, N>
+where
+    P: OutputPin,
+{
+    /// Create a new [`LedBar`] with every LED off.
+    ///
+    /// * `leds` – output pins, index 0 being the bottom of the bar
+    pub fn new(leds: [P; N]) -> Self {
+        let mut bar = Self { leds, lit: N };
+        bar.show_count(0);
+        bar
+    }
+
+    /// Light a share of the bar proportional to `frac`, rounded to the
+    /// nearest LED.
+    ///
+    /// `frac` is clamped to `0.0..=1.0`; NaN shows an empty bar.
+    pub fn show_fraction(&mut self, frac: f32) {
+        self.show_count(lit_count(frac, N));
+    }
+
+    /// Light the bottom `count` LEDs (at most `N`) and turn the rest off.
+    ///
+    /// Pin errors are ignored, like duty-cycle errors in the buzzer driver.
+    pub fn show_count(&mut self, count: usize) {
+        let count = count.min(N);
+        for (i, led) in self.leds.iter_mut().enumerate() {
+            let _ = if i < count {
+                led.set_high()
+            } else {
+                led.set_low()
+            };
+        }
+        self.lit = count;
+    }
+
+    /// Number of LEDs currently lit.
+    pub fn lit(&self) -> usize {
+        self.lit
+    }
+
+    /// Consume the driver and return the LED pins.
+    pub fn release(self) -> [P; N] {
+        self.leds
+    }
+}
+
+/// Output byte for a bar of up to eight LEDs on a shift register, lighting
+/// the bottom `count` bits (QA upwards on an [`Sr595`](super::shift_register::Sr595)).
+///
+/// Pair with [`lit_count`]: `sr.write_u8(bar_byte(lit_count(frac, 8)))`.
+pub fn bar_byte(count: usize) -> u8 {
+    match count {
+        0 => 0,
+        1..8 => (1u8 << count) - 1,
+        _ => 0xFF,
+    }
+}
+
+/// Number of LEDs out of `n` that [`LedBar::show_fraction`] lights for `frac`.
+pub fn lit_count(frac: f32, n: usize) -> usize {
+    // NaN survives the clamp but saturates to 0 in `round_u32`.
+    round_u32(frac.clamp(0.0, 1.0) * n as f32) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType;
+
+    #[derive(Default)]
+    struct Led(bool);
+    impl ErrorType for Led {
+        type Error = Infallible;
+    }
+    impl OutputPin for Led {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.0 = false;
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.0 = true;
+            Ok(())
+        }
+    }
+
+    fn states<const N: usize>(bar: LedBar<Led, N>) -> [bool; N] {
+        bar.release().map(|led| led.0)
+    }
+
+    #[test]
+    fn test_fraction_lights_proportional_count() {
+        for (frac, lit) in [
+            (0.0, 0),
+            (0.5, 2),
+            (1.0, 4),
+            (1.7, 4),
+            (-0.2, 0),
+            (f32::NAN, 0),
+        ] {
+            let mut bar = LedBar::new(<[Led; 4]>::default());
+            bar.show_fraction(frac);
+            assert_eq!(bar.lit(), lit, "{frac}");
+        }
+
+        let mut bar = LedBar::new(<[Led; 4]>::default());
+        bar.show_fraction(0.5);
+        assert_eq!(states(bar), [true, true, false, false]);
+    }
+
+    #[test]
+    fn test_bar_byte_fills_from_bit_zero() {
+        assert_eq!(bar_byte(0), 0);
+        assert_eq!(bar_byte(lit_count(0.5, 8)), 0b0000_1111);
+        assert_eq!(bar_byte(8), 0xFF);
+        assert_eq!(bar_byte(12), 0xFF);
+    }
+
+    #[test]
+    fn test_shrinking_bar_turns_leds_off() {
+        let mut bar = LedBar::new(<[Led; 5]>::default());
+        bar.show_fraction(1.0);
+        bar.show_fraction(0.4);
+        assert_eq!(states(bar), [true, true, false, false, false]);
+    }
+}
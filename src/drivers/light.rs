@@ -0,0 +1,170 @@
+//! Ambient light from a photocell, for adapting LED brightness to daylight.
+
+use super::adc::AdcChannel;
+
+/// Ambient light band reported by [`LightSensor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ambient {
+    /// Night or a dark room.
+    Dark,
+    /// Indoors or overcast.
+    Indoor,
+    /// Direct daylight.
+    Daylight,
+}
+
+/// Thresholds and LED brightness for each [`Ambient`] band.
+///
+/// Thresholds are raw ADC counts and assume the reading rises with light,
+/// i.e. the photocell sits between the supply and the ADC pin with a fixed
+/// resistor to ground. The defaults suit a 10-bit ADC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightLevels {
+    /// Readings below this are [`Ambient::Dark`].
+    pub dark_below: u16,
+    /// Readings at or above this are [`Ambient::Daylight`].
+    pub daylight_from: u16,
+    /// Counts a reading must pass a threshold by to leave the current band,
+    /// so dusk or a passing shadow does not make the LEDs flicker.
+    pub hysteresis: u16,
+    /// LED brightness in percent when [`Ambient::Dark`].
+    pub dark_percent: u8,
+    /// LED brightness in percent when [`Ambient::Indoor`].
+    pub indoor_percent: u8,
+    /// LED brightness in percent in [`Ambient::Daylight`].
+    pub daylight_percent: u8,
+}
+
+impl Default for LightLevels {
+    fn default() -> Self {
+        Self {
+            dark_below: 150,
+            daylight_from: 700,
+            hysteresis: 30,
+            dark_percent: 10,
+            indoor_percent: 40,
+            daylight_percent: 100,
+        }
+    }
+}
+
+impl LightLevels {
+    /// LED brightness in percent for `ambient`.
+    pub fn brightness_percent(&self, ambient: Ambient) -> u8 {
+        match ambient {
+            Ambient::Dark => self.dark_percent,
+            Ambient::Indoor => self.indoor_percent,
+            Ambient::Daylight => self.daylight_percent,
+        }
+    }
+
+    /// Band for `raw`, ignoring hysteresis.
+    fn band(&self, raw: u16) -> Ambient {
+        if raw < self.dark_below {
+            Ambient::Dark
+        } else if raw >= self.daylight_from {
+            Ambient::Daylight
+        } else {
+            Ambient::Indoor
+        }
+    }
+
+    /// Band for `raw` when currently in `current`: each threshold is moved
+    /// `hysteresis` counts away from the current band.
+    fn band_from(&self, current: Ambient, raw: u16) -> Ambient {
+        let h = self.hysteresis;
+        let shifted = match self.band(raw) {
+            band if band == current => return current,
+            Ambient::Dark => raw.saturating_add(h),
+            Ambient::Daylight => raw.saturating_sub(h),
+            Ambient::Indoor if current == Ambient::Dark => raw.saturating_sub(h),
+            Ambient::Indoor => raw.saturating_add(h),
+        };
+        match self.band(shifted) {
+            // Only far enough past the edge if still out of `current`.
+            band if band == current => current,
+            _ => self.band(raw),
+        }
+    }
+}
+
+/// Photocell on an ADC channel, mapped to an LED brightness.
+///
+/// Feed [`LightSensor::brightness_percent`] to a dimmable LED every second
+/// or so; readings are cheap, and the hysteresis keeps the result steady.
+pub struct LightSensor<A: AdcChannel> {
+    adc: A,
+    levels: LightLevels,
+    ambient: Option<Ambient>,
+}
+
+impl<A: AdcChannel> LightSensor<A> {
+    /// Create a sensor reading the photocell on `adc`.
+    pub fn new(adc: A, levels: LightLevels) -> Self {
+        Self {
+            adc,
+            levels,
+            ambient: None,
+        }
+    }
+
+    /// Read the photocell and return the ambient band.
+    ///
+    /// A failed conversion keeps the previous band ([`Ambient::Indoor`] if
+    /// nothing has been read yet).
+    pub fn ambient(&mut self) -> Ambient {
+        let Ok(raw) = self.adc.read() else {
+            return self.ambient.unwrap_or(Ambient::Indoor);
+        };
+        let ambient = match self.ambient {
+            Some(current) => self.levels.band_from(current, raw),
+            None => self.levels.band(raw),
+        };
+        self.ambient = Some(ambient);
+        ambient
+    }
+
+    /// Read the photocell and return the LED brightness for it in percent.
+    pub fn brightness_percent(&mut self) -> u8 {
+        let ambient = self.ambient();
+        self.levels.brightness_percent(ambient)
+    }
+
+    /// The thresholds and brightness in use.
+    pub fn levels(&self) -> &LightLevels {
+        &self.levels
+    }
+
+    /// Consume the sensor and return the ADC channel.
+    pub fn release(self) -> A {
+        self.adc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::Readings;
+
+    fn brightness(raw: &[u16]) -> Vec<u8> {
+        let mut sensor = LightSensor::new(Readings(raw.iter()), LightLevels::default());
+        raw.iter().map(|_| sensor.brightness_percent()).collect()
+    }
+
+    #[test]
+    fn test_light_maps_to_brightness_bands() {
+        for (raw, percent) in [(0, 10), (149, 10), (150, 40), (699, 40), (700, 100)] {
+            assert_eq!(brightness(&[raw]), [percent], "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_hysteresis_holds_band_near_threshold() {
+        // Dusk hovering around the 150-count dark threshold.
+        let raw = [200, 145, 140, 119, 150, 175, 181, 750, 690, 669, 700];
+        assert_eq!(
+            brightness(&raw),
+            [40, 40, 40, 10, 10, 10, 40, 100, 100, 40, 40]
+        );
+    }
+}
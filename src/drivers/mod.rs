@@ -1 +1,19 @@
+pub mod adc;
+pub mod analog_input;
+pub mod async_buzzer;
+pub mod button;
 pub mod buzzer;
+pub mod compass_ring;
+pub mod dual_buzzer;
+pub mod eeprom;
+pub mod gps;
+#[cfg(feature = "async")]
+pub mod hal_async_buzzer;
+pub mod led;
+pub mod led_bar;
+pub mod light;
+pub mod morse;
+pub mod note;
+pub mod shift_register;
+pub mod tick;
+pub mod util;
@@ -0,0 +1,121 @@
+//! Morse code timing for audible messages (e.g. an SOS on fatal errors).
+
+/// One stretch of sound or silence, in Morse time units.
+///
+/// Standard timing: dot = 1 unit, dash = 3, gap inside a letter = 1, between
+/// letters = 3, between words = 7.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    /// `true` while the tone sounds.
+    pub on: bool,
+    /// Length in units.
+    pub units: u8,
+}
+
+/// The text of a distress signal.
+pub const SOS: &[u8] = b"SOS";
+
+/// Dot/dash pattern for an ASCII letter or digit, case-insensitive.
+pub fn pattern(c: u8) -> Option<&'static [u8]> {
+    const LETTERS: [&[u8]; 26] = [
+        b".-", b"-...", b"-.-.", b"-..", b".", b"..-.", b"--.", b"....", b"..", b".---", b"-.-",
+        b".-..", b"--", b"-.", b"---", b".--.", b"--.-", b".-.", b"...", b"-", b"..-", b"...-",
+        b".--", b"-..-", b"-.--", b"--..",
+    ];
+    const DIGITS: [&[u8]; 10] = [
+        b"-----", b".----", b"..---", b"...--", b"....-", b".....", b"-....", b"--...", b"---..",
+        b"----.",
+    ];
+    match c.to_ascii_uppercase() {
+        l @ b'A'..=b'Z' => Some(LETTERS[usize::from(l - b'A')]),
+        d @ b'0'..=b'9' => Some(DIGITS[usize::from(d - b'0')]),
+        _ => None,
+    }
+}
+
+/// Every [`Segment`] needed to send `text`.
+///
+/// Spaces become word gaps; other characters without a Morse pattern are
+/// skipped. No trailing silence is produced.
+pub fn segments(text: &[u8]) -> Segments<'_> {
+    Segments {
+        text: text.iter(),
+        elements: [].iter(),
+        started: false,
+        gap: 0,
+        next_on: None,
+    }
+}
+
+/// Iterator returned by [`segments`].
+#[derive(Debug, Clone)]
+pub struct Segments<'a> {
+    text: core::slice::Iter<'a, u8>,
+    /// Dots and dashes left in the current letter.
+    elements: core::slice::Iter<'static, u8>,
+    started: bool,
+    /// Silence due before the next element.
+    gap: u8,
+    /// Element due after the gap just returned.
+    next_on: Option<Segment>,
+}
+
+impl Iterator for Segments<'_> {
+    type Item = Segment;
+
+    fn next(&mut self) -> Option<Segment> {
+        if let Some(on) = self.next_on.take() {
+            return Some(on);
+        }
+        loop {
+            if let Some(&element) = self.elements.next() {
+                let units = if element == b'-' { 3 } else { 1 };
+                let on = Segment { on: true, units };
+                let gap = core::mem::replace(&mut self.gap, 1);
+                if !core::mem::replace(&mut self.started, true) {
+                    return Some(on);
+                }
+                self.next_on = Some(on);
+                return Some(Segment {
+                    on: false,
+                    units: gap,
+                });
+            }
+            let &c = self.text.next()?;
+            if c == b' ' {
+                self.gap = 7;
+            } else if let Some(elements) = pattern(c) {
+                self.gap = self.gap.max(3);
+                self.elements = elements.iter();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(text: &[u8]) -> Vec<(bool, u8)> {
+        segments(text).map(|s| (s.on, s.units)).collect()
+    }
+
+    #[test]
+    fn test_sos_pattern() {
+        #[rustfmt::skip]
+        let expected = [
+            (true, 1), (false, 1), (true, 1), (false, 1), (true, 1),
+            (false, 3),
+            (true, 3), (false, 1), (true, 3), (false, 1), (true, 3),
+            (false, 3),
+            (true, 1), (false, 1), (true, 1), (false, 1), (true, 1),
+        ];
+        assert_eq!(collect(SOS), expected);
+    }
+
+    #[test]
+    fn test_word_gap_and_unknown_characters() {
+        assert_eq!(collect(b"e? t"), [(true, 1), (false, 7), (true, 3)]);
+        assert!(collect(b" ?").is_empty());
+    }
+}
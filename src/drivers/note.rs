@@ -0,0 +1,71 @@
+//! Musical notes in twelve-tone equal temperament.
+
+use crate::util::math;
+
+/// Pitch class within an octave. Flats are spelled as the equivalent sharp
+/// (B♭ is [`Pitch::ASharp`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pitch {
+    C,
+    CSharp,
+    D,
+    DSharp,
+    E,
+    F,
+    FSharp,
+    G,
+    GSharp,
+    A,
+    ASharp,
+    B,
+}
+
+/// A note as a MIDI number: 60 is middle C (C4), 69 is A4 = 440 Hz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Note(pub u8);
+
+impl Note {
+    /// Concert A, 440 Hz.
+    pub const A4: Note = Note(69);
+
+    /// `pitch` in scientific octave `octave` (C4 is middle C), saturating at
+    /// MIDI 127 (G9) like [`Note::up`].
+    pub const fn new(pitch: Pitch, octave: u8) -> Self {
+        let midi = 12 * (octave as u16 + 1) + pitch as u16;
+        Note(if midi > 127 { 127 } else { midi as u8 })
+    }
+
+    /// The note `semitones` higher, saturating at MIDI 127.
+    pub const fn up(self, semitones: u8) -> Self {
+        let midi = self.0.saturating_add(semitones);
+        Note(if midi > 127 { 127 } else { midi })
+    }
+
+    /// Frequency rounded to the nearest hertz.
+    pub fn frequency_hz(self) -> u32 {
+        let semitones = f32::from(self.0) - f32::from(Note::A4.0);
+        math::round_u32(440.0 * math::exp2(semitones / 12.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_frequencies() {
+        assert_eq!(Note::new(Pitch::A, 4), Note::A4);
+        assert_eq!(Note::A4.frequency_hz(), 440);
+        assert_eq!(Note::new(Pitch::C, 4).frequency_hz(), 262);
+        assert_eq!(Note::new(Pitch::A, 5).frequency_hz(), 880);
+        assert_eq!(Note(127).up(5), Note(127));
+    }
+
+    #[test]
+    fn test_new_saturates_above_g9() {
+        assert_eq!(Note::new(Pitch::G, 9), Note(127));
+        assert_eq!(Note::new(Pitch::GSharp, 9), Note(127));
+        assert_eq!(Note::new(Pitch::B, 20), Note(127));
+        assert_eq!(Note::new(Pitch::B, u8::MAX), Note(127));
+    }
+}
@@ -0,0 +1,168 @@
+//! Bit-banged 74HC595 serial-in, parallel-out shift register.
+//!
+//! Adds eight outputs per chip for three MCU pins, e.g. to drive an LED bar
+//! wider than the free pins allow. Chips can be daisy-chained (QH' into the
+//! next SER) and written in one go with [`Sr595::write_slice`]. For a bar
+//! gauge, feed it [`bar_byte`](super::led_bar::bar_byte) instead of using
+//! [`LedBar`](super::led_bar::LedBar) pins.
+
+use embedded_hal::digital::OutputPin;
+
+/// 74HC595 driven by three output pins.
+///
+/// Bits are shifted most-significant first, so bit 7 of a byte lands on QH
+/// and bit 0 on QA. The outputs only change when the latch (RCLK) pulses,
+/// after the whole byte or slice is shifted in, so there is no flicker.
+/// Pin errors are ignored, like elsewhere in the output drivers.
+pub struct Sr595<D, C, L>
+where
+    D: OutputPin,
+    C: OutputPin,
+    L: OutputPin,
+{
+    data: D,
+    clock: C,
+    latch: L,
+}
+
+impl<D, C, L> Sr595<D, C, L>
+where
+    D: OutputPin,
+    C: OutputPin,
+    L: OutputPin,
+{
+    /// Create a new [`Sr595`], leaving the clock and latch low.
+    ///
+    /// * `data`  – SER (pin 14)
+    /// * `clock` – SRCLK (pin 11), shifts on the rising edge
+    /// * `latch` – RCLK (pin 12), copies the shift register to the outputs
+    pub fn new(data: D, mut clock: C, mut latch: L) -> Self {
+        let _ = clock.set_low();
+        let _ = latch.set_low();
+        Self { data, clock, latch }
+    }
+
+    /// Shift out `byte` and latch it onto the outputs.
+    pub fn write_u8(&mut self, byte: u8) {
+        self.shift(byte);
+        self.pulse_latch();
+    }
+
+    /// Shift out every byte of `bytes` and latch them together.
+    ///
+    /// The first byte ends up in the chip furthest from the MCU in a
+    /// daisy chain, the last byte in the chip wired to `data`.
+    pub fn write_slice(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.shift(byte);
+        }
+        self.pulse_latch();
+    }
+
+    /// Consume the driver and return the data, clock and latch pins.
+    pub fn release(self) -> (D, C, L) {
+        (self.data, self.clock, self.latch)
+    }
+
+    fn shift(&mut self, byte: u8) {
+        for bit in (0..8).rev() {
+            let _ = if byte & (1 << bit) != 0 {
+                self.data.set_high()
+            } else {
+                self.data.set_low()
+            };
+            let _ = self.clock.set_high();
+            let _ = self.clock.set_low();
+        }
+    }
+
+    fn pulse_latch(&mut self) {
+        let _ = self.latch.set_high();
+        let _ = self.latch.set_low();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::RefCell;
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType;
+    use std::rc::Rc;
+
+    type Log = Rc<RefCell<Vec<(char, bool)>>>;
+
+    /// Pin that appends every level it is driven to, tagged with its name,
+    /// to a log shared with the other pins.
+    struct LogPin(char, Log);
+    impl ErrorType for LogPin {
+        type Error = Infallible;
+    }
+    impl OutputPin for LogPin {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.1.borrow_mut().push((self.0, false));
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.1.borrow_mut().push((self.0, true));
+            Ok(())
+        }
+    }
+
+    fn register(log: &Log) -> Sr595<LogPin, LogPin, LogPin> {
+        let sr = Sr595::new(
+            LogPin('d', log.clone()),
+            LogPin('c', log.clone()),
+            LogPin('l', log.clone()),
+        );
+        log.borrow_mut().clear();
+        sr
+    }
+
+    /// Replay `log` through a model 74HC595 chain, returning the bits seen on
+    /// each rising clock edge and the number of latch pulses.
+    fn decode(log: &Log) -> (Vec<bool>, usize) {
+        let (mut data, mut bits, mut latches) = (false, Vec::new(), 0);
+        for &(pin, level) in log.borrow().iter() {
+            match (pin, level) {
+                ('d', level) => data = level,
+                ('c', true) => bits.push(data),
+                ('l', true) => latches += 1,
+                _ => {}
+            }
+        }
+        (bits, latches)
+    }
+
+    #[test]
+    fn test_write_u8_shifts_msb_first_then_latches() {
+        let log = Log::default();
+        let mut sr = register(&log);
+        sr.write_u8(0b1010_0011);
+
+        let (bits, latches) = decode(&log);
+        assert_eq!(bits, [true, false, true, false, false, false, true, true]);
+        assert_eq!(latches, 1);
+        // The latch comes after the last clock pulse.
+        assert_eq!(
+            log.borrow()[log.borrow().len() - 2..],
+            [('l', true), ('l', false)]
+        );
+    }
+
+    #[test]
+    fn test_write_slice_latches_once() {
+        let log = Log::default();
+        let mut sr = register(&log);
+        sr.write_slice(&[0xFF, 0x01]);
+
+        let (bits, latches) = decode(&log);
+        assert_eq!(bits.len(), 16);
+        assert!(bits[..8].iter().all(|&b| b));
+        assert_eq!(
+            bits[8..],
+            [false, false, false, false, false, false, false, true]
+        );
+        assert_eq!(latches, 1);
+    }
+}
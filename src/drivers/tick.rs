@@ -0,0 +1,56 @@
+//! Monotonic millisecond time source.
+
+/// Free-running millisecond counter.
+///
+/// The counter wraps at `u32::MAX` (~49 days); compare instants with
+/// `wrapping_sub` rather than `<`.
+pub trait TickSource {
+    /// Milliseconds since an arbitrary, fixed starting point.
+    fn now_ms(&self) -> u32;
+}
+
+/// Lets several drivers share one clock, e.g. `AsyncBuzzer::new(pwm, &clock)`.
+impl<T: TickSource + ?Sized> TickSource for &T {
+    fn now_ms(&self) -> u32 {
+        (**self).now_ms()
+    }
+}
+
+/// Instant on a [`TickSource`] clock at which something should happen.
+///
+/// Compared with wrapping arithmetic, so deadlines up to ~24 days ahead work
+/// across the counter wrap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline(u32);
+
+impl Deadline {
+    /// The instant `duration_ms` after `now_ms`.
+    pub const fn after(now_ms: u32, duration_ms: u32) -> Self {
+        Self(now_ms.wrapping_add(duration_ms))
+    }
+
+    /// The deadline as a [`TickSource::now_ms`] value.
+    pub const fn at_ms(self) -> u32 {
+        self.0
+    }
+
+    /// `true` once `now_ms` has reached the deadline.
+    pub const fn is_due(self, now_ms: u32) -> bool {
+        (now_ms.wrapping_sub(self.0) as i32) >= 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline_due_across_wrap() {
+        let deadline = Deadline::after(u32::MAX - 10, 20);
+        assert_eq!(deadline.at_ms(), 9);
+        assert!(!deadline.is_due(u32::MAX));
+        assert!(!deadline.is_due(8));
+        assert!(deadline.is_due(9));
+        assert!(deadline.is_due(1_000));
+    }
+}
@@ -0,0 +1,46 @@
+//! Small helpers shared by the PWM-based drivers.
+
+/// Compare value for `percent` of `max`, rounded to the nearest step.
+///
+/// Used for buzzer volume and LED brightness alike. Percentages above 100
+/// count as 100.
+///
+/// In fast PWM `max` follows the period, so it shrinks as the pitch rises:
+/// the ATmega16 timer has 250 steps at 1 kHz but only about 30 at 8 kHz,
+/// where one step is over 3%. Rounding keeps the duty close to what was
+/// asked, and any non-zero percentage gets at least one step so a quiet cue
+/// or a dim LED never turns silently off.
+pub fn duty_from_percent(max: u16, percent: u8) -> u16 {
+    if percent == 0 || max == 0 {
+        return 0;
+    }
+    // Ties round down so u16::MAX / 2 stays the exact 50% value.
+    let duty = (u32::from(max) * u32::from(percent.min(100)) + 49) / 100;
+    duty.max(1) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duty_from_percent_rounds_to_nearest_step() {
+        assert_eq!(duty_from_percent(100, 33), 33);
+        assert_eq!(duty_from_percent(249, 50), 124);
+        // 10% of 7 is 0.7 and 30% is 2.1: round instead of truncating.
+        assert_eq!(duty_from_percent(7, 10), 1);
+        assert_eq!(duty_from_percent(7, 30), 2);
+        assert_eq!(duty_from_percent(7, 60), 4);
+        // Too small to represent, but still on.
+        assert_eq!(duty_from_percent(7, 1), 1);
+        assert_eq!(duty_from_percent(7, 0), 0);
+        assert_eq!(duty_from_percent(0, 50), 0);
+        assert_eq!(duty_from_percent(u16::MAX, 50), u16::MAX / 2);
+    }
+
+    #[test]
+    fn test_duty_from_percent_clamps_above_100() {
+        assert_eq!(duty_from_percent(100, 101), 100);
+        assert_eq!(duty_from_percent(u16::MAX, 255), u16::MAX);
+    }
+}
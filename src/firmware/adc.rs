@@ -0,0 +1,58 @@
+use crate::drivers::adc::AdcChannel;
+use avr_device::atmega16;
+use core::convert::Infallible;
+
+/// Firmware-side driver for the ATmega16 10-bit ADC.
+///
+/// Uses AVcc as the reference and a clk/128 prescaler (125 kHz at 16 MHz,
+/// inside the 50–200 kHz range needed for full resolution).
+pub struct AvrAdc {
+    adc: atmega16::ADC,
+}
+
+impl AvrAdc {
+    /// Wrap the PAC `ADC` peripheral and enable the converter.
+    pub fn new(adc: atmega16::ADC) -> AvrAdc {
+        adc.admux.modify(|_, w| w.refs().avcc().adlar().clear_bit());
+        adc.adcsra.modify(|_, w| {
+            w.aden()
+                .set_bit()
+                .adate()
+                .clear_bit()
+                .adps()
+                .prescaler_128()
+        });
+        AvrAdc { adc }
+    }
+
+    /// Run one blocking conversion on single-ended channel `channel` (`0..8`).
+    pub fn read_channel(&mut self, channel: u8) -> u16 {
+        self.adc.admux.modify(|_, w| w.mux().bits(channel & 0x07));
+        self.adc.adcsra.modify(|_, w| w.adsc().set_bit());
+        while self.adc.adcsra.read().adsc().bit_is_set() {}
+        self.adc.adc.read().bits()
+    }
+
+    /// Borrow single-ended channel `channel` as an [`AdcChannel`].
+    pub fn channel(&mut self, channel: u8) -> AvrAdcChannel<'_> {
+        AvrAdcChannel { adc: self, channel }
+    }
+}
+
+/// One input pin of an [`AvrAdc`], e.g. a potentiometer on `ADC0` (PA0).
+pub struct AvrAdcChannel<'a> {
+    adc: &'a mut AvrAdc,
+    channel: u8,
+}
+
+impl AdcChannel for AvrAdcChannel<'_> {
+    type Error = Infallible;
+
+    fn full_scale(&self) -> u16 {
+        1023
+    }
+
+    fn read(&mut self) -> Result<u16, Infallible> {
+        Ok(self.adc.read_channel(self.channel))
+    }
+}
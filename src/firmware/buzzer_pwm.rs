@@ -1,89 +1,684 @@
+use super::F_CPU;
 use crate::drivers::buzzer::SetFrequency;
 use avr_device::{atmega16, interrupt};
 use core::convert::Infallible;
 use embedded_hal::pwm::{ErrorType, SetDutyCycle};
 
-/// MCU clock (Hz). Used to derive the OCR value from a target frequency.
+/// `COM1B1:0` field of `TCCR1A` (bits 5:4).
+const COM1B_MASK: u8 = 0b0011_0000;
+/// `COM1B = 0b01`: toggle OC1B on compare match (square wave on PD4).
+const COM1B_TOGGLE: u8 = 0b0001_0000;
+/// `COM1B = 0b10`: clear OC1B on compare match, set at BOTTOM (non-inverting PWM).
+const COM1B_PWM: u8 = 0b0010_0000;
+/// `WGM11:10` field of `TCCR1A` (bits 1:0).
+const WGM1_LOW_MASK: u8 = 0b0000_0011;
+/// `WGM13:12` field of `TCCR1B` (bits 4:3).
+const WGM1_HIGH_MASK: u8 = 0b0001_1000;
+/// `CS12:10` field of `TCCR1B` (bits 2:0).
+const CS1_MASK: u8 = 0b0000_0111;
+/// `CS12:10` value selecting [`PRESCALER`].
+const CS1_CLOCK: u8 = cs1_for(PRESCALER);
+
+/// Timer1 clock divider, written to `CS12:10` as [`CS1_CLOCK`] and used by
+/// all the frequency maths.
+///
+/// clk/64 gives 2 Hz to 125 kHz in [`ToneMode::Ctc`] at 16 MHz, so every
+/// cue from a slow click to an ultrasonic test tone fits `OCR1A`. The cost
+/// is resolution at high pitches: a 5 kHz cue is only 25 ticks long, so
+/// pitches up to there land within 4% (under a semitone) of the request.
+const PRESCALER: u32 = 64;
+
+/// `CS12:10` value for a Timer1 clock divider (datasheet "Clock Select Bit
+/// Description"); other dividers fail to compile.
+const fn cs1_for(prescaler: u32) -> u8 {
+    match prescaler {
+        1 => 0b001,
+        8 => 0b010,
+        64 => 0b011,
+        256 => 0b100,
+        1_024 => 0b101,
+        _ => panic!("Timer1 has no such prescaler"),
+    }
+}
+
+/// Output periods timed by [`BuzzerPwm::measure_output_hz`].
+const MEASURE_PERIODS: u32 = 8;
+
+/// Fewest CPU cycles one polling pass of [`BuzzerPwm::measure_output_hz`]
+/// can take (two I/O reads, a branch and the loop jump), so a poll cap
+/// derived from it never ends a running measurement early.
+const MIN_POLL_CYCLES: u32 = 8;
+
+/// Timer1 waveform used to drive the buzzer on OC1B.
 ///
-/// Change this to match your actual fuse/clock configuration.
-const F_CPU: u32 = 16_000_000;
+/// Both modes use `OCR1A` as TOP, so [`SetFrequency::set_frequency`] always
+/// writes `OCR1A`; they differ in how OC1B follows the counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMode {
+    /// CTC (WGM1 = 4) with OC1B toggling on compare match: a 50% square wave
+    /// at `F_CPU / (2 · 64 · (1 + OCR1A))`. Loudest, but duty (volume) is
+    /// fixed; any non-zero duty just switches the output on.
+    Ctc,
+    /// Fast PWM (WGM1 = 15) with non-inverting OC1B: period
+    /// `F_CPU / (64 · (1 + OCR1A))` and pulse width `OCR1B`, so duty controls
+    /// volume.
+    FastPwm,
+}
+
+impl ToneMode {
+    /// `(WGM11:10, WGM13:12)` for this mode.
+    fn wgm(self) -> (u8, u8) {
+        match self {
+            ToneMode::Ctc => (0b00, 0b01),
+            ToneMode::FastPwm => (0b11, 0b11),
+        }
+    }
+
+    /// `COM1B` bits that connect OC1B in this mode.
+    fn com1b(self) -> u8 {
+        match self {
+            ToneMode::Ctc => COM1B_TOGGLE,
+            ToneMode::FastPwm => COM1B_PWM,
+        }
+    }
+
+    /// Timer ticks per output period, per unit of `1 + OCR1A`, divided by the
+    /// prescaler: 2 for toggling, 1 for PWM.
+    fn periods(self) -> u32 {
+        match self {
+            ToneMode::Ctc => 2,
+            ToneMode::FastPwm => 1,
+        }
+    }
+}
 
 /// Firmware-side buzzer PWM controller.
 ///
-/// Holds the PAC handle to `TC1` and caches the current maximum duty `TOP`.  
+/// Holds the PAC handle to `TC1` and caches the current maximum duty `TOP`.
 /// Implements both `SetDutyCycle` and `SetFrequency` so you can drive it through the
 /// generic HAL `Buzzer` or directly if desired.
 pub struct BuzzerPwm {
     tc1: atmega16::TC1,
     max: u16,
+    stop_clock_on_idle: bool,
+    mode: ToneMode,
 }
 
 impl BuzzerPwm {
-    /// Take the peripherals, set PD4 (OC1B) as output, put Timer1 into CTC mode with a
-    /// 1/64 prescaler, and initialise `OCR1A` to 0.
+    /// Take the peripherals, set PD4 (OC1B) as output, put Timer1 into
+    /// [`ToneMode::Ctc`] with a 1/64 prescaler, and initialise `OCR1A` to 0.
     ///
-    /// Returns a fully-initialised [`BuzzerFw`].
+    /// Returns a fully-initialised [`BuzzerPwm`].
     ///
     /// # Notes
-    /// - The magic value `(1 << 3) | (1 << 1)` sets:  
-    ///   - bit 3 → WGM12 = 1 (CTC mode)  
-    ///   - bit 1 → CS11 = 1 (prescaler /64 with CS10 = 0, CS12 = 0)  
-    ///   If you change mode/prescaler, update those bits or switch to the generated field
-    ///   setters (`wgm3().bits(..)`, etc.) for clarity.
+    /// - Control registers (`DDRD`, `PORTD`, `TCCR1A/B`) are updated with
+    ///   read-modify-write so only the PD4 bit and the `WGM1`/`COM1B`/`CS1` fields
+    ///   change. Other users of PORTD or TC1 (e.g. input capture) keep their
+    ///   configuration.
+    /// - `OCR1A` is a plain 16-bit data register with no shared bits, so it is
+    ///   written directly.
     pub fn new() -> BuzzerPwm {
         interrupt::free(|_| {
             let dp = atmega16::Peripherals::take().unwrap();
-            let portd = dp.PORTD;
-            let tc1 = dp.TC1;
+            Self::init(dp.TC1, &dp.PORTD)
+        })
+    }
+
+    /// Build a [`BuzzerPwm`] from stolen peripherals, for use where
+    /// [`atmega16::Peripherals::take`] may already have been called (e.g. a
+    /// panic handler).
+    ///
+    /// # Safety
+    /// Any other owner of `TC1` or `PORTD` must never run again, e.g. because
+    /// interrupts are disabled and control will not return to it.
+    pub unsafe fn steal() -> BuzzerPwm {
+        // SAFETY: upheld by the caller.
+        let dp = unsafe { atmega16::Peripherals::steal() };
+        Self::init(dp.TC1, &dp.PORTD)
+    }
+
+    fn init(tc1: atmega16::TC1, portd: &atmega16::PORTD) -> BuzzerPwm {
+        // PD4 = OC1B pin (datasheet). Make it an output and drive low.
+        portd.ddrd.modify(|_, w| w.pd4().set_bit());
+        portd.portd.modify(|_, w| w.pd4().clear_bit());
 
-            // PD4 = OC1B pin (datasheet). Make it an output and drive low.
-            portd.ddrd.write(|w| w.pd4().set_bit());
-            portd.portd.write(|w| w.pd4().clear_bit());
+        // Start the clk/64 prescaler. ICNC1/ICES1 are left untouched for
+        // input capture.
+        // SAFETY: only the CS1 field changes; every other bit is written
+        // back as read.
+        tc1.tccr1b
+            .modify(|r, w| unsafe { w.bits(clock_bits(r.bits())) });
 
-            // TCCR1B: CTC mode (WGM12 = 1), prescaler = clk/64 (CS11 = 1, CS10 = 0, CS12 = 0)
-            tc1.tccr1b.write(|w| unsafe { w.bits((1 << 3) | (1 << 1)) });
+        // Start with 0 in OCR1A, and in OCR1B so a pulse width left by
+        // earlier fast-PWM use cannot sit above the CTC TOP (see `ocr1b_for`).
+        tc1.ocr1a.write(|w| w.bits(0));
+        tc1.ocr1b.write(|w| w.bits(0));
+
+        let mut pwm = BuzzerPwm {
+            tc1,
+            max: u16::MAX,
+            stop_clock_on_idle: true,
+            mode: ToneMode::Ctc,
+        };
+        pwm.set_mode(ToneMode::Ctc);
+        pwm
+    }
+
+    /// Switch the Timer1 waveform between loud fixed-volume CTC and
+    /// volume-controllable fast PWM.
+    ///
+    /// # Notes
+    /// - Rewrites `WGM11:10` in `TCCR1A` and `WGM13:12` in `TCCR1B`, and clears
+    ///   `COM1B` so the output is silent until the next non-zero duty.
+    /// - Entering [`ToneMode::Ctc`] zeroes `OCR1B`, so a fast-PWM pulse width
+    ///   cannot leave the toggle match above TOP.
+    /// - The two modes divide the clock differently, so call
+    ///   [`SetFrequency::set_frequency`] again after switching.
+    pub fn set_mode(&mut self, mode: ToneMode) {
+        self.mode = mode;
+        let top = self.tc1.ocr1a.read().bits();
+        self.max = max_duty_for(top, mode);
+        self.fit_ocr1b(top);
+        interrupt::free(|_| {
+            let (a, b) = mode_bits(
+                self.tc1.tccr1a.read().bits(),
+                self.tc1.tccr1b.read().bits(),
+                mode,
+            );
+            // SAFETY: only the WGM1 and COM1B fields change; every other bit
+            // is written back as read.
+            self.tc1.tccr1a.write(|w| unsafe { w.bits(a) });
+            self.tc1.tccr1b.write(|w| unsafe { w.bits(b) });
+        });
+    }
+
+    /// The current waveform mode.
+    pub fn mode(&self) -> ToneMode {
+        self.mode
+    }
 
-            // Start with 0 in OCR1A
-            tc1.ocr1a.write(|w| w.bits(0));
+    /// Bring `OCR1B` in line with `top` for the current mode; see
+    /// [`ocr1b_for`].
+    fn fit_ocr1b(&mut self, top: u16) {
+        let ocr1b = self.tc1.ocr1b.read().bits();
+        let fitted = ocr1b_for(ocr1b, top, self.mode);
+        if fitted != ocr1b {
+            self.tc1.ocr1b.write(|w| w.bits(fitted));
+        }
+    }
+
+    /// Connect (`on`) or disconnect OC1B without touching the rest of TCCR1A.
+    fn connect_output(&mut self, on: bool) {
+        let mode = self.mode;
+        interrupt::free(|_| {
+            let a = com1b_bits(self.tc1.tccr1a.read().bits(), mode, on);
+            // SAFETY: only the COM1B field changes.
+            self.tc1.tccr1a.write(|w| unsafe { w.bits(a) });
+        });
+    }
+
+    /// Measure the tone actually reaching the transducer with Timer1 input
+    /// capture, and check it against the frequency set in `OCR1A`.
+    ///
+    /// Times eight periods between rising edges on ICP1 and returns the measured
+    /// frequency, or [`MeasureError::Mismatch`] if it is more than
+    /// `tolerance_percent` away from the commanded one. Call it while a tone
+    /// is playing, e.g. after [`Buzzer::start_tone`]; with no edges within
+    /// about four times the expected time it returns
+    /// [`MeasureError::NoSignal`]. That timeout is also bounded by a count of
+    /// polls, so it still returns if the Timer1 clock is stopped (see
+    /// [`set_stop_clock_on_idle`](Self::set_stop_clock_on_idle)).
+    ///
+    /// # Wiring
+    /// ICP1 is PD6. Feed it a logic-level copy of the signal at the
+    /// transducer (the amplifier output through a divider, or a pickup coil
+    /// and comparator), not PD4 itself, so that a broken amplifier or open
+    /// coil shows up as a missing or wrong signal. PD6 must be an input,
+    /// which it is after reset.
+    ///
+    /// # Notes
+    /// - Sets `ICES1` (capture on rising edges) in `TCCR1B`; `ICNC1` is left
+    ///   as configured.
+    /// - Polls `ICF1` and `OCF1A` in `TIFR` with interrupts enabled, so Timer0
+    ///   keeps time; a handler running longer than one timer period can make
+    ///   the measurement read low.
+    ///
+    /// [`Buzzer::start_tone`]: crate::drivers::buzzer::Buzzer::start_tone
+    pub fn measure_output_hz(&mut self, tolerance_percent: u8) -> Result<u32, MeasureError> {
+        let period = u32::from(self.tc1.ocr1a.read().bits()) + 1;
+        let max_wraps = 4 * (MEASURE_PERIODS + 1) * self.mode.periods();
+        let max_polls = poll_limit(max_wraps, period);
+        self.tc1.tccr1b.modify(|_, w| w.ices1().set_bit());
+        self.tc1
+            .tifr
+            .write(|w| w.icf1().set_bit().ocf1a().set_bit());
+
+        let mut wraps = 0u32;
+        let mut first: Option<u64> = None;
+        let mut edges = 0u32;
+        for _ in 0..max_polls {
+            let flags = self.tc1.tifr.read();
+            let wrapped = flags.ocf1a().bit_is_set();
+            if wrapped {
+                self.tc1.tifr.write(|w| w.ocf1a().set_bit());
+            }
+            if flags.icf1().bit_is_set() {
+                let icr = self.tc1.icr1.read().bits();
+                self.tc1.tifr.write(|w| w.icf1().set_bit());
+                let at = capture_ticks(wraps, wrapped, icr, period);
+                match first {
+                    None => first = Some(at),
+                    Some(start) => {
+                        edges += 1;
+                        if edges == MEASURE_PERIODS {
+                            let measured = ticks_to_hz(at - start, edges);
+                            let expected = commanded_hz(period, self.mode);
+                            return check_tolerance(measured, expected, tolerance_percent);
+                        }
+                    }
+                }
+            }
+            if wrapped {
+                wraps += 1;
+                if wraps > max_wraps {
+                    return Err(MeasureError::NoSignal);
+                }
+            }
+        }
+        Err(MeasureError::NoSignal)
+    }
+
+    /// Choose whether [`SetFrequency::standby`] also stops the Timer1 clock.
+    ///
+    /// Stopping the clock saves the most power but also halts OC1A and input
+    /// capture, so disable this if anything else shares TC1. Defaults to `true`.
+    pub fn set_stop_clock_on_idle(&mut self, stop: bool) {
+        self.stop_clock_on_idle = stop;
+    }
+}
+
+/// `TCCR1A`/`TCCR1B` values that disconnect OC1B and, if `stop_clock`, select
+/// "no clock". All other bits are preserved.
+fn standby_bits(tccr1a: u8, tccr1b: u8, stop_clock: bool) -> (u8, u8) {
+    let tccr1b = if stop_clock {
+        tccr1b & !CS1_MASK
+    } else {
+        tccr1b
+    };
+    (tccr1a & !COM1B_MASK, tccr1b)
+}
+
+/// `TCCR1B` value that runs Timer1 at [`PRESCALER`]. All other bits are
+/// preserved.
+fn clock_bits(tccr1b: u8) -> u8 {
+    (tccr1b & !CS1_MASK) | CS1_CLOCK
+}
+
+/// `TCCR1A`/`TCCR1B` values that reconnect OC1B for `mode` and restart the
+/// clk/64 prescaler. All other bits are preserved.
+fn resume_bits(tccr1a: u8, tccr1b: u8, mode: ToneMode) -> (u8, u8) {
+    (com1b_bits(tccr1a, mode, true), clock_bits(tccr1b))
+}
+
+/// `TCCR1A`/`TCCR1B` values selecting `mode`'s waveform with OC1B
+/// disconnected. All other bits are preserved.
+fn mode_bits(tccr1a: u8, tccr1b: u8, mode: ToneMode) -> (u8, u8) {
+    let (low, high) = mode.wgm();
+    (
+        (tccr1a & !(WGM1_LOW_MASK | COM1B_MASK)) | low,
+        (tccr1b & !WGM1_HIGH_MASK) | (high << 3),
+    )
+}
+
+/// `OCR1B` value that keeps OC1B working once TOP is `top` in `mode`.
+///
+/// In CTC OC1B toggles only when the counter equals `OCR1B`, so a value left
+/// above TOP (e.g. a fast-PWM duty at a lower pitch) is never reached and the
+/// buzzer goes silent; CTC always uses 0. In fast PWM a width above TOP just
+/// holds the output high, so it is clamped to 100%.
+fn ocr1b_for(ocr1b: u16, top: u16, mode: ToneMode) -> u16 {
+    match mode {
+        ToneMode::Ctc => 0,
+        ToneMode::FastPwm => ocr1b.min(top),
+    }
+}
+
+/// `TCCR1A` with `COM1B` set for `mode` (`on`) or cleared.
+fn com1b_bits(tccr1a: u8, mode: ToneMode, on: bool) -> u8 {
+    let com1b = if on { mode.com1b() } else { 0 };
+    (tccr1a & !COM1B_MASK) | com1b
+}
 
-            BuzzerPwm { tc1, max: u16::MAX }
+/// `OCR1A` value for `hz` in `mode`, or why it does not fit the 16-bit timer.
+///
+/// The period in timer ticks is computed in `u32` and range-checked before
+/// narrowing, so deep tones are rejected instead of silently wrapping.
+fn top_for(hz: u32, mode: ToneMode) -> Result<u16, FrequencyError> {
+    if hz == 0 {
+        return Err(FrequencyError::TooLow);
+    }
+    let ticks = (F_CPU / (PRESCALER * mode.periods())) / hz;
+    match ticks {
+        0 => Err(FrequencyError::TooHigh),
+        t if t > u32::from(u16::MAX) + 1 => Err(FrequencyError::TooLow),
+        t => Ok((t - 1) as u16),
+    }
+}
+
+/// Timer ticks from the start of a measurement to a capture of `icr`,
+/// `wraps` TOP matches later.
+///
+/// `wrapped` is set when the counter also reached TOP since the last poll;
+/// a capture low in the count then came after that wrap, a high one before.
+fn capture_ticks(wraps: u32, wrapped: bool, icr: u16, period: u32) -> u64 {
+    let after_wrap = wrapped && u32::from(icr) < period / 2;
+    u64::from(wraps + u32::from(after_wrap)) * u64::from(period) + u64::from(icr)
+}
+
+/// Polls of `TIFR` that take at least as long as `max_wraps` timer periods
+/// of `period` clk/64 counts, counted on the CPU clock rather than Timer1.
+fn poll_limit(max_wraps: u32, period: u32) -> u32 {
+    let cycles = u64::from(max_wraps) * u64::from(period) * u64::from(PRESCALER);
+    (cycles / u64::from(MIN_POLL_CYCLES)).min(u64::from(u32::MAX)) as u32
+}
+
+/// Frequency of `edges` periods spanning `ticks` clk/64 counts.
+fn ticks_to_hz(ticks: u64, edges: u32) -> u32 {
+    let rate = u64::from(F_CPU / PRESCALER) * u64::from(edges);
+    ((rate + ticks / 2) / ticks.max(1)) as u32
+}
+
+/// Output frequency for a TOP `period - 1` in `OCR1A` under `mode`.
+fn commanded_hz(period: u32, mode: ToneMode) -> u32 {
+    ticks_to_hz(u64::from(period * mode.periods()), 1)
+}
+
+/// `Ok(measured)` if it is within `tolerance_percent` of `expected`.
+fn check_tolerance(
+    measured: u32,
+    expected: u32,
+    tolerance_percent: u8,
+) -> Result<u32, MeasureError> {
+    let allowed = u64::from(expected) * u64::from(tolerance_percent) / 100;
+    if u64::from(measured.abs_diff(expected)) <= allowed {
+        Ok(measured)
+    } else {
+        Err(MeasureError::Mismatch {
+            measured_hz: measured,
+            expected_hz: expected,
         })
     }
 }
 
+/// [`SetDutyCycle::max_duty_cycle`] for `top` in `OCR1A` under `mode`.
+fn max_duty_for(top: u16, mode: ToneMode) -> u16 {
+    match mode {
+        ToneMode::FastPwm => top,
+        ToneMode::Ctc => u16::MAX,
+    }
+}
+
+/// A frequency [`BuzzerPwm`] cannot produce with its clk/64 prescaler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrequencyError {
+    /// Below ~2 Hz in [`ToneMode::Ctc`] or ~4 Hz in [`ToneMode::FastPwm`]
+    /// (the period would not fit `OCR1A`), or 0 Hz.
+    TooLow,
+    /// Above 125 kHz in CTC or 250 kHz in fast PWM.
+    TooHigh,
+}
+
+/// Why [`BuzzerPwm::measure_output_hz`] rejected the buzzer output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeasureError {
+    /// No edges reached ICP1: the output, amplifier or feedback line is dead.
+    NoSignal,
+    /// The output oscillates at the wrong frequency.
+    Mismatch {
+        /// Frequency seen on ICP1.
+        measured_hz: u32,
+        /// Frequency set in `OCR1A`.
+        expected_hz: u32,
+    },
+}
+
 impl ErrorType for BuzzerPwm {
     type Error = Infallible;
 }
 
 impl SetFrequency for BuzzerPwm {
-    type Error = Infallible;
+    type Error = FrequencyError;
 
     /// Set the output frequency in Hz.
     ///
-    /// - `128` is effectively `prescaler (64) * 2`, because in toggle/CTC the output period
-    ///   is 2 * OCR1A cycles. If you change mode or prescaler, update this constant.
-    fn set_frequency(&mut self, hz: u32) -> Result<(), Infallible> {
-        let top = ((F_CPU / 128) / hz).saturating_sub(1) as u16;
-        self.tc1.ocr1a.write(|w| w.bits(top));
+    /// - The divisor is `prescaler (64) * 2` in [`ToneMode::Ctc`], because a toggled
+    ///   output period is two compare matches, and `64` in [`ToneMode::FastPwm`].
+    /// - `OCR1A` is written, and `OCR1B` if it no longer fits the new TOP (see
+    ///   [`BuzzerPwm::set_mode`]); the mode and prescaler bits in `TCCR1A/B`
+    ///   are left as-is.
+    /// - In [`ToneMode::FastPwm`] the new TOP becomes
+    ///   [`SetDutyCycle::max_duty_cycle`], so percentages stay exact.
+    /// - Out-of-range frequencies return an error and leave `OCR1A` unchanged.
+    /// - A frequency that rounds to the TOP already in `OCR1A` skips the
+    ///   write, so nearly identical cues do not reload the timer.
+    fn set_frequency(&mut self, hz: u32) -> Result<(), FrequencyError> {
+        let top = top_for(hz, self.mode)?;
+        if self.tc1.ocr1a.read().bits() != top {
+            self.tc1.ocr1a.write(|w| w.bits(top));
+        }
+        self.fit_ocr1b(top);
+        self.max = max_duty_for(top, self.mode);
         Ok(())
     }
+
+    /// Disconnect OC1B from PD4 and, by default, stop the Timer1 clock.
+    ///
+    /// With COM1B cleared PD4 falls back to its `PORTD` value, which
+    /// [`BuzzerPwm::new`] drives low, so the transducer sees no current.
+    fn standby(&mut self) {
+        let stop_clock = self.stop_clock_on_idle;
+        interrupt::free(|_| {
+            let (a, b) = standby_bits(
+                self.tc1.tccr1a.read().bits(),
+                self.tc1.tccr1b.read().bits(),
+                stop_clock,
+            );
+            // SAFETY: only the COM1B and CS1 fields change; every other bit
+            // is written back as read.
+            self.tc1.tccr1a.write(|w| unsafe { w.bits(a) });
+            self.tc1.tccr1b.write(|w| unsafe { w.bits(b) });
+        });
+    }
+
+    /// Reconnect OC1B for the current mode and restart the clk/64 prescaler.
+    fn resume(&mut self) {
+        let mode = self.mode;
+        interrupt::free(|_| {
+            let (a, b) = resume_bits(
+                self.tc1.tccr1a.read().bits(),
+                self.tc1.tccr1b.read().bits(),
+                mode,
+            );
+            // SAFETY: as in `standby`.
+            self.tc1.tccr1a.write(|w| unsafe { w.bits(a) });
+            self.tc1.tccr1b.write(|w| unsafe { w.bits(b) });
+        });
+    }
 }
 
 impl SetDutyCycle for BuzzerPwm {
-    /// Return the cached maximum duty value (`u16::MAX` in this simplified model).
+    /// Return the duty that gives a 100% pulse at the current frequency.
     ///
-    /// If you move to a mode where TOP is ICR1 or OCR1A, consider updating `self.max`
-    /// whenever you change TOP so this stays accurate.
+    /// In [`ToneMode::FastPwm`] this is the TOP last written to `OCR1A` by
+    /// [`SetFrequency::set_frequency`], so half of it really is half the
+    /// period. In [`ToneMode::Ctc`] duty only switches the output on or off,
+    /// so it stays `u16::MAX`.
     fn max_duty_cycle(&self) -> u16 {
         self.max
     }
 
-    /// Write a raw duty value.
+    /// Set the output level.
     ///
-    /// **Note:** In this CTC configuration OCR1A controls the period, so reusing it for
-    /// duty usually isn’t what you want. TODO for actual control.
+    /// `0` disconnects OC1B, silencing the buzzer in either mode. Otherwise
+    /// the output is connected; in [`ToneMode::FastPwm`] `duty` is also
+    /// written to `OCR1B` as the pulse width, while in [`ToneMode::Ctc`] the
+    /// value is ignored (the toggle output is always 50%).
     fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Infallible> {
-        self.tc1.ocr1a.write(|w| w.bits(duty));
+        if duty != 0 && self.mode == ToneMode::FastPwm {
+            self.tc1.ocr1b.write(|w| w.bits(duty));
+        }
+        self.connect_output(duty != 0);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standby_clears_com1b_and_clock_only() {
+        // COM1A = 0b10, COM1B = 0b01, WGM11:10 = 0b00; ICNC1, WGM12, clk/64.
+        let (a, b) = standby_bits(0b1001_0000, 0b1000_1011, true);
+        assert_eq!(a, 0b1000_0000);
+        assert_eq!(b, 0b1000_1000);
+
+        let (_, b) = standby_bits(0b1001_0000, 0b1000_1011, false);
+        assert_eq!(b, 0b1000_1011);
+    }
+
+    #[test]
+    fn test_resume_restores_output_and_prescaler() {
+        let (a, b) = resume_bits(0b1000_0000, 0b1000_1000, ToneMode::Ctc);
+        assert_eq!(a, 0b1001_0000);
+        assert_eq!(b, 0b1000_1011);
+        assert_eq!(resume_bits(a, b, ToneMode::Ctc), (a, b));
+
+        let (a, _) = resume_bits(0b1000_0011, 0b1001_1000, ToneMode::FastPwm);
+        assert_eq!(a, 0b1010_0011);
+    }
+
+    #[test]
+    fn test_mode_bits_for_ctc_and_fast_pwm() {
+        // Start from fast PWM with OC1B connected, COM1A = 0b10, ICNC1, clk/64.
+        let (a, b) = mode_bits(0b1010_0011, 0b1001_1011, ToneMode::Ctc);
+        assert_eq!(a, 0b1000_0000);
+        assert_eq!(b, 0b1000_1011);
+
+        let (a, b) = mode_bits(a, b, ToneMode::FastPwm);
+        assert_eq!(a, 0b1000_0011);
+        assert_eq!(b, 0b1001_1011);
+
+        assert_eq!(com1b_bits(a, ToneMode::FastPwm, true), 0b1010_0011);
+        assert_eq!(com1b_bits(0b1001_0000, ToneMode::Ctc, false), 0b1000_0000);
+    }
+
+    #[test]
+    fn test_fast_pwm_duty_does_not_silence_a_higher_ctc_tone() {
+        // 50% of 440 Hz in fast PWM, then a 1 kHz CTC tone.
+        let top = top_for(440, ToneMode::FastPwm).unwrap();
+        let ocr1b = max_duty_for(top, ToneMode::FastPwm) / 2;
+        let ctc_top = top_for(1_000, ToneMode::Ctc).unwrap();
+        assert!(ocr1b > ctc_top, "{ocr1b} vs {ctc_top}");
+
+        // Entering CTC, then retuning in it.
+        let ocr1b = ocr1b_for(ocr1b, top, ToneMode::Ctc);
+        let ocr1b = ocr1b_for(ocr1b, ctc_top, ToneMode::Ctc);
+        assert!(ocr1b <= ctc_top);
+
+        // Fast PWM widths above a new, lower TOP become 100%.
+        assert_eq!(ocr1b_for(284, 249, ToneMode::FastPwm), 249);
+        assert_eq!(ocr1b_for(100, 249, ToneMode::FastPwm), 100);
+    }
+
+    #[test]
+    fn test_fast_pwm_max_duty_tracks_top() {
+        let top = top_for(1_000, ToneMode::FastPwm).unwrap();
+        assert_eq!(max_duty_for(top, ToneMode::FastPwm), 249);
+        // Buzzer::tone's 50% is then exactly half the 250-tick period.
+        assert_eq!(
+            u32::from(max_duty_for(top, ToneMode::FastPwm)) * 50 / 100,
+            124
+        );
+        assert_eq!(max_duty_for(top, ToneMode::Ctc), u16::MAX);
+    }
+
+    #[test]
+    fn test_capture_to_frequency() {
+        // 1 kHz in fast PWM: TOP 249, one wrap per period.
+        let period = u32::from(top_for(1_000, ToneMode::FastPwm).unwrap()) + 1;
+        assert_eq!(commanded_hz(period, ToneMode::FastPwm), 1_000);
+        assert_eq!(commanded_hz(period, ToneMode::Ctc), 500);
+
+        let start = capture_ticks(0, false, 10, period);
+        let end = capture_ticks(8, false, 10, period);
+        assert_eq!(ticks_to_hz(end - start, 8), 1_000);
+        // Edges drifting 2 ticks per period: 250 kHz / 252.
+        let end = capture_ticks(8, false, 26, period);
+        assert_eq!(ticks_to_hz(end - start, 8), 992);
+
+        // Wrap and capture in the same poll: a low count came after the wrap.
+        assert_eq!(capture_ticks(3, true, 5, period), 4 * 250 + 5);
+        assert_eq!(capture_ticks(3, true, 240, period), 3 * 250 + 240);
+    }
+
+    #[test]
+    fn test_poll_limit_outlasts_the_wrap_timeout() {
+        // 1 kHz in fast PWM: 36 wraps of 250 counts, 576 000 CPU cycles.
+        assert_eq!(poll_limit(36, 250), 72_000);
+        // Slowest CTC tone: 72 wraps of a full 16-bit period still fits.
+        assert_eq!(poll_limit(72, 65_536), 37_748_736);
+        assert!(poll_limit(1, 1) >= 1);
+    }
+
+    #[test]
+    fn test_tolerance_check() {
+        assert_eq!(check_tolerance(992, 1_000, 1), Ok(992));
+        assert_eq!(
+            check_tolerance(1_020, 1_000, 1),
+            Err(MeasureError::Mismatch {
+                measured_hz: 1_020,
+                expected_hz: 1_000,
+            })
+        );
+        assert_eq!(check_tolerance(1_000, 1_000, 0), Ok(1_000));
+    }
+
+    #[test]
+    fn test_prescaler_matches_cs1_bits_and_keeps_cues_in_tune() {
+        // ICNC1, ICES1 and WGM13:12 set, with a stale clk/1024 selected.
+        let tccr1b = 0b1101_1101;
+        // The datasheet's CS12:10 = 0b011 selects clk/64.
+        assert_eq!(PRESCALER, 64);
+        assert_eq!(clock_bits(tccr1b), 0b1101_1011);
+        for mode in [ToneMode::Ctc, ToneMode::FastPwm] {
+            assert_eq!(resume_bits(0, tccr1b, mode).1, 0b1101_1011);
+        }
+        // Stopped by `standby`, the clock restarts at the same divider.
+        let (_, stopped) = standby_bits(0, tccr1b, true);
+        assert_eq!(resume_bits(0, stopped, ToneMode::Ctc).1 & CS1_MASK, 0b011);
+
+        for mode in [ToneMode::Ctc, ToneMode::FastPwm] {
+            for hz in (200..=5_000).step_by(50) {
+                let period = u32::from(top_for(hz, mode).unwrap()) + 1;
+                let played = commanded_hz(period, mode);
+                assert!(played.abs_diff(hz) * 25 <= hz, "{mode:?} {hz} -> {played}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_top_rejects_frequencies_that_overflow_ocr1a() {
+        assert_eq!(top_for(1_000, ToneMode::Ctc), Ok(124));
+        assert_eq!(top_for(1_000, ToneMode::FastPwm), Ok(249));
+        // 2 Hz needs 62 500 ticks in CTC but 125 000 in fast PWM.
+        assert_eq!(top_for(2, ToneMode::Ctc), Ok(62_499));
+        assert_eq!(top_for(2, ToneMode::FastPwm), Err(FrequencyError::TooLow));
+        assert_eq!(top_for(1, ToneMode::Ctc), Err(FrequencyError::TooLow));
+        assert_eq!(top_for(0, ToneMode::Ctc), Err(FrequencyError::TooLow));
+        assert_eq!(
+            top_for(200_000, ToneMode::Ctc),
+            Err(FrequencyError::TooHigh)
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! System millisecond clock on Timer0.
+//!
+//! [`SystemClock`] is the single time base for the firmware: it implements
+//! both [`TickSource`] (for timeouts, debouncing and buzzer scheduling) and
+//! [`DelayNs`], so nothing else needs its own timer.
+//!
+//! # Resources
+//! Claims Timer0 (`TCCR0`, `TCNT0`, `OCR0`), the `OCIE0` bit of `TIMSK` and
+//! the `TIMER0_COMP` interrupt. Timer1 stays free for
+//! [`BuzzerPwm`](super::buzzer_pwm::BuzzerPwm). Interrupts must be enabled
+//! (`unsafe { avr_device::interrupt::enable() }`) for time to advance.
+//!
+//! The tick also sounds the [`watchdog`](super::watchdog) warning.
+//!
+//! [`calibrate_from_gps`] tunes the timer-free [`BusyDelay`] against the GPS
+//! clock instead, for code that runs before the clock is started.
+
+use core::cell::Cell;
+
+use avr_device::{atmega16, interrupt, interrupt::Mutex};
+use embedded_hal::delay::DelayNs;
+use embedded_hal_nb::serial::Read;
+
+use super::{F_CPU, shared::delay::BusyDelay};
+use crate::drivers::{
+    gps::{NmeaReader, RmcFix, nmea::ReadError, parse_rmc},
+    tick::TickSource,
+};
+
+/// Timer0 prescaler; clk/64 gives 4 µs counts at 16 MHz.
+const PRESCALER: u32 = 64;
+/// Compare interrupts per second.
+const TICK_HZ: u32 = 1_000;
+/// `OCR0` value for one compare match per millisecond.
+const OCR0_1MS: u8 = compare_value(F_CPU, PRESCALER, TICK_HZ);
+/// Microseconds per Timer0 count.
+const US_PER_COUNT: u32 = 1_000_000 / (F_CPU / PRESCALER);
+
+/// Milliseconds in a UTC day, where RMC timestamps wrap.
+const DAY_MS: u32 = 86_400_000;
+/// [`calibrate_from_gps`] polls the receiver this many times per
+/// millisecond, often enough not to drop bytes at 9600 baud.
+const POLLS_PER_MS: u32 = 4;
+
+/// Milliseconds since [`SystemClock::new`], advanced by `TIMER0_COMP`.
+static MILLIS: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+/// Millisecond tick and delay provider backed by Timer0 in CTC mode.
+pub struct SystemClock {
+    tc0: atmega16::TC0,
+}
+
+impl SystemClock {
+    /// Start Timer0 ticking at 1 kHz and enable its compare interrupt.
+    ///
+    /// # Notes
+    /// - Timer0 runs in CTC mode (`WGM01`) at clk/64 with `OCR0` = 249, so
+    ///   `TCNT0` counts 0..=249 in 4 µs steps within each millisecond.
+    /// - `TIMSK` is shared with Timer1/2 and updated with `modify`.
+    pub fn new(tc0: atmega16::TC0) -> Self {
+        interrupt::free(|cs| {
+            tc0.tccr0.write(|w| w.cs().no_clock());
+            tc0.tcnt0.write(|w| w.bits(0));
+            tc0.ocr0.write(|w| w.bits(OCR0_1MS));
+            tc0.tifr.write(|w| w.ocf0().set_bit());
+            tc0.timsk.modify(|_, w| w.ocie0().set_bit());
+            MILLIS.borrow(cs).set(0);
+            tc0.tccr0.write(|w| w.wgm01().set_bit().cs().prescale_64());
+        });
+        SystemClock { tc0 }
+    }
+
+    /// `(milliseconds, counts into the current millisecond)`, consistent even
+    /// if the compare match fired but its interrupt has not run yet.
+    fn snapshot(&self) -> (u32, u8) {
+        interrupt::free(|cs| {
+            let mut ms = MILLIS.borrow(cs).get();
+            let mut counts = self.tc0.tcnt0.read().bits();
+            if self.tc0.tifr.read().ocf0().bit_is_set() {
+                counts = self.tc0.tcnt0.read().bits();
+                ms = ms.wrapping_add(1);
+            }
+            (ms, counts)
+        })
+    }
+
+    /// Stop Timer0, disable its interrupt and return the peripheral.
+    pub fn release(self) -> atmega16::TC0 {
+        self.tc0.tccr0.write(|w| w.cs().no_clock());
+        self.tc0.timsk.modify(|_, w| w.ocie0().clear_bit());
+        self.tc0
+    }
+}
+
+impl TickSource for SystemClock {
+    fn now_ms(&self) -> u32 {
+        self.snapshot().0
+    }
+}
+
+impl DelayNs for SystemClock {
+    /// Rounded up to whole microseconds; resolution is one 4 µs count.
+    fn delay_ns(&mut self, ns: u32) {
+        self.delay_us(ns.div_ceil(1_000));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        let start = self.snapshot();
+        while elapsed_us(start, self.snapshot()) < us {}
+    }
+
+    fn delay_ms(&mut self, mut ms: u32) {
+        // Keep each wait well inside the ~71 min `elapsed_us` range.
+        while ms > 0 {
+            let chunk = ms.min(1_000_000);
+            self.delay_us(chunk * 1_000);
+            ms -= chunk;
+        }
+    }
+}
+
+#[cfg(target_arch = "avr")]
+#[avr_device::interrupt(atmega16)]
+fn TIMER0_COMP() {
+    interrupt::free(|cs| {
+        let millis = MILLIS.borrow(cs);
+        millis.set(millis.get().wrapping_add(1));
+        super::watchdog::poll(cs, millis.get());
+    });
+}
+
+/// Reason [`calibrate_from_gps`] could not measure the loop rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationError<E> {
+    /// The serial port reported an error.
+    Serial(E),
+    /// No two RMC timestamps arrived within the timeout.
+    Timeout,
+}
+
+/// Measure how many [`BusyDelay`] loop iterations fit between two RMC
+/// timestamps and make that the delay's rate.
+///
+/// Spins in chunks of a quarter of the current `spins_per_ms`, reading the
+/// receiver between chunks, and counts the spins from the first timed RMC
+/// sentence to the next one with a different time (1 s later at the usual
+/// 1 Hz). Gives up with [`CalibrationError::Timeout`] after `timeout_ms`,
+/// judged by the uncalibrated rate. Returns the new `spins_per_ms`.
+///
+/// # Notes
+/// - RMC arrives a roughly fixed time after the second it reports, so the
+///   interval is accurate to the receiver's output jitter, typically well
+///   under 1%. The 1PPS pin would be better but needs another input.
+/// - The polling between chunks counts towards the measured rate, which is
+///   what later `delay_ms` calls want as long as its share stays small.
+/// - Lines with bad checksums or of other types are skipped.
+pub fn calibrate_from_gps<R, const N: usize>(
+    delay: &mut BusyDelay,
+    gps: &mut NmeaReader<R, N>,
+    timeout_ms: u32,
+) -> Result<u32, CalibrationError<R::Error>>
+where
+    R: Read<u8>,
+{
+    let chunk = (delay.spins_per_ms() / POLLS_PER_MS).max(1);
+    let max_chunks = u64::from(timeout_ms) * u64::from(POLLS_PER_MS);
+    let mut chunks = 0u64;
+    let mut first: Option<(u32, u64)> = None;
+    while chunks < max_chunks {
+        match gps.read() {
+            Ok(line) => {
+                if let Ok(RmcFix {
+                    time: Some(time), ..
+                }) = parse_rmc(line)
+                {
+                    let now = time.millis_of_day();
+                    match first {
+                        None => first = Some((now, chunks)),
+                        Some((start, _)) if start == now => {}
+                        Some((start, at)) => {
+                            let spins = (chunks - at) * u64::from(chunk);
+                            let rate = spins_per_ms(spins, utc_elapsed_ms(start, now));
+                            delay.set_spins_per_ms(rate);
+                            return Ok(rate);
+                        }
+                    }
+                }
+            }
+            Err(nb::Error::WouldBlock) | Err(nb::Error::Other(ReadError::Sentence(_))) => {}
+            Err(nb::Error::Other(ReadError::Serial(e))) => {
+                return Err(CalibrationError::Serial(e));
+            }
+        }
+        delay.spin(chunk);
+        chunks += 1;
+    }
+    Err(CalibrationError::Timeout)
+}
+
+/// Loop rate from `spins` counted over `elapsed_ms`, rounded to nearest.
+fn spins_per_ms(spins: u64, elapsed_ms: u32) -> u32 {
+    let elapsed = u64::from(elapsed_ms.max(1));
+    ((spins + elapsed / 2) / elapsed).clamp(1, u64::from(u32::MAX)) as u32
+}
+
+/// Milliseconds from UTC time of day `start` to `end`, across midnight.
+fn utc_elapsed_ms(start: u32, end: u32) -> u32 {
+    (end + DAY_MS - start) % DAY_MS
+}
+
+/// CTC compare value giving `tick_hz` interrupts from `f_cpu / prescaler`.
+const fn compare_value(f_cpu: u32, prescaler: u32, tick_hz: u32) -> u8 {
+    let top = f_cpu / prescaler / tick_hz - 1;
+    assert!(top <= u8::MAX as u32, "tick too slow for an 8-bit timer");
+    top as u8
+}
+
+/// Microseconds between two `(ms, counts)` snapshots.
+fn elapsed_us(start: (u32, u8), now: (u32, u8)) -> u32 {
+    now.0
+        .wrapping_sub(start.0)
+        .wrapping_mul(1_000)
+        .wrapping_add(u32::from(now.1) * US_PER_COUNT)
+        .wrapping_sub(u32::from(start.1) * US_PER_COUNT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_millisecond_compare_value() {
+        assert_eq!(OCR0_1MS, 249);
+        assert_eq!(US_PER_COUNT, 4);
+        assert_eq!(compare_value(8_000_000, 64, 1_000), 124);
+    }
+
+    #[test]
+    fn test_elapsed_us_spans_ticks_and_wraparound() {
+        assert_eq!(elapsed_us((10, 0), (10, 25)), 100);
+        assert_eq!(elapsed_us((10, 200), (11, 50)), 400);
+        assert_eq!(elapsed_us((u32::MAX, 249), (1, 0)), 1_004);
+    }
+
+    /// Serial port replaying a script; `None` reads as `WouldBlock`.
+    struct Script(std::collections::VecDeque<Option<u8>>);
+    impl embedded_hal_nb::serial::ErrorType for Script {
+        type Error = core::convert::Infallible;
+    }
+    impl Read<u8> for Script {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            self.0.pop_front().flatten().ok_or(nb::Error::WouldBlock)
+        }
+    }
+
+    fn rmc(time: &str) -> Vec<Option<u8>> {
+        let body = format!("GPRMC,{time},A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W");
+        let sum = crate::drivers::gps::checksum(body.as_bytes());
+        format!("${body}*{sum:02X}\r\n").bytes().map(Some).collect()
+    }
+
+    #[test]
+    fn test_calibration_math() {
+        assert_eq!(spins_per_ms(16_000_000, 1_000), 16_000);
+        assert_eq!(spins_per_ms(1_999, 1_000), 2);
+        assert_eq!(spins_per_ms(0, 1_000), 1);
+        assert_eq!(utc_elapsed_ms(1_000, 2_000), 1_000);
+        assert_eq!(utc_elapsed_ms(DAY_MS - 500, 500), 1_000);
+    }
+
+    #[test]
+    fn test_calibrate_counts_spins_between_rmc_seconds() {
+        // 8000 quarter-millisecond polls between fixes 1 s apart across
+        // midnight: the loop runs twice as fast as assumed.
+        let mut script = rmc("235959.500");
+        script.extend([None; 7_999]);
+        script.extend(rmc("000000.500"));
+        let mut gps = NmeaReader::<_>::new(Script(script.into()));
+        let mut delay = BusyDelay::new();
+
+        assert_eq!(calibrate_from_gps(&mut delay, &mut gps, 5_000), Ok(2_000));
+        assert_eq!(delay.spins_per_ms(), 2_000);
+
+        let mut gps = NmeaReader::<_>::new(Script(rmc("120000.000").into()));
+        assert_eq!(
+            calibrate_from_gps(&mut delay, &mut gps, 100),
+            Err(CalibrationError::Timeout)
+        );
+    }
+}
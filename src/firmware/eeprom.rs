@@ -0,0 +1,127 @@
+//! ATmega16 on-chip EEPROM driver and memory map.
+//!
+//! # Layout
+//!
+//! | Address             | Size            | Contents                                   |
+//! |---------------------|-----------------|--------------------------------------------|
+//! | 0                   | [`HEADER_SIZE`] | magic and [`LAYOUT_VERSION`], see [`eeprom::check_layout`] |
+//! | [`WAYPOINT_ADDR`]   | [`WAYPOINT_SLOTS`] × [`SLOT_SIZE`] | [`WaypointStore`] slots |
+//! | [`CONFIG_ADDR`]     | [`CONFIG_SIZE`] | [`GuidanceConfig`], see [`eeprom::save_config`] |
+//!
+//! Bump [`LAYOUT_VERSION`] whenever this map or a record format changes, and
+//! teach [`AvrEeprom::check_layout`] to migrate from the previous version.
+
+use crate::{
+    drivers::eeprom::{
+        self, CONFIG_SIZE, Eeprom, HEADER_SIZE, LayoutStatus, NearestWaypoint, SLOT_SIZE,
+        WaypointStore,
+    },
+    guidance::GuidanceConfig,
+    nav::Coord,
+};
+use avr_device::{atmega16, interrupt};
+use core::convert::Infallible;
+
+/// Size of the ATmega16 on-chip EEPROM in bytes.
+pub const EEPROM_SIZE: u16 = 512;
+
+/// Address of the saved [`GuidanceConfig`], at the top of the EEPROM so
+/// waypoint slots can grow upwards from 0.
+pub const CONFIG_ADDR: u16 = EEPROM_SIZE - CONFIG_SIZE;
+
+/// Version of the memory map above.
+pub const LAYOUT_VERSION: u8 = 1;
+
+/// Address of waypoint slot 0, right after the header.
+pub const WAYPOINT_ADDR: u16 = HEADER_SIZE;
+
+/// Number of waypoint slots that fit between the header and the config.
+pub const WAYPOINT_SLOTS: u8 = ((CONFIG_ADDR - WAYPOINT_ADDR) / SLOT_SIZE) as u8;
+
+/// Firmware-side driver for the ATmega16 on-chip EEPROM.
+///
+/// Implements [`Eeprom`] so it can back a generic
+/// [`WaypointStore`]. Addresses above
+/// [`EEPROM_SIZE`] wrap around, as on the hardware.
+pub struct AvrEeprom {
+    eeprom: atmega16::EEPROM,
+}
+
+impl AvrEeprom {
+    /// Wrap the PAC `EEPROM` peripheral.
+    pub fn new(eeprom: atmega16::EEPROM) -> AvrEeprom {
+        AvrEeprom { eeprom }
+    }
+
+    /// Save `config` at [`CONFIG_ADDR`]; see [`eeprom::save_config`].
+    pub fn save_config(&mut self, config: &GuidanceConfig) {
+        let Ok(()) = eeprom::save_config(self, CONFIG_ADDR, config);
+    }
+
+    /// Load the config saved by [`AvrEeprom::save_config`], or the defaults
+    /// if none was saved or it is corrupt.
+    pub fn load_config(&mut self) -> GuidanceConfig {
+        let Ok(config) = eeprom::load_config(self, CONFIG_ADDR);
+        config
+    }
+
+    /// Slot, waypoint and distance in metres of the saved waypoint nearest to
+    /// `current`, e.g. to navigate back to the closest saved spot; see
+    /// [`WaypointStore::nearest`]. `None` if no waypoint is saved.
+    pub fn nearest_waypoint(&mut self, current: Coord) -> Option<NearestWaypoint> {
+        // Slots are always in range and AvrEeprom accesses cannot fail.
+        WaypointStore::new(self, WAYPOINT_ADDR, WAYPOINT_SLOTS)
+            .nearest(current)
+            .ok()
+            .flatten()
+    }
+
+    /// Bring the EEPROM up to [`LAYOUT_VERSION`]; see [`eeprom::check_layout`].
+    ///
+    /// There is no older layout to migrate from yet, so any other version
+    /// resets the EEPROM.
+    pub fn check_layout(&mut self) -> LayoutStatus {
+        let len = EEPROM_SIZE - HEADER_SIZE;
+        let Ok(status) = eeprom::check_layout(self, LAYOUT_VERSION, len, |_, _| Ok(false));
+        status
+    }
+
+    /// Busy-wait until any previous write has finished (EEWE cleared).
+    fn wait_ready(&self) {
+        while self.eeprom.eecr.read().eewe().bit_is_set() {}
+    }
+}
+
+impl Eeprom for AvrEeprom {
+    type Error = Infallible;
+
+    fn read_byte(&mut self, addr: u16) -> Result<u8, Infallible> {
+        self.wait_ready();
+        self.eeprom.eear.write(|w| w.bits(addr));
+        self.eeprom.eecr.write(|w| w.eere().set_bit());
+        Ok(self.eeprom.eedr.read().bits())
+    }
+
+    /// Write `value` to `addr`, skipping the write if the cell already holds it.
+    ///
+    /// # Notes
+    /// - EEWE must be set within four cycles of EEMWE, so both strobes are plain
+    ///   `write`s inside a critical section. This driver owns `EECR` outright, so
+    ///   there are no other bits to preserve.
+    /// - A write takes ~8.5 ms; the next access blocks until it completes.
+    fn write_byte(&mut self, addr: u16, value: u8) -> Result<(), Infallible> {
+        if self.read_byte(addr)? == value {
+            return Ok(());
+        }
+        self.wait_ready();
+        self.eeprom.eear.write(|w| w.bits(addr));
+        self.eeprom.eedr.write(|w| w.bits(value));
+        interrupt::free(|_| {
+            self.eeprom.eecr.write(|w| w.eemwe().set_bit());
+            self.eeprom
+                .eecr
+                .write(|w| w.eemwe().set_bit().eewe().set_bit());
+        });
+        Ok(())
+    }
+}
@@ -1,5 +1,28 @@
+#[cfg(feature = "firmware")]
+pub mod adc;
+
 #[cfg(feature = "firmware")]
 pub mod buzzer_pwm;
 
 #[cfg(feature = "firmware")]
+pub mod clock;
+
+#[cfg(feature = "firmware")]
+pub mod eeprom;
+
+#[cfg(all(feature = "panic-sos", not(test)))]
+pub mod panic;
+
+#[cfg(feature = "firmware")]
+pub mod spi;
+
+#[cfg(feature = "firmware")]
+pub mod watchdog;
+
+/// Portable helpers with no AVR peripheral access.
 pub mod shared;
+
+/// MCU clock (Hz), shared by every driver that derives timer values from it.
+///
+/// Change this to match your actual fuse/clock configuration.
+pub const F_CPU: u32 = 16_000_000;
@@ -0,0 +1,51 @@
+//! Panic handler that sounds the panic warble and SOS on the buzzer before
+//! halting.
+//!
+//! Enabled by the `panic-sos` feature in place of `panic-halt`. Each repeat
+//! starts with [`Buzzer::panic_pattern`], which no routine cue resembles.
+//!
+//! # Requirements
+//! The handler takes over Timer1 and PD4 (OC1B) with
+//! [`BuzzerPwm::steal`], so the buzzer must be wired there and nothing may
+//! rely on TC1 or PORTD afterwards. Interrupts are disabled first and the
+//! [`watchdog`] is stopped so it cannot cut the SOS short;
+//! the handler never returns and does not allocate.
+
+use core::panic::PanicInfo;
+
+use avr_device::interrupt;
+
+use super::{buzzer_pwm::BuzzerPwm, shared::delay::BusyDelay, watchdog};
+use crate::drivers::{buzzer::Buzzer, morse};
+
+/// Pitch of the SOS tone.
+const SOS_HZ: u32 = 1_000;
+
+/// One Morse unit; 120 ms is slow enough to recognise by ear.
+const UNIT_MS: u32 = 120;
+
+/// How many times SOS is repeated before the device halts silently.
+const SOS_REPEATS: u8 = 5;
+
+/// [`Buzzer::panic_pattern`] cycles before each SOS, about 1 s.
+const PANIC_CYCLES: u32 = 8;
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    interrupt::disable();
+    // SAFETY: interrupts are off and this function never returns, so no
+    // other owner of TC1/PORTD or the watchdog will run again.
+    unsafe { watchdog::disable() };
+    let mut buzzer = Buzzer::new(unsafe { BuzzerPwm::steal() }, BusyDelay::new());
+
+    for _ in 0..SOS_REPEATS {
+        let _ = buzzer.panic_pattern(PANIC_CYCLES);
+        buzzer.rest(UNIT_MS * 7);
+        let _ = buzzer.morse(morse::SOS, SOS_HZ, UNIT_MS);
+        buzzer.rest(UNIT_MS * 7);
+    }
+    buzzer.idle();
+    loop {
+        core::hint::spin_loop();
+    }
+}
@@ -0,0 +1,138 @@
+//! Integer CORDIC routines for angle and magnitude without soft-float.
+//!
+//! Angles are fixed-point degrees; all inputs are plain `i32` vector
+//! components in any consistent unit.
+
+/// `atan(2^-i)` in degrees, Q16 (`deg * 65536`), for `i = 0..16`.
+const ATAN_TABLE_Q16: [i64; 16] = [
+    2_949_120, 1_740_967, 919_879, 466_945, 234_379, 117_304, 58_666, 29_335, 14_668, 7_334, 3_667,
+    1_833, 917, 458, 229, 115,
+];
+
+/// Reciprocal of the CORDIC gain (`∏ 1/√(1 + 2^-2i)` ≈ 0.607 253), Q16.
+const INV_GAIN_Q16: i64 = 39_797;
+
+/// Extra fractional bits added to the inputs so small vectors keep precision.
+const GUARD_BITS: u32 = 16;
+
+/// Rotate (`x`, `y`) onto the positive x axis.
+///
+/// Returns the rotated x (still multiplied by the CORDIC gain and the guard
+/// bits) and the angle removed, in degrees Q16 on `-180°..=180°`.
+fn vectoring(x: i32, y: i32) -> (i64, i64) {
+    let mut x = i64::from(x) << GUARD_BITS;
+    let mut y = i64::from(y) << GUARD_BITS;
+    let mut angle = 0i64;
+
+    // CORDIC only converges within ±99°, so pre-rotate the left half-plane.
+    if x < 0 {
+        let (px, py) = (x, y);
+        if py >= 0 {
+            x = py;
+            y = -px;
+            angle = 90 << 16;
+        } else {
+            x = -py;
+            y = px;
+            angle = -90 << 16;
+        }
+    }
+
+    for (i, &step) in ATAN_TABLE_Q16.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if y > 0 {
+            x += dx;
+            y -= dy;
+            angle += step;
+        } else {
+            x -= dx;
+            y += dy;
+            angle -= step;
+        }
+    }
+    (x, angle)
+}
+
+/// Angle of the vector (`x`, `y`) from the positive x axis towards the
+/// positive y axis, in hundredths of a degree on `-18000..=18000`.
+///
+/// Accurate to about ±0.01°. Returns 0 for the zero vector.
+pub fn atan2_centideg(y: i32, x: i32) -> i32 {
+    if x == 0 && y == 0 {
+        return 0;
+    }
+    let (_, angle_q16) = vectoring(x, y);
+    let centideg = angle_q16 * 100;
+    ((centideg + (1 << 15)) >> 16) as i32
+}
+
+/// Angle of the vector (`x`, `y`) in whole degrees on `-180..=180`.
+///
+/// Rounded to the nearest degree, so accurate to ±0.5°.
+pub fn atan2_deg(y: i32, x: i32) -> i16 {
+    let centideg = atan2_centideg(y, x);
+    ((centideg + 50 * centideg.signum()) / 100) as i16
+}
+
+/// Length of the vector (`x`, `y`), i.e. `√(x² + y²)`.
+///
+/// Relative error is below 0.01%, plus truncation to an integer.
+pub fn hypot(x: i32, y: i32) -> u32 {
+    let (scaled, _) = vectoring(x, y);
+    // Drop the guard bits first so the gain multiply cannot overflow i64.
+    (((scaled >> GUARD_BITS) * INV_GAIN_Q16) >> 16) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atan2_all_quadrants_match_libm() {
+        let vectors = [
+            (1, 1000),
+            (1000, 1),
+            (700, 300),
+            (300, -700),
+            (-700, -300),
+            (-300, 700),
+            (-1000, 0),
+            (0, -1000),
+            (-1, -1000),
+            (123_456_789, -98_765_432),
+        ];
+        for (y, x) in vectors {
+            let expected = libm::atan2(f64::from(y), f64::from(x)).to_degrees();
+            let centi = f64::from(atan2_centideg(y, x)) / 100.0;
+            assert!(
+                (centi - expected).abs() <= 0.02,
+                "({y}, {x}): {centi} vs {expected}"
+            );
+            let whole = f64::from(atan2_deg(y, x));
+            assert!(
+                (whole - expected).abs() <= 0.5,
+                "({y}, {x}): {whole} vs {expected}"
+            );
+        }
+        assert_eq!(atan2_deg(0, 0), 0);
+    }
+
+    #[test]
+    fn test_hypot_matches_libm() {
+        let vectors = [
+            (3, 4),
+            (-3, 4),
+            (-300, -400),
+            (1_000_000, -1),
+            (i32::MAX, i32::MAX),
+        ];
+        for (x, y) in vectors {
+            let expected = libm::hypot(f64::from(x), f64::from(y));
+            let got = f64::from(hypot(x, y));
+            assert!(
+                (got - expected).abs() <= expected * 1e-4 + 1.0,
+                "({x}, {y}): {got} vs {expected}"
+            );
+        }
+    }
+}
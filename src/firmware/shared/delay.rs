@@ -1,15 +1,50 @@
 use core::hint;
 use embedded_hal::delay::DelayNs;
 
+/// Loop iterations per millisecond assumed by [`BusyDelay::new`].
+pub const DEFAULT_SPINS_PER_MS: u32 = 1_000;
+
 /// A *very* simple blocking delay that burns CPU cycles.
 ///
 /// **Accuracy:**  
-/// - Assumes 1 spin ≈ 1 CPU cycle – true on many MCUs but *not* guaranteed.  
-pub struct BusyDelay;
+/// - Assumes [`DEFAULT_SPINS_PER_MS`] spins ≈ 1 ms unless told otherwise.  
+/// - Use `firmware::clock::calibrate_from_gps` to measure the real rate.  
+pub struct BusyDelay {
+    spins_per_ms: u32,
+}
 
 impl BusyDelay {
     pub fn new() -> Self {
-        Self
+        Self::with_spins_per_ms(DEFAULT_SPINS_PER_MS)
+    }
+
+    /// Delay with a known loop rate, e.g. one saved after calibration.
+    pub fn with_spins_per_ms(spins_per_ms: u32) -> Self {
+        Self { spins_per_ms }
+    }
+
+    /// Loop iterations counted as one millisecond.
+    pub fn spins_per_ms(&self) -> u32 {
+        self.spins_per_ms
+    }
+
+    /// Change the loop rate used by [`DelayNs::delay_ms`].
+    pub fn set_spins_per_ms(&mut self, spins_per_ms: u32) {
+        self.spins_per_ms = spins_per_ms;
+    }
+
+    /// Busy-wait for `spins` loop iterations.
+    pub fn spin(&mut self, spins: u32) {
+        for _ in 0..spins {
+            // Compiler hint: “I’m intentionally spinning; don’t optimise away.”
+            hint::spin_loop();
+        }
+    }
+}
+
+impl Default for BusyDelay {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -23,14 +58,76 @@ impl DelayNs for BusyDelay {
     /// Busy-wait for `ms` milliseconds.
     ///
     /// Inner loop:
-    ///   * 1 000 iterations × `spin_loop()` ≈ 1 000 CPU cycles  
-    ///   * On a 1 MHz AVR that’s ≈ 1 ms (rough rule-of-thumb).
+    ///   * `spins_per_ms` iterations × `spin_loop()` per millisecond  
+    ///   * The default 1 000 is ≈ 1 ms on a 1 MHz AVR (rough rule-of-thumb).
     fn delay_ms(&mut self, ms: u32) {
         for _ in 0..ms {
-            for _ in 0..1_000 {
-                // Compiler hint: “I’m intentionally spinning; don’t optimise away.”
-                hint::spin_loop();
-            }
+            self.spin(self.spins_per_ms);
         }
     }
 }
+
+/// Zero-time delay that only records how long it was asked to wait.
+///
+/// Lets driver and integration tests run instantly while still checking
+/// timing. Available in unit tests and behind the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NopDelay {
+    elapsed_ns: u64,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl NopDelay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total requested time in nanoseconds.
+    pub fn elapsed_ns(&self) -> u64 {
+        self.elapsed_ns
+    }
+
+    /// Total requested time in whole milliseconds.
+    pub fn elapsed_ms(&self) -> u64 {
+        self.elapsed_ns / 1_000_000
+    }
+
+    /// Reset the recorded total to zero.
+    pub fn reset(&mut self) {
+        self.elapsed_ns = 0;
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl DelayNs for NopDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.elapsed_ns += u64::from(ns);
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.elapsed_ns += u64::from(us) * 1_000;
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.elapsed_ns += u64::from(ms) * 1_000_000;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nop_delay_accumulates_requested_time() {
+        let mut delay = NopDelay::new();
+        delay.delay_ms(250);
+        delay.delay_ms(u32::MAX);
+        delay.delay_us(1_500);
+        delay.delay_ns(500_000);
+        assert_eq!(delay.elapsed_ms(), 250 + u64::from(u32::MAX) + 2);
+
+        delay.reset();
+        assert_eq!(delay.elapsed_ns(), 0);
+    }
+}
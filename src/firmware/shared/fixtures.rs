@@ -0,0 +1,198 @@
+//! Test doubles shared by the unit tests, next to [`NopDelay`].
+//!
+//! [`NopDelay`]: super::delay::NopDelay
+
+use core::{cell::Cell, convert::Infallible};
+
+use embedded_hal::pwm::{ErrorType, SetDutyCycle};
+
+use crate::{
+    drivers::{
+        adc::AdcChannel, buzzer::SetFrequency, eeprom::Eeprom, gps::FixSource, tick::TickSource,
+    },
+    nav::Coord,
+    notify::Notifier,
+};
+
+/// Tick source that only moves when the test sets it.
+#[derive(Debug, Default)]
+pub struct ManualTicks(pub Cell<u32>);
+
+impl TickSource for ManualTicks {
+    fn now_ms(&self) -> u32 {
+        self.0.get()
+    }
+}
+
+/// Tick source that advances 1 ms every time it is read.
+#[derive(Debug, Default)]
+pub struct SteppingTicks(pub Cell<u32>);
+
+impl TickSource for SteppingTicks {
+    fn now_ms(&self) -> u32 {
+        let now = self.0.get();
+        self.0.set(now.wrapping_add(1));
+        now
+    }
+}
+
+/// Notifier that counts the cues it was asked to play.
+#[derive(Debug, Default)]
+pub struct CountingNotifier {
+    pub confirms: u32,
+    pub errors: u32,
+}
+
+impl Notifier for CountingNotifier {
+    fn confirm(&mut self) {
+        self.confirms += 1;
+    }
+    fn error(&mut self) {
+        self.errors += 1;
+    }
+}
+
+/// Receiver that always reports the same fix, or none.
+#[derive(Debug, Default)]
+pub struct FakeGps(pub Option<Coord>);
+
+impl FixSource for FakeGps {
+    fn latest_fix(&mut self) -> Option<Coord> {
+        self.0
+    }
+}
+
+/// `N` bytes of EEPROM in RAM.
+#[derive(Debug)]
+pub struct RamEeprom<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Eeprom for RamEeprom<N> {
+    type Error = Infallible;
+    fn read_byte(&mut self, addr: u16) -> Result<u8, Self::Error> {
+        Ok(self.0[addr as usize])
+    }
+    fn write_byte(&mut self, addr: u16, value: u8) -> Result<(), Self::Error> {
+        self.0[addr as usize] = value;
+        Ok(())
+    }
+}
+
+/// PWM that records the frequency of every tone that was switched on.
+#[derive(Debug, Default)]
+pub struct BeepLog {
+    hz: u32,
+    pub beeps: Vec<u32>,
+}
+
+impl ErrorType for BeepLog {
+    type Error = Infallible;
+}
+
+impl SetDutyCycle for BeepLog {
+    fn max_duty_cycle(&self) -> u16 {
+        100
+    }
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        if duty > 0 {
+            self.beeps.push(self.hz);
+        }
+        Ok(())
+    }
+}
+
+impl SetFrequency for BeepLog {
+    type Error = Infallible;
+    fn set_frequency(&mut self, hz: u32) -> Result<(), Infallible> {
+        self.hz = hz;
+        Ok(())
+    }
+}
+
+/// PWM that records every frequency and duty written to it, and counts
+/// standby/resume calls.
+#[derive(Debug, Default)]
+pub struct RecordingPwm {
+    pub frequencies: Vec<u32>,
+    pub duties: Vec<u16>,
+    pub standbys: u32,
+    pub resumes: u32,
+}
+
+impl ErrorType for RecordingPwm {
+    type Error = Infallible;
+}
+
+impl SetDutyCycle for RecordingPwm {
+    fn max_duty_cycle(&self) -> u16 {
+        100
+    }
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.duties.push(duty);
+        Ok(())
+    }
+}
+
+impl SetFrequency for RecordingPwm {
+    type Error = Infallible;
+    fn set_frequency(&mut self, hz: u32) -> Result<(), Infallible> {
+        self.frequencies.push(hz);
+        Ok(())
+    }
+    fn standby(&mut self) {
+        self.standbys += 1;
+    }
+    fn resume(&mut self) {
+        self.resumes += 1;
+    }
+}
+
+/// PWM with only `max` duty steps, as at high pitches in fast PWM, that
+/// records every duty written to it.
+#[derive(Debug)]
+pub struct CoarsePwm {
+    pub max: u16,
+    pub duties: Vec<u16>,
+}
+
+impl CoarsePwm {
+    pub fn new(max: u16) -> Self {
+        Self {
+            max,
+            duties: Vec::new(),
+        }
+    }
+}
+
+impl ErrorType for CoarsePwm {
+    type Error = Infallible;
+}
+
+impl SetDutyCycle for CoarsePwm {
+    fn max_duty_cycle(&self) -> u16 {
+        self.max
+    }
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.duties.push(duty);
+        Ok(())
+    }
+}
+
+impl SetFrequency for CoarsePwm {
+    type Error = Infallible;
+    fn set_frequency(&mut self, _hz: u32) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// 10-bit ADC replaying a fixed list of readings.
+pub struct Readings<'a>(pub core::slice::Iter<'a, u16>);
+
+impl AdcChannel for Readings<'_> {
+    type Error = Infallible;
+    fn full_scale(&self) -> u16 {
+        1023
+    }
+    fn read(&mut self) -> Result<u16, Infallible> {
+        Ok(*self.0.next().unwrap())
+    }
+}
@@ -1 +1,4 @@
+pub mod cordic;
 pub mod delay;
+#[cfg(test)]
+pub mod fixtures;
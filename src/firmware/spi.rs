@@ -0,0 +1,204 @@
+use avr_device::atmega16;
+use embedded_hal::spi::{self, ErrorKind, ErrorType, Mode, Phase, Polarity, SpiBus};
+
+/// `SPCR` bit masks.
+const SPE: u8 = 1 << 6;
+const MSTR: u8 = 1 << 4;
+const CPOL: u8 = 1 << 3;
+const CPHA: u8 = 1 << 2;
+
+/// Byte clocked out while reading, as SD cards expect.
+const FILL_BYTE: u8 = 0xFF;
+
+/// SCK frequency as a fraction of the CPU clock.
+///
+/// SD cards must be initialised at 100–400 kHz (`Div64` or `Div128` at
+/// 16 MHz) and can then run up to `Div2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockDivider {
+    /// f_CPU / 2.
+    Div2,
+    /// f_CPU / 4.
+    Div4,
+    /// f_CPU / 8.
+    Div8,
+    /// f_CPU / 16.
+    Div16,
+    /// f_CPU / 32.
+    Div32,
+    /// f_CPU / 64.
+    Div64,
+    /// f_CPU / 128.
+    Div128,
+}
+
+/// Errors reported by [`Spi`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpiError {
+    /// `SPDR` was written while a transfer was still in progress (`WCOL`).
+    WriteCollision,
+}
+
+impl spi::Error for SpiError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// Firmware-side SPI master on the ATmega16 hardware SPI.
+///
+/// Pins are fixed by the hardware: PB4 = SS, PB5 = MOSI, PB6 = MISO,
+/// PB7 = SCK. Data is sent MSB first. Chip select is not driven here; use
+/// the bus with an `embedded-hal` `SpiDevice` wrapper and a GPIO CS pin.
+pub struct Spi {
+    spi: atmega16::SPI,
+}
+
+impl Spi {
+    /// Configure the SPI peripheral as master in `mode` at `divider`.
+    ///
+    /// # Notes
+    /// - PB4 (SS) is made an output even if it is not used as chip select:
+    ///   as an input, a low level on SS would silently switch the SPI to
+    ///   slave mode.
+    /// - `DDRB` is updated with `modify` so only PB4, PB5 and PB7 change.
+    pub fn new(
+        spi: atmega16::SPI,
+        portb: &atmega16::PORTB,
+        mode: Mode,
+        divider: ClockDivider,
+    ) -> Spi {
+        portb
+            .ddrb
+            .modify(|_, w| w.pb4().set_bit().pb5().set_bit().pb7().set_bit());
+        portb.ddrb.modify(|_, w| w.pb6().clear_bit());
+        let mut spi = Spi { spi };
+        spi.configure(mode, divider);
+        spi
+    }
+
+    /// Change mode and clock, e.g. to speed up after SD-card initialisation.
+    pub fn configure(&mut self, mode: Mode, divider: ClockDivider) {
+        let (spcr, double_speed) = config_bits(mode, divider);
+        // SAFETY: every SPCR bit is defined by `config_bits`.
+        self.spi.spcr.write(|w| unsafe { w.bits(spcr) });
+        self.spi.spsr.write(|w| w.spi2x().bit(double_speed));
+    }
+
+    /// Exchange one byte.
+    fn transfer_byte(&mut self, byte: u8) -> Result<u8, SpiError> {
+        self.spi.spdr.write(|w| w.bits(byte));
+        if self.spi.spsr.read().wcol().bit_is_set() {
+            // Reading SPDR after SPSR clears WCOL.
+            let _ = self.spi.spdr.read().bits();
+            return Err(SpiError::WriteCollision);
+        }
+        while self.spi.spsr.read().spif().bit_is_clear() {}
+        Ok(self.spi.spdr.read().bits())
+    }
+}
+
+/// `SPCR` value and `SPI2X` flag for master mode with `mode` and `divider`.
+fn config_bits(mode: Mode, divider: ClockDivider) -> (u8, bool) {
+    let (spr, double_speed) = match divider {
+        ClockDivider::Div2 => (0b00, true),
+        ClockDivider::Div4 => (0b00, false),
+        ClockDivider::Div8 => (0b01, true),
+        ClockDivider::Div16 => (0b01, false),
+        ClockDivider::Div32 => (0b10, true),
+        ClockDivider::Div64 => (0b10, false),
+        ClockDivider::Div128 => (0b11, false),
+    };
+    let mut spcr = SPE | MSTR | spr;
+    if mode.polarity == Polarity::IdleHigh {
+        spcr |= CPOL;
+    }
+    if mode.phase == Phase::CaptureOnSecondTransition {
+        spcr |= CPHA;
+    }
+    (spcr, double_speed)
+}
+
+impl ErrorType for Spi {
+    type Error = SpiError;
+}
+
+impl SpiBus for Spi {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), SpiError> {
+        for word in words {
+            *word = self.transfer_byte(FILL_BYTE)?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), SpiError> {
+        for &word in words {
+            self.transfer_byte(word)?;
+        }
+        Ok(())
+    }
+
+    /// Exchange `max(read.len(), write.len())` bytes; a shorter `write` is
+    /// padded with `0xFF` and extra incoming bytes beyond `read` are dropped.
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), SpiError> {
+        for i in 0..read.len().max(write.len()) {
+            let rx = self.transfer_byte(write.get(i).copied().unwrap_or(FILL_BYTE))?;
+            if let Some(slot) = read.get_mut(i) {
+                *slot = rx;
+            }
+        }
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), SpiError> {
+        for word in words {
+            *word = self.transfer_byte(*word)?;
+        }
+        Ok(())
+    }
+
+    /// Transfers are blocking, so there is never anything left to flush.
+    fn flush(&mut self) -> Result<(), SpiError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::spi::{MODE_0, MODE_1, MODE_2, MODE_3};
+
+    #[test]
+    fn test_config_bits_for_modes() {
+        assert_eq!(
+            config_bits(MODE_0, ClockDivider::Div4),
+            (0b0101_0000, false)
+        );
+        assert_eq!(
+            config_bits(MODE_1, ClockDivider::Div4),
+            (0b0101_0100, false)
+        );
+        assert_eq!(
+            config_bits(MODE_2, ClockDivider::Div4),
+            (0b0101_1000, false)
+        );
+        assert_eq!(
+            config_bits(MODE_3, ClockDivider::Div4),
+            (0b0101_1100, false)
+        );
+    }
+
+    #[test]
+    fn test_config_bits_for_dividers() {
+        let spr = |d| {
+            let (spcr, x2) = config_bits(MODE_0, d);
+            (spcr & 0b11, x2)
+        };
+        assert_eq!(spr(ClockDivider::Div2), (0b00, true));
+        assert_eq!(spr(ClockDivider::Div8), (0b01, true));
+        assert_eq!(spr(ClockDivider::Div16), (0b01, false));
+        assert_eq!(spr(ClockDivider::Div32), (0b10, true));
+        assert_eq!(spr(ClockDivider::Div64), (0b10, false));
+        assert_eq!(spr(ClockDivider::Div128), (0b11, false));
+    }
+}
@@ -0,0 +1,102 @@
+//! Watchdog reset with an audible warning shortly before it fires.
+//!
+//! [`Watchdog::start`] arms the ATmega16 watchdog with its longest timeout,
+//! about [`TIMEOUT_MS`], and the main loop must call [`Watchdog::feed`] more
+//! often than that. The ATmega16 watchdog cannot interrupt before it resets,
+//! so the [`SystemClock`](super::clock::SystemClock) tick watches the feeds
+//! instead: once the loop has gone [`WARN_AFTER_MS`] without one, the
+//! `TIMER0_COMP` interrupt takes over the buzzer and plays
+//! [`Buzzer::panic_pattern`] until the reset, so a hang is never silent.
+//!
+//! # Requirements
+//! Needs a running [`SystemClock`](super::clock::SystemClock) with interrupts
+//! enabled. Like the panic handler, the warning takes over Timer1 and PD4
+//! (OC1B) with [`BuzzerPwm::steal`]; nothing else runs again before the
+//! reset.
+
+use core::cell::Cell;
+
+use avr_device::{asm, atmega16, interrupt, interrupt::CriticalSection, interrupt::Mutex};
+
+use super::{buzzer_pwm::BuzzerPwm, shared::delay::BusyDelay};
+use crate::drivers::{buzzer::Buzzer, tick::TickSource};
+
+/// Watchdog timeout with the 2048K-cycle prescaler, at 5 V. The datasheet
+/// gives 2.2 s at 3 V; the watchdog oscillator varies by a few percent more.
+pub const TIMEOUT_MS: u32 = 2_100;
+
+/// Time without a [`Watchdog::feed`] after which the warning starts, leaving
+/// about three [`Buzzer::panic_pattern`] cycles before the reset.
+pub const WARN_AFTER_MS: u32 = 1_700;
+
+/// [`TickSource::now_ms`] of the last feed, or `None` while disarmed.
+static LAST_FEED_MS: Mutex<Cell<Option<u32>>> = Mutex::new(Cell::new(None));
+
+/// The armed ATmega16 watchdog.
+pub struct Watchdog {
+    _wdt: atmega16::WDT,
+}
+
+impl Watchdog {
+    /// Arm the watchdog with its longest timeout; see the module docs.
+    ///
+    /// `ticks` is normally the [`SystemClock`](super::clock::SystemClock)
+    /// whose interrupt sounds the warning.
+    pub fn start<T: TickSource>(wdt: atmega16::WDT, ticks: &T) -> Self {
+        interrupt::free(|cs| {
+            asm::wdr();
+            wdt.wdtcr.write(|w| w.wde().set_bit().wdpl().cycles_2048k());
+            LAST_FEED_MS.borrow(cs).set(Some(ticks.now_ms()));
+        });
+        Watchdog { _wdt: wdt }
+    }
+
+    /// Restart the timeout. Call at least every [`WARN_AFTER_MS`] from the
+    /// main loop, never from an interrupt, or a hung loop goes unnoticed.
+    pub fn feed<T: TickSource>(&mut self, ticks: &T) {
+        interrupt::free(|cs| {
+            asm::wdr();
+            LAST_FEED_MS.borrow(cs).set(Some(ticks.now_ms()));
+        });
+    }
+}
+
+/// Stop the watchdog, e.g. so the panic handler can finish its SOS.
+///
+/// # Safety
+/// Steals the `WDT` peripheral; any [`Watchdog`] must never be fed again.
+pub unsafe fn disable() {
+    interrupt::free(|cs| {
+        // SAFETY: upheld by the caller.
+        let wdt = unsafe { atmega16::Peripherals::steal() }.WDT;
+        asm::wdr();
+        // WDE may only be cleared within four cycles of setting WDTOE.
+        wdt.wdtcr.write(|w| w.wdtoe().set_bit().wde().set_bit());
+        wdt.wdtcr.write(|w| unsafe { w.bits(0) });
+        LAST_FEED_MS.borrow(cs).set(None);
+    });
+}
+
+/// Sound the warning if the watchdog is about to fire.
+///
+/// Called on every [`SystemClock`](super::clock::SystemClock) tick; call it
+/// from another periodic interrupt when that clock is not running.
+pub fn poll(cs: CriticalSection, now_ms: u32) {
+    if LAST_FEED_MS
+        .borrow(cs)
+        .get()
+        .is_some_and(|fed_ms| now_ms.wrapping_sub(fed_ms) >= WARN_AFTER_MS)
+    {
+        warn_until_reset();
+    }
+}
+
+fn warn_until_reset() -> ! {
+    // SAFETY: called from the tick interrupt with interrupts disabled, and
+    // only the watchdog reset ends the loop, so no other owner of TC1/PORTD
+    // will run again.
+    let mut buzzer = Buzzer::new(unsafe { BuzzerPwm::steal() }, BusyDelay::new());
+    loop {
+        let _ = buzzer.panic_pattern(1);
+    }
+}
@@ -0,0 +1,124 @@
+//! Proximity beeping whose rate glides with distance instead of snapping.
+
+use crate::{drivers::tick::TickSource, util::math};
+
+/// Distance-proportional beep interval with a slew-rate limit.
+///
+/// The target interval rises linearly from `min_interval_ms` at `near_m` to
+/// `max_interval_ms` at `far_m` and is held outside that range, like a
+/// parking sensor. The interval actually returned moves towards the target
+/// by at most `max_slew_ms_per_s` for each second between updates, so a GPS
+/// jump or a leg change makes the cadence glide rather than snap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cadence {
+    near_m: f32,
+    far_m: f32,
+    min_interval_ms: u32,
+    max_interval_ms: u32,
+    max_slew_ms_per_s: u32,
+    interval_ms: Option<f32>,
+    last_ms: u32,
+}
+
+impl Cadence {
+    /// Create a cadence controller.
+    ///
+    /// * `near_m`, `far_m` – distances mapped to the fastest and slowest beeping
+    /// * `min_interval_ms`, `max_interval_ms` – time between beeps at those distances
+    /// * `max_slew_ms_per_s` – largest change of the interval per second
+    pub const fn new(
+        near_m: f32,
+        far_m: f32,
+        min_interval_ms: u32,
+        max_interval_ms: u32,
+        max_slew_ms_per_s: u32,
+    ) -> Self {
+        Self {
+            near_m,
+            far_m,
+            min_interval_ms,
+            max_interval_ms,
+            max_slew_ms_per_s,
+            interval_ms: None,
+            last_ms: 0,
+        }
+    }
+
+    /// Interval the distance alone asks for, without slew limiting.
+    pub fn target_interval_ms(&self, distance_m: f32) -> u32 {
+        math::round_u32(math::lerp_clamped(
+            distance_m,
+            self.near_m,
+            self.far_m,
+            self.min_interval_ms as f32,
+            self.max_interval_ms as f32,
+        ))
+    }
+
+    /// Feed the latest distance and return the interval to wait before the
+    /// next beep.
+    ///
+    /// The first update jumps straight to the target. `ticks` times the slew
+    /// between updates.
+    pub fn update<T: TickSource>(&mut self, distance_m: f32, ticks: &T) -> u32 {
+        let now_ms = ticks.now_ms();
+        let target = self.target_interval_ms(distance_m) as f32;
+        let interval = match self.interval_ms {
+            None => target,
+            Some(current) => {
+                let elapsed_ms = now_ms.wrapping_sub(self.last_ms) as f32;
+                let step = self.max_slew_ms_per_s as f32 * elapsed_ms / 1_000.0;
+                current + (target - current).clamp(-step, step)
+            }
+        };
+        self.interval_ms = Some(interval);
+        self.last_ms = now_ms;
+        math::round_u32(interval)
+    }
+
+    /// Forget the current interval, so the next update jumps to its target.
+    pub fn reset(&mut self) {
+        self.interval_ms = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::ManualTicks;
+
+    #[test]
+    fn test_distance_step_glides_cadence() {
+        let ticks = ManualTicks::default();
+        let mut cadence = Cadence::new(5.0, 105.0, 100, 1_100, 200);
+        assert_eq!(cadence.target_interval_ms(55.0), 600);
+        assert_eq!(cadence.update(105.0, &ticks), 1_100);
+
+        // Jump to the waypoint: 200 ms/s over 1 s updates, not one snap.
+        let intervals: Vec<u32> = (1..=6)
+            .map(|s| {
+                ticks.0.set(s * 1_000);
+                cadence.update(0.0, &ticks)
+            })
+            .collect();
+        assert_eq!(intervals, [900, 700, 500, 300, 100, 100]);
+
+        // Twice the time between updates allows twice the step.
+        ticks.0.set(8_000);
+        assert_eq!(cadence.update(105.0, &ticks), 500);
+
+        cadence.reset();
+        ticks.0.set(8_100);
+        assert_eq!(cadence.update(105.0, &ticks), 1_100);
+    }
+
+    #[test]
+    fn test_slew_across_counter_wrap() {
+        let ticks = ManualTicks::default();
+        let mut cadence = Cadence::new(0.0, 10.0, 100, 1_100, 100);
+        ticks.0.set(u32::MAX - 499);
+        cadence.update(10.0, &ticks);
+        ticks.0.set(500);
+        assert_eq!(cadence.update(0.0, &ticks), 1_000);
+    }
+}
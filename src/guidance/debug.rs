@@ -0,0 +1,84 @@
+//! Human-readable guidance lines on a serial port, for bench debugging.
+//!
+//! Enabled by the `debug-serial` feature. Lines look like
+//! `dist=123m brg=045 turn=SlightRight\r\n` and are formatted into a stack
+//! buffer, so no allocator is needed.
+
+use core::fmt::{self, Write as _};
+
+use embedded_hal_nb::serial::Write;
+
+use super::Steering;
+
+/// Longest line produced by [`format_steering`], including `\r\n`.
+pub const MAX_LINE_LEN: usize = 48;
+
+/// Format `steering` as one line into `buf` and return the length written.
+///
+/// Output that does not fit in `buf` is truncated.
+pub fn format_steering(buf: &mut [u8], steering: &Steering) -> usize {
+    let mut line = LineBuf { buf, len: 0 };
+    let _ = write!(
+        line,
+        "dist={}m brg={:03} turn={:?}\r\n",
+        steering.distance_m as u32,
+        (steering.bearing_deg + 0.5) as u32 % 360,
+        steering.turn
+    );
+    line.len
+}
+
+/// Write `steering` as one line to `usart`, blocking until sent.
+pub fn write_steering<W>(usart: &mut W, steering: &Steering) -> Result<(), W::Error>
+where
+    W: Write<u8>,
+{
+    let mut buf = [0u8; MAX_LINE_LEN];
+    let len = format_steering(&mut buf, steering);
+    for &byte in &buf[..len] {
+        nb::block!(usart.write(byte))?;
+    }
+    nb::block!(usart.flush())
+}
+
+/// [`fmt::Write`] sink over a fixed byte slice.
+struct LineBuf<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl fmt::Write for LineBuf<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let free = self.buf.len() - self.len;
+        let n = s.len().min(free);
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        if n < s.len() { Err(fmt::Error) } else { Ok(()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guidance::Turn;
+
+    const STEERING: Steering = Steering {
+        distance_m: 123.4,
+        bearing_deg: 44.6,
+        turn: Turn::SlightRight,
+    };
+
+    #[test]
+    fn test_format_steering_line() {
+        let mut buf = [0u8; MAX_LINE_LEN];
+        let len = format_steering(&mut buf, &STEERING);
+        assert_eq!(&buf[..len], b"dist=123m brg=045 turn=SlightRight\r\n");
+    }
+
+    #[test]
+    fn test_format_steering_truncates_to_buffer() {
+        let mut buf = [0u8; 10];
+        assert_eq!(format_steering(&mut buf, &STEERING), 10);
+        assert_eq!(&buf, b"dist=123m ");
+    }
+}
@@ -0,0 +1,96 @@
+//! Thinning out fixes before guidance decisions.
+
+/// How often [`Decimator::ready`] lets a fix through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rate {
+    /// Every `n`th fix; `0` and `1` pass every fix.
+    EveryNth(u16),
+    /// At most one fix per this many milliseconds.
+    EveryMs(u32),
+}
+
+/// Passes through only some fixes, so guidance (and its beeps) runs at a
+/// calmer rate than a 5–10 Hz receiver.
+///
+/// Keep feeding every fix to anything that benefits from the full rate, such
+/// as a position filter or speed estimate, and call [`Guidance::update`]
+/// only when [`Decimator::ready`] returns `true`. The first fix always
+/// passes.
+///
+/// [`Guidance::update`]: super::Guidance::update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decimator {
+    rate: Rate,
+    skipped: u16,
+    last_ms: Option<u32>,
+}
+
+impl Decimator {
+    /// Create a decimator passing fixes at `rate`.
+    pub const fn new(rate: Rate) -> Self {
+        Self {
+            rate,
+            skipped: 0,
+            last_ms: None,
+        }
+    }
+
+    /// The configured rate.
+    pub fn rate(&self) -> Rate {
+        self.rate
+    }
+
+    /// Change the rate; the next fix passes.
+    pub fn set_rate(&mut self, rate: Rate) {
+        *self = Self::new(rate);
+    }
+
+    /// Call once per fix; `true` if this one should drive guidance.
+    ///
+    /// `now_ms` is only used by [`Rate::EveryMs`].
+    pub fn ready(&mut self, now_ms: u32) -> bool {
+        match self.rate {
+            Rate::EveryNth(n) => {
+                let pass = self.skipped == 0;
+                self.skipped += 1;
+                if self.skipped >= n {
+                    self.skipped = 0;
+                }
+                pass
+            }
+            Rate::EveryMs(interval) => match self.last_ms {
+                Some(last) if now_ms.wrapping_sub(last) < interval => false,
+                _ => {
+                    self.last_ms = Some(now_ms);
+                    true
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factor_five_passes_one_fix_in_five() {
+        let mut decimator = Decimator::new(Rate::EveryNth(5));
+        let passed: Vec<usize> = (0..20).filter(|_| decimator.ready(0)).collect();
+        assert_eq!(passed, [0, 5, 10, 15]);
+
+        decimator.set_rate(Rate::EveryNth(1));
+        assert!((0..3).all(|_| decimator.ready(0)));
+    }
+
+    #[test]
+    fn test_time_based_rate_at_ten_hz() {
+        let mut decimator = Decimator::new(Rate::EveryMs(1_000));
+        let start = u32::MAX - 250;
+        let passed: Vec<u32> = (0..30)
+            .map(|i| i * 100)
+            .filter(|&t| decimator.ready(start.wrapping_add(t)))
+            .collect();
+        assert_eq!(passed, [0, 1_000, 2_000]);
+    }
+}
@@ -0,0 +1,97 @@
+//! Heading from successive fixes, ignoring jitter while barely moving.
+
+use crate::nav::{Coord, leg_range_bearing};
+
+/// Estimates the direction of travel from GPS positions.
+///
+/// A new heading is taken only once the user has moved at least
+/// `min_move_m` from where the last one was taken; smaller moves keep the
+/// previous heading. Position noise of a few metres would otherwise swing
+/// the heading wildly while standing or creeping, so the threshold should
+/// be a few metres on foot and more in a vehicle. Slow movement accumulates
+/// until it counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadingEstimator {
+    min_move_m: f32,
+    anchor: Option<Coord>,
+    heading_deg: Option<f32>,
+}
+
+impl HeadingEstimator {
+    /// Create an estimator that needs `min_move_m` of movement per heading.
+    pub const fn new(min_move_m: f32) -> Self {
+        Self {
+            min_move_m,
+            anchor: None,
+            heading_deg: None,
+        }
+    }
+
+    /// Feed the latest fix and return the current heading in degrees
+    /// `0..360`, or `None` until the user has moved far enough once.
+    pub fn update(&mut self, fix: Coord) -> Option<f32> {
+        let Some(anchor) = self.anchor else {
+            self.anchor = Some(fix);
+            return None;
+        };
+        let (distance_m, bearing_deg) = leg_range_bearing(anchor, fix);
+        if distance_m >= self.min_move_m {
+            crate::log_info!(
+                "heading: {} after {}m",
+                bearing_deg as u32,
+                distance_m as u32
+            );
+            self.anchor = Some(fix);
+            self.heading_deg = Some(bearing_deg);
+        }
+        self.heading_deg
+    }
+
+    /// The heading returned by the last [`HeadingEstimator::update`].
+    pub fn heading_deg(&self) -> Option<f32> {
+        self.heading_deg
+    }
+
+    /// Forget the heading and position, e.g. after a long fix outage.
+    pub fn reset(&mut self) {
+        self.anchor = None;
+        self.heading_deg = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Roughly 1.1 m of latitude.
+    const STEP_DEG: f32 = 0.000_01;
+
+    #[test]
+    fn test_small_moves_keep_heading_and_large_moves_update_it() {
+        let mut heading = HeadingEstimator::new(5.0);
+        let start = Coord::new(10.0, 20.0);
+        assert_eq!(heading.update(start), None);
+        // ~2 m north: not enough for a first heading.
+        assert_eq!(
+            heading.update(Coord::new(10.0 + 2.0 * STEP_DEG, 20.0)),
+            None
+        );
+
+        // ~11 m north.
+        let north = Coord::new(10.0 + 10.0 * STEP_DEG, 20.0);
+        let deg = heading.update(north).unwrap();
+        assert!(!(1.0..=359.0).contains(&deg), "{deg}");
+
+        // Jitter of ~2 m east keeps north.
+        let jitter = Coord::new(north.lat, 20.0 + 2.0 * STEP_DEG);
+        assert_eq!(heading.update(jitter), Some(deg));
+
+        // ~11 m south of the last heading fix.
+        let deg = heading.update(Coord::new(10.0, 20.0)).unwrap();
+        assert!((deg - 180.0).abs() < 1.0, "{deg}");
+        assert_eq!(heading.heading_deg(), Some(deg));
+
+        heading.reset();
+        assert_eq!(heading.heading_deg(), None);
+    }
+}
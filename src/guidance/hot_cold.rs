@@ -0,0 +1,126 @@
+//! "Hot/cold" cues from the change in distance to the target.
+
+/// How the distance to the target changed since the last cue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// Closer than the reference distance by more than the dead-band.
+    Hotter,
+    /// Farther than the reference distance by more than the dead-band.
+    Colder,
+    /// Within the dead-band, or no reference yet.
+    Steady,
+}
+
+impl Cue {
+    /// Beep pitch for this cue: high for hotter, low for colder.
+    pub const fn pitch_hz(self) -> u32 {
+        match self {
+            Cue::Hotter => 1_800,
+            Cue::Colder => 500,
+            Cue::Steady => 1_000,
+        }
+    }
+
+    /// Pause between beeps: quick when hotter, slow when colder.
+    pub const fn interval_ms(self) -> u32 {
+        match self {
+            Cue::Hotter => 300,
+            Cue::Colder => 1_200,
+            Cue::Steady => 700,
+        }
+    }
+}
+
+/// Geocaching-style guidance that ignores bearing and only reports whether
+/// the user is getting closer or farther.
+///
+/// Each update is compared with a reference distance. The reference only
+/// moves when the distance changes by more than `dead_band_m`, so GPS jitter
+/// while standing still reads as [`Cue::Steady`] instead of flip-flopping,
+/// and a slow walk accumulates until it counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HotCold {
+    dead_band_m: f32,
+    reference_m: Option<f32>,
+}
+
+impl HotCold {
+    /// Create a tracker that ignores changes of `dead_band_m` or less.
+    pub const fn new(dead_band_m: f32) -> Self {
+        Self {
+            dead_band_m,
+            reference_m: None,
+        }
+    }
+
+    /// Feed the latest distance to the target.
+    pub fn update(&mut self, distance_m: f32) -> Cue {
+        let Some(reference) = self.reference_m else {
+            self.reference_m = Some(distance_m);
+            return Cue::Steady;
+        };
+        let change = distance_m - reference;
+        if change.abs() <= self.dead_band_m {
+            return Cue::Steady;
+        }
+        self.reference_m = Some(distance_m);
+        if change < 0.0 {
+            Cue::Hotter
+        } else {
+            Cue::Colder
+        }
+    }
+
+    /// Forget the reference, e.g. after switching targets.
+    pub fn reset(&mut self) {
+        self.reference_m = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_getting_closer_reads_hotter() {
+        let mut game = HotCold::new(2.0);
+        let cues: Vec<Cue> = [100.0, 97.0, 96.0, 94.5, 90.0]
+            .into_iter()
+            .map(|d| game.update(d))
+            .collect();
+        // 97 -> 96 is inside the dead-band; 97 -> 94.5 then counts.
+        assert_eq!(
+            cues,
+            [
+                Cue::Steady,
+                Cue::Hotter,
+                Cue::Steady,
+                Cue::Hotter,
+                Cue::Hotter
+            ]
+        );
+    }
+
+    #[test]
+    fn test_getting_farther_reads_colder_and_jitter_is_steady() {
+        let mut game = HotCold::new(2.0);
+        let cues: Vec<Cue> = [50.0, 51.0, 49.5, 50.8, 53.0, 58.0]
+            .into_iter()
+            .map(|d| game.update(d))
+            .collect();
+        assert_eq!(
+            cues,
+            [
+                Cue::Steady,
+                Cue::Steady,
+                Cue::Steady,
+                Cue::Steady,
+                Cue::Colder,
+                Cue::Colder
+            ]
+        );
+
+        game.reset();
+        assert_eq!(game.update(10.0), Cue::Steady);
+    }
+}
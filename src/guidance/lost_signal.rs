@@ -0,0 +1,169 @@
+//! Bridging short GPS dropouts by dead reckoning before alarming.
+
+use crate::{
+    drivers::{gps::FixSource, tick::TickSource},
+    nav::Coord,
+    notify::Notifier,
+};
+
+/// How much [`LostSignal`] trusts the position it reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalState {
+    /// The receiver delivered a fix on the last update.
+    Confident,
+    /// The fix is missing, and the position is extrapolated from the last
+    /// known speed and direction.
+    DeadReckoning,
+    /// The fix has been missing for longer than the timeout.
+    Lost,
+}
+
+/// Keeps guidance going through brief fix dropouts, e.g. under a bridge.
+///
+/// While fixes arrive they are passed straight through and the movement
+/// between the last two is remembered. When the receiver loses its fix the
+/// position is extrapolated from that movement for up to `dr_timeout_ms`,
+/// without any cue. If the fix is still missing after that, the state becomes
+/// [`SignalState::Lost`] and [`Notifier::error`] is raised once. The next
+/// fix goes straight back to [`SignalState::Confident`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LostSignal {
+    dr_timeout_ms: u32,
+    last_fix: Option<(Coord, u32)>,
+    /// Latitude and longitude change per millisecond between the last two fixes.
+    velocity: Option<(f32, f32)>,
+    state: SignalState,
+}
+
+impl LostSignal {
+    /// Create a monitor that dead-reckons for up to `dr_timeout_ms` after
+    /// the last fix.
+    pub const fn new(dr_timeout_ms: u32) -> Self {
+        Self {
+            dr_timeout_ms,
+            last_fix: None,
+            velocity: None,
+            state: SignalState::Confident,
+        }
+    }
+
+    /// Poll `gps` and return the position to guide by, or `None` when there
+    /// is none: before the first fix, or once the signal is
+    /// [`SignalState::Lost`].
+    ///
+    /// With only one fix seen so far, dead reckoning holds that position.
+    pub fn update<G, T, N>(&mut self, gps: &mut G, ticks: &T, notifier: &mut N) -> Option<Coord>
+    where
+        G: FixSource,
+        T: TickSource,
+        N: Notifier,
+    {
+        let now = ticks.now_ms();
+        if let Some(fix) = gps.latest_fix() {
+            self.velocity = match (self.state, self.last_fix) {
+                // Movement across a long outage says little about the
+                // current speed; wait for two fresh fixes.
+                (SignalState::Lost, _) | (_, None) => None,
+                (_, Some((prev, at))) => match now.wrapping_sub(at) {
+                    0 => self.velocity,
+                    dt => Some((
+                        (fix.lat - prev.lat) / dt as f32,
+                        (fix.lon - prev.lon) / dt as f32,
+                    )),
+                },
+            };
+            self.last_fix = Some((fix, now));
+            self.state = SignalState::Confident;
+            return Some(fix);
+        }
+
+        let (last, at) = self.last_fix?;
+        let elapsed = now.wrapping_sub(at);
+        if elapsed >= self.dr_timeout_ms {
+            if self.state != SignalState::Lost {
+                crate::log_warn!("signal: lost after {}ms", elapsed);
+                self.state = SignalState::Lost;
+                notifier.error();
+            }
+            return None;
+        }
+        self.state = SignalState::DeadReckoning;
+        let (d_lat, d_lon) = self.velocity.unwrap_or((0.0, 0.0));
+        let t = elapsed as f32;
+        Some(Coord::new(last.lat + d_lat * t, last.lon + d_lon * t))
+    }
+
+    /// The state after the last [`LostSignal::update`].
+    pub fn state(&self) -> SignalState {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::{CountingNotifier, FakeGps, ManualTicks};
+    use core::cell::Cell;
+
+    #[test]
+    fn test_confident_then_dead_reckoned_then_alarming() {
+        let start = u32::MAX - 1_500;
+        let ticks = ManualTicks(Cell::new(start));
+        let mut notifier = CountingNotifier::default();
+        let mut signal = LostSignal::new(3_000);
+        // Heading north at 0.0001° per second.
+        let stream = [
+            Some(Coord::new(10.0, 20.0)),
+            Some(Coord::new(10.000_1, 20.0)),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Coord::new(10.01, 20.0)),
+        ];
+
+        let mut seen = Vec::new();
+        for (step, fix) in stream.into_iter().enumerate() {
+            ticks.0.set(start.wrapping_add(step as u32 * 1_000));
+            let pos = signal.update(&mut FakeGps(fix), &ticks, &mut notifier);
+            seen.push((signal.state(), pos));
+        }
+
+        use SignalState::*;
+        let states: Vec<_> = seen.iter().map(|&(state, _)| state).collect();
+        assert_eq!(
+            states,
+            [
+                Confident,
+                Confident,
+                DeadReckoning,
+                DeadReckoning,
+                Lost,
+                Lost,
+                Lost,
+                Confident
+            ]
+        );
+        for (step, steps_north) in [(2, 2.0), (3, 3.0)] {
+            let pos = seen[step].1.unwrap();
+            let expected = Coord::new(10.0 + steps_north * 0.000_1, 20.0);
+            assert!(pos.approx_eq(&expected, 1e-5), "{pos:?}");
+        }
+        assert!(seen[4..7].iter().all(|&(_, pos)| pos.is_none()));
+        assert_eq!(notifier.errors, 1);
+    }
+
+    #[test]
+    fn test_no_position_before_first_fix() {
+        let ticks = ManualTicks(Cell::new(0));
+        let mut notifier = CountingNotifier::default();
+        let mut signal = LostSignal::new(3_000);
+        ticks.0.set(10_000);
+        assert_eq!(
+            signal.update(&mut FakeGps(None), &ticks, &mut notifier),
+            None
+        );
+        assert_eq!(notifier.errors, 0);
+    }
+}
@@ -0,0 +1,833 @@
+//! Turn-by-turn guidance along a [`Route`].
+
+use crate::{
+    drivers::{gps::GgaFix, tick::TickSource},
+    nav::{
+        Bearing, Coord,
+        arrival::{Arrival, Hysteresis},
+        leg_distance_m, leg_range_bearing,
+        route::Route,
+    },
+};
+
+pub mod cadence;
+#[cfg(feature = "debug-serial")]
+pub mod debug;
+pub mod decimate;
+pub mod heading;
+pub mod hot_cold;
+pub mod lost_signal;
+pub mod off_route;
+pub mod preset;
+pub mod preview;
+pub mod quiet;
+pub mod stereo;
+pub mod tones;
+
+pub use cadence::Cadence;
+pub use decimate::{Decimator, Rate};
+pub use heading::HeadingEstimator;
+pub use hot_cold::{Cue, HotCold};
+pub use lost_signal::{LostSignal, SignalState};
+pub use off_route::OffRoute;
+pub use preset::AudioPreset;
+pub use preview::{play_route_preview, route_preview};
+pub use quiet::QuietApproach;
+pub use stereo::stereo_cue;
+pub use tones::TurnTones;
+
+/// Distance beyond a leg's arrival radius needed to re-arm arrival detection.
+pub const ARRIVAL_MARGIN_M: f32 = 5.0;
+
+/// Default [`GuidanceConfig::dead_band_m`].
+pub const DEFAULT_DEAD_BAND_M: f32 = 3.0;
+
+/// Default [`GuidanceConfig::repeat_interval_ms`].
+pub const DEFAULT_REPEAT_INTERVAL_MS: u32 = 2_000;
+
+/// Default [`GuidanceConfig::volume_percent`]: the loudest a square wave gets.
+pub const DEFAULT_VOLUME_PERCENT: u8 = 50;
+
+/// Default [`GuidanceConfig::min_speed_m_s`], about a slow walk.
+pub const DEFAULT_MIN_SPEED_M_S: f32 = 0.5;
+
+/// Default [`GuidanceConfig::uere_m`], typical of a consumer receiver without
+/// DGPS or RTK.
+pub const DEFAULT_UERE_M: f32 = 4.0;
+
+/// Default [`GuidanceConfig::dr_timeout_ms`].
+pub const DEFAULT_DR_TIMEOUT_MS: u32 = 5_000;
+
+/// Default [`GuidanceConfig::min_move_m`], suited to walking.
+pub const DEFAULT_MIN_MOVE_M: f32 = 5.0;
+
+/// Default [`GuidanceConfig::min_fix_quality`]: a plain GPS fix.
+pub const DEFAULT_MIN_FIX_QUALITY: u8 = 1;
+
+/// Default [`GuidanceConfig::min_satellites`], the fewest that give a 3D fix.
+pub const DEFAULT_MIN_SATELLITES: u8 = 4;
+
+/// Trust order of a GGA fix quality, `0` for no fix, or `None` for
+/// qualities that are not real measured positions.
+fn fix_rank(quality: u8) -> Option<u8> {
+    match quality {
+        0 => Some(0),
+        6 => Some(1),
+        1 => Some(2),
+        2 => Some(3),
+        5 => Some(4),
+        4 => Some(5),
+        _ => None,
+    }
+}
+
+/// Tunable thresholds for [`Guidance`].
+///
+/// Build one with [`GuidanceConfig::builder`], setting only the values that
+/// differ from the defaults.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GuidanceConfig {
+    /// Smallest arrival radius used for any leg; legs with a tighter radius
+    /// are widened to it, e.g. to match the receiver's accuracy.
+    pub min_arrival_radius_m: f32,
+    /// Distance beyond a leg's arrival radius needed to re-arm arrival.
+    pub arrival_margin_m: f32,
+    /// Re-arm arrival after this long beyond the leg's radius instead of on
+    /// [`GuidanceConfig::arrival_margin_m`], for stationary waypoints. Needs
+    /// [`Guidance::update_with_ticks`]; see [`GuidanceConfig::hysteresis`].
+    pub arrival_rearm_ms: Option<u32>,
+    /// Distance from the target at which [`GuidanceEvent::PreAlert`] fires,
+    /// giving the user time to prepare for the turn. `0.0` disables it.
+    pub pre_alert_m: f32,
+    /// Hold the route on each arrival until [`Guidance::acknowledge`] is
+    /// called, e.g. for safety-critical waypoints that must not be passed
+    /// unnoticed. See [`crate::app::acknowledge_arrival`].
+    pub arrival_requires_ack: bool,
+    /// Distance change ignored by the [`HotCold`] cue from
+    /// [`GuidanceConfig::hot_cold`].
+    pub dead_band_m: f32,
+    /// Minimum time between repeated cues, used by
+    /// [`GuidanceConfig::decimator`].
+    pub repeat_interval_ms: u32,
+    /// Duty in percent for guidance cues; see [`crate::app::turn_cue`].
+    pub volume_percent: u8,
+    /// Pitch of each turn cue; see [`crate::app::turn_cue`].
+    pub turn_tones: TurnTones,
+    /// Ground speed below which the GPS course is too noisy to steer by; see
+    /// [`GuidanceConfig::heading_is_reliable`].
+    pub min_speed_m_s: f32,
+    /// Movement needed before [`GuidanceConfig::heading_estimator`] takes a
+    /// new heading; raise it for vehicles.
+    pub min_move_m: f32,
+    /// How long to dead-reckon through a lost fix before the "no GPS" alarm,
+    /// used by [`GuidanceConfig::lost_signal`].
+    pub dr_timeout_ms: u32,
+    /// Receiver range error in metres; times HDOP it gives the horizontal
+    /// accuracy checked by [`GuidanceConfig::radius_below_accuracy`].
+    pub uere_m: f32,
+    /// Lowest GGA fix quality [`GuidanceConfig::usable_fix`] accepts, e.g.
+    /// `2` to require DGPS. Qualities rank estimated (dead-reckoned, `6`),
+    /// GPS (`1`), DGPS (`2`), float RTK (`5`), then RTK (`4`); PPS (`3`),
+    /// manual input (`7`) and simulation (`8`) are never accepted.
+    pub min_fix_quality: u8,
+    /// Fewest satellites in the solution [`GuidanceConfig::usable_fix`]
+    /// accepts.
+    pub min_satellites: u8,
+}
+
+impl Default for GuidanceConfig {
+    fn default() -> Self {
+        Self {
+            min_arrival_radius_m: 0.0,
+            arrival_margin_m: ARRIVAL_MARGIN_M,
+            arrival_rearm_ms: None,
+            pre_alert_m: 0.0,
+            arrival_requires_ack: false,
+            dead_band_m: DEFAULT_DEAD_BAND_M,
+            repeat_interval_ms: DEFAULT_REPEAT_INTERVAL_MS,
+            volume_percent: DEFAULT_VOLUME_PERCENT,
+            turn_tones: TurnTones::default(),
+            min_speed_m_s: DEFAULT_MIN_SPEED_M_S,
+            min_move_m: DEFAULT_MIN_MOVE_M,
+            dr_timeout_ms: DEFAULT_DR_TIMEOUT_MS,
+            uere_m: DEFAULT_UERE_M,
+            min_fix_quality: DEFAULT_MIN_FIX_QUALITY,
+            min_satellites: DEFAULT_MIN_SATELLITES,
+        }
+    }
+}
+
+impl GuidanceConfig {
+    /// Start from the defaults and override individual thresholds.
+    pub fn builder() -> GuidanceConfigBuilder {
+        GuidanceConfigBuilder(Self::default())
+    }
+
+    /// The defaults with the audio settings of `preset`.
+    pub fn preset(preset: AudioPreset) -> Self {
+        Self::builder().preset(preset).build()
+    }
+
+    /// How a latched arrival re-arms: after
+    /// [`GuidanceConfig::arrival_rearm_ms`] if set, otherwise beyond
+    /// [`GuidanceConfig::arrival_margin_m`].
+    pub fn hysteresis(&self) -> Hysteresis {
+        match self.arrival_rearm_ms {
+            Some(ms) => Hysteresis::Time(ms),
+            None => Hysteresis::Distance(self.arrival_margin_m),
+        }
+    }
+
+    /// A [`HotCold`] tracker using [`GuidanceConfig::dead_band_m`].
+    pub fn hot_cold(&self) -> HotCold {
+        HotCold::new(self.dead_band_m)
+    }
+
+    /// A [`Decimator`] passing one update per
+    /// [`GuidanceConfig::repeat_interval_ms`].
+    pub fn decimator(&self) -> Decimator {
+        Decimator::new(Rate::EveryMs(self.repeat_interval_ms))
+    }
+
+    /// A [`HeadingEstimator`] using [`GuidanceConfig::min_move_m`].
+    pub fn heading_estimator(&self) -> HeadingEstimator {
+        HeadingEstimator::new(self.min_move_m)
+    }
+
+    /// A [`LostSignal`] monitor using [`GuidanceConfig::dr_timeout_ms`].
+    pub fn lost_signal(&self) -> LostSignal {
+        LostSignal::new(self.dr_timeout_ms)
+    }
+
+    /// `true`, with a logged warning, if an arrival radius of `radius_m` is
+    /// tighter than the accuracy implied by `hdop`.
+    ///
+    /// Such a radius may never be reached, or only by luck. The leg radius is
+    /// widened to [`GuidanceConfig::min_arrival_radius_m`] first, as in
+    /// guidance. Play a cue on `true` to tell the user when setting a radius.
+    pub fn radius_below_accuracy(&self, radius_m: f32, hdop: f32) -> bool {
+        let radius_m = radius_m.max(self.min_arrival_radius_m);
+        let accuracy_m = hdop * self.uere_m;
+        if radius_m >= accuracy_m {
+            return false;
+        }
+        crate::log_warn!(
+            "guidance: {}m radius below {}m accuracy",
+            radius_m as u32,
+            accuracy_m as u32
+        );
+        true
+    }
+
+    /// The position of `fix` if it is good enough to guide by, judged by
+    /// [`GuidanceConfig::min_fix_quality`] and
+    /// [`GuidanceConfig::min_satellites`].
+    ///
+    /// Pass only accepted positions to [`Guidance::update`]. While fixes are
+    /// rejected, keep playing the "acquiring" cue, e.g. by passing `false` to
+    /// [`FixAcquisition::update`](crate::drivers::gps::FixAcquisition::update).
+    pub fn usable_fix(&self, fix: &GgaFix) -> Option<Coord> {
+        let min_rank = fix_rank(self.min_fix_quality).unwrap_or(u8::MAX);
+        match fix_rank(fix.fix_quality) {
+            Some(rank) if rank > 0 && rank >= min_rank => {}
+            _ => return None,
+        }
+        if fix.satellites < self.min_satellites {
+            return None;
+        }
+        fix.position
+    }
+
+    /// `true` when moving fast enough for the GPS course to be used as the
+    /// heading.
+    pub fn heading_is_reliable(&self, speed_m_s: f32) -> bool {
+        speed_m_s >= self.min_speed_m_s
+    }
+}
+
+/// Builder for [`GuidanceConfig`]; see [`GuidanceConfig::builder`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GuidanceConfigBuilder(GuidanceConfig);
+
+impl GuidanceConfigBuilder {
+    /// See [`GuidanceConfig::min_arrival_radius_m`].
+    pub fn min_arrival_radius_m(mut self, metres: f32) -> Self {
+        self.0.min_arrival_radius_m = metres;
+        self
+    }
+
+    /// See [`GuidanceConfig::arrival_margin_m`].
+    pub fn arrival_margin_m(mut self, metres: f32) -> Self {
+        self.0.arrival_margin_m = metres;
+        self
+    }
+
+    /// See [`GuidanceConfig::arrival_rearm_ms`].
+    pub fn arrival_rearm_ms(mut self, ms: u32) -> Self {
+        self.0.arrival_rearm_ms = Some(ms);
+        self
+    }
+
+    /// See [`GuidanceConfig::pre_alert_m`].
+    pub fn pre_alert_m(mut self, metres: f32) -> Self {
+        self.0.pre_alert_m = metres;
+        self
+    }
+
+    /// See [`GuidanceConfig::arrival_requires_ack`].
+    pub fn arrival_requires_ack(mut self, required: bool) -> Self {
+        self.0.arrival_requires_ack = required;
+        self
+    }
+
+    /// See [`GuidanceConfig::dead_band_m`].
+    pub fn dead_band_m(mut self, metres: f32) -> Self {
+        self.0.dead_band_m = metres;
+        self
+    }
+
+    /// See [`GuidanceConfig::repeat_interval_ms`].
+    pub fn repeat_interval_ms(mut self, ms: u32) -> Self {
+        self.0.repeat_interval_ms = ms;
+        self
+    }
+
+    /// See [`GuidanceConfig::volume_percent`].
+    pub fn volume_percent(mut self, percent: u8) -> Self {
+        self.0.volume_percent = percent;
+        self
+    }
+
+    /// See [`GuidanceConfig::turn_tones`].
+    pub fn turn_tones(mut self, tones: TurnTones) -> Self {
+        self.0.turn_tones = tones;
+        self
+    }
+
+    /// Apply the audio settings of `preset`; later calls can still adjust
+    /// them.
+    pub fn preset(mut self, preset: AudioPreset) -> Self {
+        preset.apply(&mut self.0);
+        self
+    }
+
+    /// See [`GuidanceConfig::min_speed_m_s`].
+    pub fn min_speed_m_s(mut self, speed: f32) -> Self {
+        self.0.min_speed_m_s = speed;
+        self
+    }
+
+    /// See [`GuidanceConfig::min_move_m`].
+    pub fn min_move_m(mut self, metres: f32) -> Self {
+        self.0.min_move_m = metres;
+        self
+    }
+
+    /// See [`GuidanceConfig::dr_timeout_ms`].
+    pub fn dr_timeout_ms(mut self, ms: u32) -> Self {
+        self.0.dr_timeout_ms = ms;
+        self
+    }
+
+    /// See [`GuidanceConfig::uere_m`].
+    pub fn uere_m(mut self, metres: f32) -> Self {
+        self.0.uere_m = metres;
+        self
+    }
+
+    /// See [`GuidanceConfig::min_fix_quality`].
+    pub fn min_fix_quality(mut self, quality: u8) -> Self {
+        self.0.min_fix_quality = quality;
+        self
+    }
+
+    /// See [`GuidanceConfig::min_satellites`].
+    pub fn min_satellites(mut self, count: u8) -> Self {
+        self.0.min_satellites = count;
+        self
+    }
+
+    /// Finish building.
+    pub fn build(self) -> GuidanceConfig {
+        self.0
+    }
+}
+
+/// Which way to turn to face the target, from the heading-relative bearing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    /// Within ±15° of the current heading.
+    Ahead,
+    /// 15°–45° to the left.
+    SlightLeft,
+    /// 45°–120° to the left.
+    Left,
+    /// 120°–165° to the left.
+    SharpLeft,
+    /// 15°–45° to the right.
+    SlightRight,
+    /// 45°–120° to the right.
+    Right,
+    /// 120°–165° to the right.
+    SharpRight,
+    /// More than 165° either way: turn around.
+    Behind,
+}
+
+impl Turn {
+    /// Classify a bearing relative to the heading, in degrees (positive is
+    /// clockwise). Any angle is accepted and wrapped to `-180..180` first.
+    pub fn from_relative_deg(relative_deg: f32) -> Self {
+        let rel = Bearing::new(relative_deg).relative_to(Bearing::NORTH);
+        let (side_left, mag) = (rel < 0.0, rel.abs());
+        match (mag, side_left) {
+            (m, _) if m <= 15.0 => Turn::Ahead,
+            (m, _) if m > 165.0 => Turn::Behind,
+            (m, true) if m <= 45.0 => Turn::SlightLeft,
+            (m, true) if m <= 120.0 => Turn::Left,
+            (_, true) => Turn::SharpLeft,
+            (m, false) if m <= 45.0 => Turn::SlightRight,
+            (m, false) if m <= 120.0 => Turn::Right,
+            (_, false) => Turn::SharpRight,
+        }
+    }
+}
+
+/// Where the current target is relative to the user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Steering {
+    /// Distance to the target in metres.
+    pub distance_m: f32,
+    /// Bearing to the target in degrees `0..360` from true north.
+    pub bearing_deg: f32,
+    /// Turn needed from the current heading.
+    pub turn: Turn,
+}
+
+/// Something guidance wants the user to know about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuidanceEvent {
+    /// The user came within `pre_alert_m` of waypoint `index`.
+    ///
+    /// Fires at most once per leg, and not at all if the same fix already
+    /// counts as arrival.
+    PreAlert(usize),
+    /// Waypoint `index` was reached and the route moved on to the next leg.
+    ///
+    /// With [`GuidanceConfig::arrival_requires_ack`] the route stays on the
+    /// leg until [`Guidance::acknowledge`].
+    Arrived(usize),
+}
+
+/// Follows a route, detecting arrival at each waypoint in turn.
+#[derive(Debug, Clone)]
+pub struct Guidance<const N: usize> {
+    route: Route<N>,
+    config: GuidanceConfig,
+    arrival: Arrival,
+    pre_alerted: bool,
+    awaiting_ack: bool,
+    leg: usize,
+}
+
+impl<const N: usize> Guidance<N> {
+    /// Start guiding along `route` from its current leg with the default
+    /// [`GuidanceConfig`].
+    pub fn new(route: Route<N>) -> Self {
+        Self::with_config(route, GuidanceConfig::default())
+    }
+
+    /// Start guiding along `route` with custom thresholds.
+    pub fn with_config(route: Route<N>, config: GuidanceConfig) -> Self {
+        let leg = route.current_index();
+        Self {
+            arrival: leg_arrival(&route, &config),
+            route,
+            config,
+            pre_alerted: false,
+            awaiting_ack: false,
+            leg,
+        }
+    }
+
+    /// The thresholds in use.
+    pub fn config(&self) -> &GuidanceConfig {
+        &self.config
+    }
+
+    /// The route being followed.
+    pub fn route(&self) -> &Route<N> {
+        &self.route
+    }
+
+    /// Mutable access to the route, e.g. to skip or go back a leg.
+    ///
+    /// Leg changes are picked up on the next [`Guidance::update`].
+    pub fn route_mut(&mut self) -> &mut Route<N> {
+        &mut self.route
+    }
+
+    /// Distance, bearing and turn towards the current target, or `None` once
+    /// the route is complete.
+    ///
+    /// * `fix` – current position
+    /// * `heading_deg` – direction of travel, degrees from true north
+    pub fn steering(&self, fix: Coord, heading_deg: f32) -> Option<Steering> {
+        let target = self.route.target()?;
+        let (distance_m, bearing_deg) = leg_range_bearing(fix, target);
+        Some(Steering {
+            distance_m,
+            bearing_deg,
+            turn: Turn::from_relative_deg(
+                Bearing::new(bearing_deg).relative_to(Bearing::new(heading_deg)),
+            ),
+        })
+    }
+
+    /// Process a new position fix.
+    ///
+    /// Arrival uses the current leg's own radius (see
+    /// [`Route::push_with_radius`]); on arrival the route advances, unless
+    /// an acknowledgement is required, in which case no further events are
+    /// reported until [`Guidance::acknowledge`].
+    ///
+    /// Time does not advance here, so with
+    /// [`GuidanceConfig::arrival_rearm_ms`] use
+    /// [`Guidance::update_with_ticks`].
+    pub fn update(&mut self, fix: Coord) -> Option<GuidanceEvent> {
+        self.step(fix, |arrival, distance| arrival.update(distance))
+    }
+
+    /// Process a new position fix like [`Guidance::update`], timing
+    /// [`GuidanceConfig::arrival_rearm_ms`] on `ticks`.
+    pub fn update_with_ticks<T: TickSource>(
+        &mut self,
+        fix: Coord,
+        ticks: &T,
+    ) -> Option<GuidanceEvent> {
+        self.step(fix, |arrival, distance| {
+            arrival.update_with_ticks(distance, ticks)
+        })
+    }
+
+    fn step(
+        &mut self,
+        fix: Coord,
+        arrive: impl FnOnce(&mut Arrival, f32) -> bool,
+    ) -> Option<GuidanceEvent> {
+        self.sync_leg();
+        if self.awaiting_ack {
+            return None;
+        }
+        let target = self.route.target()?;
+        let distance = leg_distance_m(fix, target);
+        let index = self.leg;
+        if !arrive(&mut self.arrival, distance) {
+            if !self.pre_alerted
+                && distance <= self.config.pre_alert_m
+                && self.config.pre_alert_m > 0.0
+            {
+                self.pre_alerted = true;
+                crate::log_info!("guidance: pre-alert {} at {}m", index, distance as u32);
+                return Some(GuidanceEvent::PreAlert(index));
+            }
+            return None;
+        }
+        crate::log_info!("guidance: arrived {} at {}m", index, distance as u32);
+        if self.config.arrival_requires_ack {
+            self.awaiting_ack = true;
+        } else {
+            self.route.advance();
+            self.sync_leg();
+        }
+        Some(GuidanceEvent::Arrived(index))
+    }
+
+    /// [`GuidanceConfig::radius_below_accuracy`] for the current leg, e.g.
+    /// with the HDOP of each new GGA fix. `false` once the route is complete.
+    pub fn radius_below_accuracy(&self, hdop: f32) -> bool {
+        self.route
+            .target_radius_m()
+            .is_some_and(|radius_m| self.config.radius_below_accuracy(radius_m, hdop))
+    }
+
+    /// `true` between an arrival that needs acknowledging and
+    /// [`Guidance::acknowledge`].
+    pub fn is_awaiting_ack(&self) -> bool {
+        self.awaiting_ack
+    }
+
+    /// Confirm a held arrival and move on to the next leg. Does nothing if no
+    /// arrival is waiting.
+    pub fn acknowledge(&mut self) {
+        if self.awaiting_ack {
+            self.awaiting_ack = false;
+            self.route.advance();
+            self.sync_leg();
+        }
+    }
+
+    /// Re-arm arrival detection if the leg changed since the last update.
+    fn sync_leg(&mut self) {
+        if self.route.current_index() != self.leg {
+            self.leg = self.route.current_index();
+            self.arrival = leg_arrival(&self.route, &self.config);
+            self.pre_alerted = false;
+            self.awaiting_ack = false;
+        }
+    }
+}
+
+fn leg_arrival<const N: usize>(route: &Route<N>, config: &GuidanceConfig) -> Arrival {
+    let radius_m = route
+        .target_radius_m()
+        .unwrap_or(0.0)
+        .max(config.min_arrival_radius_m);
+    Arrival::with_hysteresis(radius_m, config.hysteresis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::ManualTicks;
+    use core::cell::Cell;
+
+    /// Roughly 11.1 m of latitude.
+    const STEP_DEG: f32 = 0.000_1;
+
+    #[test]
+    fn test_legs_with_different_radii_arrive_at_different_distances() {
+        let gate = Coord::new(10.0, 20.0);
+        let field = Coord::new(10.1, 20.0);
+        let mut route = Route::<2>::new();
+        route.push_with_radius(gate, 5.0).unwrap();
+        route.push_with_radius(field, 50.0).unwrap();
+        let mut guidance = Guidance::new(route);
+
+        // ~11 m from the gate: outside its 5 m radius.
+        assert_eq!(guidance.update(Coord::new(10.0 - STEP_DEG, 20.0)), None);
+        assert_eq!(guidance.update(gate), Some(GuidanceEvent::Arrived(0)));
+
+        // ~44 m short of the field: inside its 50 m radius.
+        let near_field = Coord::new(10.1 - 4.0 * STEP_DEG, 20.0);
+        assert_eq!(guidance.update(near_field), Some(GuidanceEvent::Arrived(1)));
+        assert!(guidance.route().is_complete());
+    }
+
+    #[test]
+    fn test_looped_route_re_arms_arrival_each_lap() {
+        let a = Coord::new(10.0, 20.0);
+        let b = Coord::new(10.01, 20.0);
+        let c = Coord::new(10.01, 20.01);
+        let mut route = Route::<3>::from_slice(&[a, b, c]).unwrap();
+        route.set_loop(true);
+        let mut guidance = Guidance::new(route);
+        // About a metre short, well inside the arrival radius.
+        let near = |c: Coord| Coord::new(c.lat - STEP_DEG / 10.0, c.lon);
+
+        let mut arrivals = Vec::new();
+        for _ in 0..2 {
+            for fix in [a, b, c] {
+                arrivals.extend(guidance.update(near(fix)));
+            }
+        }
+        use GuidanceEvent::Arrived;
+        assert_eq!(
+            arrivals,
+            [
+                Arrived(0),
+                Arrived(1),
+                Arrived(2),
+                Arrived(0),
+                Arrived(1),
+                Arrived(2)
+            ]
+        );
+        assert!(!guidance.route().is_complete());
+
+        // A single-waypoint patrol needs the user to leave and come back.
+        let mut guidance = Guidance::new({
+            let mut route = Route::<1>::from_slice(&[a]).unwrap();
+            route.set_loop(true);
+            route
+        });
+        assert_eq!(guidance.update(near(a)), Some(Arrived(0)));
+        assert_eq!(guidance.update(near(a)), None);
+        assert_eq!(guidance.update(b), None);
+        assert_eq!(guidance.update(near(a)), Some(Arrived(0)));
+    }
+
+    #[test]
+    fn test_time_hysteresis_re_arms_a_stationary_patrol() {
+        let a = Coord::new(10.0, 20.0);
+        let mut route = Route::<1>::from_slice(&[a]).unwrap();
+        route.set_loop(true);
+        let config = GuidanceConfig::builder().arrival_rearm_ms(30_000).build();
+        let mut guidance = Guidance::with_config(route, config);
+        let ticks = ManualTicks(Cell::new(0));
+        // About 11 m out: beyond the 10 m radius but inside the 5 m margin.
+        let jitter = Coord::new(a.lat - STEP_DEG, a.lon);
+
+        let mut at = |ms: u32, fix: Coord| {
+            ticks.0.set(ms);
+            guidance.update_with_ticks(fix, &ticks)
+        };
+        assert_eq!(at(0, a), Some(GuidanceEvent::Arrived(0)));
+        assert_eq!(at(10_000, jitter), None);
+        assert_eq!(at(39_999, jitter), None);
+        assert_eq!(at(40_000, jitter), None);
+        assert_eq!(at(41_000, a), Some(GuidanceEvent::Arrived(0)));
+
+        // The default distance margin never re-arms on the same jitter.
+        let mut guidance = Guidance::new(guidance.route().clone());
+        let mut at = |ms: u32, fix: Coord| {
+            ticks.0.set(ms);
+            guidance.update_with_ticks(fix, &ticks)
+        };
+        assert_eq!(at(0, a), Some(GuidanceEvent::Arrived(0)));
+        assert_eq!(at(60_000, jitter), None);
+        assert_eq!(at(61_000, a), None);
+    }
+
+    #[test]
+    fn test_zero_pre_alert_never_fires_on_the_waypoint() {
+        let a = Coord::new(10.0, 20.0);
+        let route = Route::<1>::from_slice(&[a]).unwrap();
+        let mut guidance = Guidance::new(route);
+        assert_eq!(guidance.config().pre_alert_m, 0.0);
+        // Exactly on the waypoint, the distance is 0.0 <= pre_alert_m.
+        assert_eq!(guidance.update(a), Some(GuidanceEvent::Arrived(0)));
+    }
+
+    #[test]
+    fn test_pre_alert_fires_once_per_leg() {
+        let a = Coord::new(10.0, 20.0);
+        let b = Coord::new(10.01, 20.0);
+        let route = Route::<2>::from_slice(&[a, b]).unwrap();
+        let config = GuidanceConfig::builder().pre_alert_m(50.0).build();
+        let mut guidance = Guidance::with_config(route, config);
+
+        let towards = |target: Coord, steps: f32| Coord::new(target.lat - steps * STEP_DEG, 20.0);
+        let events: Vec<_> = [8.0, 4.0, 3.0, 5.0, 2.0, 0.0]
+            .into_iter()
+            .map(|steps| guidance.update(towards(a, steps)))
+            .collect();
+        assert_eq!(
+            events,
+            [
+                None,
+                Some(GuidanceEvent::PreAlert(0)),
+                None,
+                None,
+                None,
+                Some(GuidanceEvent::Arrived(0)),
+            ]
+        );
+
+        assert_eq!(guidance.update(towards(b, 6.0)), None);
+        assert_eq!(
+            guidance.update(towards(b, 3.0)),
+            Some(GuidanceEvent::PreAlert(1))
+        );
+        assert_eq!(guidance.update(towards(b, 2.0)), None);
+    }
+
+    #[test]
+    fn test_builder_defaults_and_overrides() {
+        assert_eq!(GuidanceConfig::builder().build(), GuidanceConfig::default());
+
+        let config = GuidanceConfig::builder()
+            .min_arrival_radius_m(15.0)
+            .min_speed_m_s(1.0)
+            .build();
+        assert_eq!(config.arrival_margin_m, ARRIVAL_MARGIN_M);
+        assert!(!config.heading_is_reliable(0.8));
+        assert!(GuidanceConfig::default().heading_is_reliable(0.8));
+
+        // A 5 m gate is widened to the 15 m floor: ~11 m away now arrives.
+        let gate = Coord::new(10.0, 20.0);
+        let mut route = Route::<1>::new();
+        route.push_with_radius(gate, 5.0).unwrap();
+        let near = Coord::new(10.0 - STEP_DEG, 20.0);
+        assert_eq!(Guidance::new(route.clone()).update(near), None);
+        assert_eq!(
+            Guidance::with_config(route, config).update(near),
+            Some(GuidanceEvent::Arrived(0))
+        );
+    }
+
+    #[test]
+    fn test_radius_below_hdop_accuracy_warns() {
+        let config = GuidanceConfig::default();
+        // HDOP 1.2 × 4 m UERE ≈ 4.8 m.
+        assert!(config.radius_below_accuracy(1.0, 1.2));
+        assert!(!config.radius_below_accuracy(5.0, 1.2));
+        assert!(!config.radius_below_accuracy(1.0, 0.2));
+
+        let config = GuidanceConfig::builder().min_arrival_radius_m(10.0).build();
+        assert!(!config.radius_below_accuracy(1.0, 1.2));
+        assert!(config.radius_below_accuracy(1.0, 3.0));
+
+        let mut route = Route::<2>::new();
+        route.push_with_radius(Coord::new(10.0, 20.0), 2.0).unwrap();
+        route
+            .push_with_radius(Coord::new(10.1, 20.0), 20.0)
+            .unwrap();
+        let mut guidance = Guidance::new(route);
+        assert!(guidance.radius_below_accuracy(1.5));
+        guidance.route_mut().advance();
+        assert!(!guidance.radius_below_accuracy(1.5));
+        guidance.route_mut().advance();
+        assert!(!guidance.radius_below_accuracy(50.0));
+    }
+
+    #[test]
+    fn test_usable_fix_requires_quality_and_satellites() {
+        let fix = |fix_quality, satellites| GgaFix {
+            time: None,
+            position: Some(Coord::new(10.0, 20.0)),
+            fix_quality,
+            satellites,
+            hdop: Some(1.0),
+            altitude_m: None,
+        };
+        let config = GuidanceConfig::default();
+        assert_eq!(config.usable_fix(&fix(1, 3)), None);
+        assert_eq!(config.usable_fix(&fix(1, 8)), Some(Coord::new(10.0, 20.0)));
+        assert_eq!(config.usable_fix(&fix(0, 8)), None);
+        // Manual input and simulation are not measurements, however ranked.
+        assert_eq!(config.usable_fix(&fix(7, 8)), None);
+        assert_eq!(config.usable_fix(&fix(8, 8)), None);
+        assert!(config.usable_fix(&fix(6, 8)).is_none());
+        assert!(config.usable_fix(&fix(4, 8)).is_some());
+
+        let config = GuidanceConfig::builder()
+            .min_fix_quality(2)
+            .min_satellites(6)
+            .build();
+        assert_eq!(config.usable_fix(&fix(1, 8)), None);
+        assert_eq!(config.usable_fix(&fix(6, 8)), None);
+        assert_eq!(config.usable_fix(&fix(2, 5)), None);
+        assert!(config.usable_fix(&fix(2, 8)).is_some());
+        assert!(config.usable_fix(&fix(5, 8)).is_some());
+    }
+
+    #[test]
+    fn test_turn_classification_wraps_and_picks_side() {
+        let cases = [
+            (0.0, Turn::Ahead),
+            (-14.0, Turn::Ahead),
+            (30.0, Turn::SlightRight),
+            (-30.0, Turn::SlightLeft),
+            (90.0, Turn::Right),
+            (270.0, Turn::Left),
+            (-150.0, Turn::SharpLeft),
+            (150.0 + 360.0, Turn::SharpRight),
+            (180.0, Turn::Behind),
+            (-170.0, Turn::Behind),
+        ];
+        for (rel, turn) in cases {
+            assert_eq!(Turn::from_relative_deg(rel), turn, "{rel}");
+        }
+    }
+}
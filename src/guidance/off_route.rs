@@ -0,0 +1,123 @@
+//! Off-route alarm driven by cross-track error.
+
+use crate::{
+    nav::{Coord, leg_cross_track_m},
+    notify::Notifier,
+};
+
+/// Raises [`Notifier::error`] once when the user strays from the leg.
+///
+/// The alarm fires only after the cross-track distance has stayed above
+/// `threshold_m` for `hold_ms`, so a single noisy fix does not trigger it.
+/// It re-arms once the distance drops below `threshold_m - margin_m`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffRoute {
+    threshold_m: f32,
+    margin_m: f32,
+    hold_ms: u32,
+    over_since: Option<u32>,
+    active: bool,
+}
+
+impl OffRoute {
+    /// Create a detector.
+    ///
+    /// * `threshold_m` – sideways distance from the track that counts as off route
+    /// * `margin_m` – how far back inside the threshold the user must come to clear
+    /// * `hold_ms` – how long the threshold must be exceeded before alarming
+    pub const fn new(threshold_m: f32, margin_m: f32, hold_ms: u32) -> Self {
+        Self {
+            threshold_m,
+            margin_m,
+            hold_ms,
+            over_since: None,
+            active: false,
+        }
+    }
+
+    /// Check `fix` against the leg from `start` to `end`.
+    ///
+    /// Returns `true` on the update that raised the alarm.
+    pub fn update<N: Notifier>(
+        &mut self,
+        start: Coord,
+        end: Coord,
+        fix: Coord,
+        now_ms: u32,
+        notifier: &mut N,
+    ) -> bool {
+        let cross_track_m = leg_cross_track_m(start, end, fix);
+        self.update_distance(cross_track_m, now_ms, notifier)
+    }
+
+    /// Feed a precomputed cross-track distance (either sign).
+    ///
+    /// Returns `true` on the update that raised the alarm.
+    pub fn update_distance<N: Notifier>(
+        &mut self,
+        cross_track_m: f32,
+        now_ms: u32,
+        notifier: &mut N,
+    ) -> bool {
+        let off = cross_track_m.abs();
+        if self.active {
+            if off < self.threshold_m - self.margin_m {
+                self.active = false;
+                self.over_since = None;
+            }
+            return false;
+        }
+        if off <= self.threshold_m {
+            self.over_since = None;
+            return false;
+        }
+        let since = *self.over_since.get_or_insert(now_ms);
+        if now_ms.wrapping_sub(since) < self.hold_ms {
+            return false;
+        }
+        self.active = true;
+        notifier.error();
+        true
+    }
+
+    /// Whether the off-route alarm is currently raised.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::CountingNotifier;
+
+    #[test]
+    fn test_drift_off_and_back_alarms_once() {
+        let mut off_route = OffRoute::new(20.0, 5.0, 3_000);
+        let mut notifier = CountingNotifier::default();
+        // One fix per second: brief spike, sustained drift, wobble, return.
+        let path = [
+            5.0, 25.0, 10.0, 22.0, 24.0, 30.0, 35.0, 18.0, 21.0, 16.0, 14.0, 26.0, 27.0, 28.0,
+        ];
+        let raised: Vec<usize> = path
+            .iter()
+            .enumerate()
+            .filter(|&(t, &d)| off_route.update_distance(d, t as u32 * 1_000, &mut notifier))
+            .map(|(t, _)| t)
+            .collect();
+
+        assert_eq!(raised, [6]);
+        assert_eq!(notifier.errors, 1);
+        assert!(!off_route.is_active());
+    }
+
+    #[test]
+    fn test_update_uses_leg_geometry() {
+        let mut off_route = OffRoute::new(50.0, 10.0, 0);
+        let mut notifier = CountingNotifier::default();
+        let (start, end) = (Coord::new(10.0, 20.0), Coord::new(10.1, 20.0));
+        assert!(!off_route.update(start, end, Coord::new(10.05, 20.0003), 0, &mut notifier));
+        assert!(off_route.update(start, end, Coord::new(10.05, 19.999), 1, &mut notifier));
+        assert_eq!(notifier.errors, 1);
+    }
+}
@@ -0,0 +1,78 @@
+//! Named bundles of the audio settings in [`GuidanceConfig`].
+
+use super::{DEFAULT_REPEAT_INTERVAL_MS, DEFAULT_VOLUME_PERCENT, GuidanceConfig, TurnTones, tones};
+
+/// A coherent "audio language": volume, repeat rate and turn pitches chosen
+/// to go together.
+///
+/// Apply one with [`GuidanceConfig::preset`] or
+/// [`GuidanceConfigBuilder::preset`](super::GuidanceConfigBuilder::preset),
+/// then tweak individual fields as needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioPreset {
+    /// Quiet, low and unhurried, for shared or indoor spaces.
+    Subtle,
+    /// The defaults.
+    Standard,
+    /// Full volume near a piezo's resonance and frequent repeats, for wind
+    /// and traffic.
+    Loud,
+    /// A softer timbre from middle C, with turns spanning about an octave.
+    Musical,
+}
+
+impl AudioPreset {
+    /// Overwrite the audio settings of `config`, keeping everything else.
+    pub fn apply(self, config: &mut GuidanceConfig) {
+        let (volume_percent, repeat_interval_ms, base_hz, hz_per_deg) = match self {
+            AudioPreset::Subtle => (10, 4_000, 600, 2.0),
+            AudioPreset::Standard => (
+                DEFAULT_VOLUME_PERCENT,
+                DEFAULT_REPEAT_INTERVAL_MS,
+                tones::DEFAULT_BASE_HZ,
+                tones::DEFAULT_HZ_PER_DEG,
+            ),
+            AudioPreset::Loud => (50, 1_000, 2_000, 8.0),
+            AudioPreset::Musical => (25, 2_000, 262, 1.5),
+        };
+        config.volume_percent = volume_percent;
+        config.repeat_interval_ms = repeat_interval_ms;
+        config.turn_tones = TurnTones {
+            base_hz,
+            hz_per_deg,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guidance::Turn;
+
+    #[test]
+    fn test_presets_sound_different_for_the_same_turn() {
+        let subtle = GuidanceConfig::preset(AudioPreset::Subtle);
+        let loud = GuidanceConfig::preset(AudioPreset::Loud);
+        assert_ne!(
+            subtle.turn_tones.for_turn(Turn::Left),
+            loud.turn_tones.for_turn(Turn::Left)
+        );
+        assert!(subtle.volume_percent < loud.volume_percent);
+        assert!(subtle.repeat_interval_ms > loud.repeat_interval_ms);
+
+        assert_eq!(
+            GuidanceConfig::preset(AudioPreset::Standard),
+            GuidanceConfig::default()
+        );
+
+        // Non-audio settings survive, and presets can be tweaked afterwards.
+        let config = GuidanceConfig::builder()
+            .min_satellites(7)
+            .preset(AudioPreset::Musical)
+            .volume_percent(40)
+            .build();
+        assert_eq!(config.min_satellites, 7);
+        assert_eq!(config.volume_percent, 40);
+        assert_eq!(config.turn_tones.for_turn(Turn::Behind), 532);
+    }
+}
@@ -0,0 +1,119 @@
+//! Audible summary of a [`Route`] before setting off.
+
+use core::iter;
+
+use embedded_hal::{delay::DelayNs, digital::OutputPin, pwm::SetDutyCycle};
+
+use crate::{
+    drivers::buzzer::{Buzzer, COUNT_BEEP_HZ, SetFrequency},
+    nav::{leg_distance_m, route::Route},
+};
+
+/// Length of each leg-count beep, and of the gap between them.
+pub const PREVIEW_UNIT_MS: u32 = 150;
+/// Silence between the leg count and the distance tone.
+pub const PREVIEW_GAP_MS: u32 = 500;
+/// Pitch the distance tone starts from.
+pub const PREVIEW_START_HZ: u32 = 500;
+/// How far the distance tone rises per kilometre of route.
+pub const PREVIEW_HZ_PER_KM: u32 = 250;
+/// Highest pitch the distance tone reaches, however long the route.
+pub const PREVIEW_MAX_HZ: u32 = 4_000;
+/// Number of steps in the distance tone.
+pub const PREVIEW_RISE_STEPS: u32 = 8;
+/// Length of the whole distance tone.
+pub const PREVIEW_RISE_MS: u32 = 800;
+
+/// Pitch the distance tone rises to for a route of `total_m` metres.
+pub fn preview_top_hz(total_m: f32) -> u32 {
+    let rise = (total_m.max(0.0) * PREVIEW_HZ_PER_KM as f32 / 1_000.0) as u32;
+    PREVIEW_START_HZ.saturating_add(rise).min(PREVIEW_MAX_HZ)
+}
+
+/// Notes summarising `route`, as `(frequency_hz, duty_percent, duration_ms)`
+/// for [`Buzzer::melody`].
+///
+/// One [`COUNT_BEEP_HZ`] beep per waypoint, then after [`PREVIEW_GAP_MS`] a
+/// tone climbing from [`PREVIEW_START_HZ`] to [`preview_top_hz`] of the
+/// distance from the first waypoint to the last, so a longer route rises
+/// higher. Rests are notes at 0 Hz. An empty route yields nothing.
+pub fn route_preview<const N: usize>(route: &Route<N>) -> impl Iterator<Item = (u32, u8, u32)> {
+    let waypoints = route.waypoints();
+    let total_m: f32 = waypoints
+        .windows(2)
+        .map(|leg| leg_distance_m(leg[0], leg[1]))
+        .sum();
+    let top_hz = preview_top_hz(total_m);
+    let step_ms = PREVIEW_RISE_MS / PREVIEW_RISE_STEPS;
+
+    let beeps = (0..waypoints.len()).flat_map(|i| {
+        let gap = (i > 0).then_some((0, 0, PREVIEW_UNIT_MS));
+        gap.into_iter()
+            .chain(iter::once((COUNT_BEEP_HZ, 50, PREVIEW_UNIT_MS)))
+    });
+    let rise = (0..PREVIEW_RISE_STEPS).map(move |i| {
+        let hz = PREVIEW_START_HZ + (top_hz - PREVIEW_START_HZ) * i / (PREVIEW_RISE_STEPS - 1);
+        (hz, 50, step_ms)
+    });
+    let distance = iter::once((0, 0, PREVIEW_GAP_MS))
+        .chain(rise)
+        .take(if waypoints.is_empty() { 0 } else { usize::MAX });
+    beeps.chain(distance)
+}
+
+/// Play [`route_preview`] of `route` on `buzzer`, e.g. after loading a route
+/// so the user can check it is the one they meant.
+pub fn play_route_preview<PWM, D, EN, const N: usize>(
+    buzzer: &mut Buzzer<PWM, D, EN>,
+    route: &Route<N>,
+) -> Result<(), <PWM as SetFrequency>::Error>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+    EN: OutputPin,
+{
+    buzzer.melody(route_preview(route))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::BeepLog;
+    use crate::{firmware::shared::delay::NopDelay, nav::Coord};
+
+    /// A route of `n` waypoints, `spacing_deg` of latitude apart.
+    fn route(n: usize, spacing_deg: f32) -> Route<8> {
+        let mut route = Route::new();
+        for i in 0..n {
+            route
+                .push(Coord::new(10.0 + i as f32 * spacing_deg, 20.0))
+                .unwrap();
+        }
+        route
+    }
+
+    #[test]
+    fn test_four_legs_beep_four_times_then_rise() {
+        // Three gaps of ~1.1 km between four waypoints.
+        let route = route(4, 0.01);
+        let mut buzzer = Buzzer::new(BeepLog::default(), NopDelay::new());
+        play_route_preview(&mut buzzer, &route).unwrap();
+        let (pwm, _) = buzzer.release();
+
+        let (count, rise) = pwm.beeps.split_at(4);
+        assert_eq!(count, [COUNT_BEEP_HZ; 4]);
+        assert_eq!(rise.len(), PREVIEW_RISE_STEPS as usize);
+        assert!(rise.windows(2).all(|w| w[0] < w[1]), "{rise:?}");
+        assert_eq!(rise[0], PREVIEW_START_HZ);
+        // ~3.3 km at 250 Hz per km.
+        assert!((1_320..=1_340).contains(rise.last().unwrap()), "{rise:?}");
+    }
+
+    #[test]
+    fn test_longer_route_rises_higher_up_to_cap() {
+        let top = |route: &Route<8>| route_preview(route).last().unwrap().0;
+        assert!(top(&route(2, 0.02)) > top(&route(2, 0.01)));
+        assert_eq!(top(&route(2, 1.0)), PREVIEW_MAX_HZ);
+        assert_eq!(route_preview(&route(0, 0.01)).count(), 0);
+    }
+}
@@ -0,0 +1,91 @@
+//! Distance-to-volume mapping: cues get softer as the target gets closer.
+
+use crate::util::math;
+
+/// Distance at or below which [`QuietApproach::default`] plays at its
+/// minimum volume.
+pub const DEFAULT_NEAR_M: f32 = 5.0;
+/// Distance at or beyond which [`QuietApproach::default`] plays at its
+/// maximum volume.
+pub const DEFAULT_FAR_M: f32 = 50.0;
+/// Quietest duty used by [`QuietApproach::default`].
+pub const DEFAULT_MIN_DUTY_PERCENT: u8 = 10;
+/// Loudest duty used by [`QuietApproach::default`].
+pub const DEFAULT_MAX_DUTY_PERCENT: u8 = 50;
+
+/// Maps the remaining distance to a buzzer duty, so cues fade as the user
+/// closes in instead of blasting when they are basically there.
+///
+/// The duty rises linearly from `min_duty_percent` at `near_m` to
+/// `max_duty_percent` at `far_m` and is held outside that range. Pass the
+/// result as `duty_percent` to [`Buzzer::tone`]; it only changes the
+/// loudness with a PWM output that supports duty (fast PWM on the AVR).
+/// Duties above 50% get quieter again on a square wave, so keep
+/// `max_duty_percent` at or below it.
+///
+/// [`Buzzer::tone`]: crate::drivers::buzzer::Buzzer::tone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuietApproach {
+    /// Distance at which the quietest cue plays.
+    pub near_m: f32,
+    /// Distance at which the loudest cue plays.
+    pub far_m: f32,
+    /// Duty at or inside `near_m`.
+    pub min_duty_percent: u8,
+    /// Duty at or beyond `far_m`.
+    pub max_duty_percent: u8,
+}
+
+impl Default for QuietApproach {
+    fn default() -> Self {
+        Self {
+            near_m: DEFAULT_NEAR_M,
+            far_m: DEFAULT_FAR_M,
+            min_duty_percent: DEFAULT_MIN_DUTY_PERCENT,
+            max_duty_percent: DEFAULT_MAX_DUTY_PERCENT,
+        }
+    }
+}
+
+impl QuietApproach {
+    /// Duty in percent for a cue `distance_m` from the target.
+    pub fn duty_percent(&self, distance_m: f32) -> u8 {
+        let duty = math::lerp_clamped(
+            distance_m,
+            self.near_m,
+            self.far_m,
+            f32::from(self.min_duty_percent),
+            f32::from(self.max_duty_percent),
+        );
+        math::round_u32(duty) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_falls_monotonically_on_approach() {
+        let quiet = QuietApproach::default();
+        let duties: Vec<u8> = (0..=60)
+            .rev()
+            .map(|d| quiet.duty_percent(d as f32))
+            .collect();
+        assert!(duties.windows(2).all(|w| w[1] <= w[0]), "{duties:?}");
+        assert_eq!(duties[0], DEFAULT_MAX_DUTY_PERCENT);
+        assert_eq!(*duties.last().unwrap(), DEFAULT_MIN_DUTY_PERCENT);
+        assert_eq!(quiet.duty_percent(27.5), 30);
+    }
+
+    #[test]
+    fn test_zero_width_range_switches_at_near() {
+        let quiet = QuietApproach {
+            near_m: 10.0,
+            far_m: 10.0,
+            ..QuietApproach::default()
+        };
+        assert_eq!(quiet.duty_percent(10.0), DEFAULT_MIN_DUTY_PERCENT);
+        assert_eq!(quiet.duty_percent(10.5), DEFAULT_MAX_DUTY_PERCENT);
+    }
+}
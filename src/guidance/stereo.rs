@@ -0,0 +1,60 @@
+//! Left/right buzzer cue for boards with two buzzers.
+
+use super::Turn;
+use crate::notify::Notifier;
+
+/// Signal the turn towards the target on a left and a right notifier, like a
+/// parking sensor.
+///
+/// Any left turn beeps only `left` and any right turn only `right`
+/// ([`Notifier::confirm`]). Straight ahead beeps both; a target behind the
+/// user plays [`Notifier::error`] on both to mean "turn around". Returns the
+/// [`Turn`] that was signalled.
+///
+/// `relative_bearing_deg` is the bearing to the target minus the heading,
+/// positive clockwise; any angle is accepted.
+pub fn stereo_cue<L, R>(relative_bearing_deg: f32, left: &mut L, right: &mut R) -> Turn
+where
+    L: Notifier,
+    R: Notifier,
+{
+    let turn = Turn::from_relative_deg(relative_bearing_deg);
+    match turn {
+        Turn::Ahead => {
+            left.confirm();
+            right.confirm();
+        }
+        Turn::SlightLeft | Turn::Left | Turn::SharpLeft => left.confirm(),
+        Turn::SlightRight | Turn::Right | Turn::SharpRight => right.confirm(),
+        Turn::Behind => {
+            left.error();
+            right.error();
+        }
+    }
+    turn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::CountingNotifier;
+
+    #[test]
+    fn test_left_turn_signals_only_left() {
+        let (mut left, mut right) = (CountingNotifier::default(), CountingNotifier::default());
+        assert_eq!(stereo_cue(-60.0, &mut left, &mut right), Turn::Left);
+        assert_eq!(stereo_cue(330.0, &mut left, &mut right), Turn::SlightLeft);
+        assert_eq!((left.confirms, left.errors), (2, 0));
+        assert_eq!((right.confirms, right.errors), (0, 0));
+    }
+
+    #[test]
+    fn test_straight_signals_both_and_behind_errors_both() {
+        let (mut left, mut right) = (CountingNotifier::default(), CountingNotifier::default());
+        stereo_cue(5.0, &mut left, &mut right);
+        stereo_cue(100.0, &mut left, &mut right);
+        stereo_cue(180.0, &mut left, &mut right);
+        assert_eq!((left.confirms, left.errors), (1, 1));
+        assert_eq!((right.confirms, right.errors), (2, 1));
+    }
+}
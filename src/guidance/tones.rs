@@ -0,0 +1,105 @@
+//! Turn-to-pitch mapping: the sharper the turn, the higher the beep.
+
+use super::Turn;
+use crate::{nav::Bearing, util::math};
+
+/// Pitch for straight ahead when using [`TurnTones::default`].
+pub const DEFAULT_BASE_HZ: u32 = 800;
+/// Pitch rise per degree of turn when using [`TurnTones::default`].
+pub const DEFAULT_HZ_PER_DEG: f32 = 8.0;
+/// Highest pitch [`TurnTones::frequency_hz`] returns, the top of human
+/// hearing. [`Buzzer::set_frequency_limits`] narrows it to the transducer.
+///
+/// [`Buzzer::set_frequency_limits`]: crate::drivers::buzzer::Buzzer::set_frequency_limits
+pub const MAX_TURN_HZ: u32 = 20_000;
+
+/// Maps how far the user must turn to a beep frequency.
+///
+/// The pitch is `base_hz + hz_per_deg · |turn|`, capped at [`MAX_TURN_HZ`],
+/// so it rises with the size of the turn on either side and peaks when the
+/// target is behind. Combine it
+/// with a side cue such as [`stereo_cue`](super::stereo_cue) to give left and
+/// right.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurnTones {
+    /// Frequency for no turn at all.
+    pub base_hz: u32,
+    /// Extra hertz per degree of turn.
+    pub hz_per_deg: f32,
+}
+
+impl Default for TurnTones {
+    fn default() -> Self {
+        Self {
+            base_hz: DEFAULT_BASE_HZ,
+            hz_per_deg: DEFAULT_HZ_PER_DEG,
+        }
+    }
+}
+
+impl TurnTones {
+    /// Pitch for a heading-relative bearing in degrees (any angle).
+    ///
+    /// Saturates at [`MAX_TURN_HZ`], so configured steps too large for the
+    /// range cannot overflow.
+    pub fn frequency_hz(&self, relative_deg: f32) -> u32 {
+        let magnitude = Bearing::new(relative_deg).relative_to(Bearing::NORTH).abs();
+        let rise = math::round_u32(self.hz_per_deg * magnitude);
+        self.base_hz.saturating_add(rise).min(MAX_TURN_HZ)
+    }
+
+    /// Pitch for a [`Turn`] category, using the middle of its angle range.
+    pub fn for_turn(&self, turn: Turn) -> u32 {
+        let magnitude = match turn {
+            Turn::Ahead => 0.0,
+            Turn::SlightLeft | Turn::SlightRight => 30.0,
+            Turn::Left | Turn::Right => 82.5,
+            Turn::SharpLeft | Turn::SharpRight => 142.5,
+            Turn::Behind => 180.0,
+        };
+        self.frequency_hz(magnitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sharp_left_is_higher_than_slight_left() {
+        let tones = TurnTones::default();
+        let slight = tones.for_turn(Turn::SlightLeft);
+        let sharp = tones.for_turn(Turn::SharpLeft);
+        assert_eq!(slight, 1_040);
+        assert_eq!(sharp, 1_940);
+        assert_eq!(tones.for_turn(Turn::SharpRight), sharp);
+        assert_eq!(tones.for_turn(Turn::Ahead), DEFAULT_BASE_HZ);
+    }
+
+    #[test]
+    fn test_frequency_scales_with_magnitude_on_both_sides() {
+        let tones = TurnTones {
+            base_hz: 500,
+            hz_per_deg: 2.0,
+        };
+        assert_eq!(tones.frequency_hz(-40.0), 580);
+        assert_eq!(tones.frequency_hz(40.0), 580);
+        assert_eq!(tones.frequency_hz(350.0), 520);
+        assert_eq!(tones.frequency_hz(180.0), 860);
+    }
+
+    #[test]
+    fn test_large_steps_saturate_at_max() {
+        let tones = TurnTones {
+            base_hz: u32::MAX - 10,
+            hz_per_deg: 1e9,
+        };
+        assert_eq!(tones.frequency_hz(90.0), MAX_TURN_HZ);
+        let tones = TurnTones {
+            base_hz: 1_000,
+            hz_per_deg: 500.0,
+        };
+        assert_eq!(tones.frequency_hz(180.0), MAX_TURN_HZ);
+        assert_eq!(tones.frequency_hz(10.0), 6_000);
+    }
+}
@@ -1,6 +1,12 @@
 #![cfg_attr(not(test), no_std)]
+#![cfg_attr(target_arch = "avr", feature(asm_experimental_arch))]
 
+pub mod app;
 pub mod drivers;
+pub mod guidance;
+pub mod nav;
+pub mod notify;
+pub mod util;
 
 //#[cfg(feature = "firmware")]
 pub mod firmware;
@@ -4,6 +4,7 @@
 use avr_device::entry;
 use gps::drivers;
 use gps::firmware;
+#[cfg(not(feature = "panic-sos"))]
 use panic_halt as _;
 
 #[entry]
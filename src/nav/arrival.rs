@@ -0,0 +1,171 @@
+//! Arrival detection with hysteresis around a waypoint.
+
+use crate::drivers::tick::TickSource;
+
+/// What it takes for a latched [`Arrival`] to re-arm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hysteresis {
+    /// Re-arm once the distance exceeds the radius by this many metres: the
+    /// user "walked away".
+    Distance(f32),
+    /// Re-arm once the distance has stayed beyond the radius for this many
+    /// milliseconds: the user "waited a bit". Suits stationary waypoints,
+    /// where GPS jitter alone can carry a fix past any sensible margin.
+    Time(u32),
+}
+
+/// Latching "arrived" detector for one waypoint.
+///
+/// Arrival fires once when the distance first drops to `radius_m` or below,
+/// and stays latched until the [`Hysteresis`] is satisfied: by default the
+/// distance exceeding `radius_m + margin_m`. The hysteresis stops GPS jitter
+/// at the boundary from re-triggering the cue, while the auto-reset lets
+/// looped or patrol routes announce a later re-approach.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arrival {
+    radius_m: f32,
+    hysteresis: Hysteresis,
+    arrived: bool,
+    /// When the latched distance last went beyond the radius.
+    left_at_ms: Option<u32>,
+}
+
+impl Arrival {
+    /// Create a detector.
+    ///
+    /// * `radius_m` – distance at which the waypoint counts as reached
+    /// * `margin_m` – extra distance that must be exceeded to re-arm
+    pub const fn new(radius_m: f32, margin_m: f32) -> Self {
+        Self::with_hysteresis(radius_m, Hysteresis::Distance(margin_m))
+    }
+
+    /// Create a detector that re-arms according to `hysteresis`.
+    ///
+    /// With [`Hysteresis::Time`], feed it through
+    /// [`Arrival::update_with_ticks`].
+    pub const fn with_hysteresis(radius_m: f32, hysteresis: Hysteresis) -> Self {
+        Self {
+            radius_m,
+            hysteresis,
+            arrived: false,
+            left_at_ms: None,
+        }
+    }
+
+    /// Feed the latest distance to the waypoint.
+    ///
+    /// Returns `true` only on the update where arrival is first detected.
+    /// Time does not advance here, so a [`Hysteresis::Time`] latch only
+    /// clears through [`Arrival::update_with_ticks`] or [`Arrival::reset`].
+    pub fn update(&mut self, distance_m: f32) -> bool {
+        self.update_at(distance_m, 0)
+    }
+
+    /// Feed the latest distance like [`Arrival::update`], timing a
+    /// [`Hysteresis::Time`] window on `ticks`.
+    pub fn update_with_ticks<T: TickSource>(&mut self, distance_m: f32, ticks: &T) -> bool {
+        self.update_at(distance_m, ticks.now_ms())
+    }
+
+    fn update_at(&mut self, distance_m: f32, now_ms: u32) -> bool {
+        if self.arrived {
+            let outside = distance_m > self.radius_m;
+            let rearm = match self.hysteresis {
+                Hysteresis::Distance(margin_m) => distance_m > self.radius_m + margin_m,
+                Hysteresis::Time(window_ms) => {
+                    self.left_at_ms = if outside {
+                        self.left_at_ms.or(Some(now_ms))
+                    } else {
+                        None
+                    };
+                    self.left_at_ms
+                        .is_some_and(|at| now_ms.wrapping_sub(at) >= window_ms)
+                }
+            };
+            if rearm {
+                self.reset();
+            }
+            false
+        } else if distance_m <= self.radius_m {
+            self.arrived = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether the detector is currently latched inside the radius.
+    pub fn is_arrived(&self) -> bool {
+        self.arrived
+    }
+
+    /// Clear the latch, e.g. when the target waypoint changes.
+    pub fn reset(&mut self) {
+        self.arrived = false;
+        self.left_at_ms = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::ManualTicks;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_approach_arrive_depart_reapproach() {
+        let mut arrival = Arrival::new(10.0, 5.0);
+        let fired: Vec<bool> = [50.0, 20.0, 9.0, 11.0, 8.0, 14.0, 16.0, 30.0, 12.0, 10.0]
+            .into_iter()
+            .map(|d| arrival.update(d))
+            .collect();
+        assert_eq!(
+            fired,
+            [
+                false, false, true, false, false, false, false, false, false, true
+            ]
+        );
+        assert!(arrival.is_arrived());
+    }
+
+    #[test]
+    fn test_jitter_inside_margin_stays_latched() {
+        let mut arrival = Arrival::new(10.0, 5.0);
+        assert!(arrival.update(10.0));
+        for d in [12.0, 15.0, 9.0, 14.9] {
+            assert!(!arrival.update(d));
+            assert!(arrival.is_arrived());
+        }
+    }
+
+    #[test]
+    fn test_time_window_rearms_after_waiting_outside() {
+        let ticks = ManualTicks(Cell::new(u32::MAX - 500));
+        let mut arrival = Arrival::with_hysteresis(10.0, Hysteresis::Time(2_000));
+        let mut at = |ms: u32, d: f32| {
+            ticks.0.set((u32::MAX - 500).wrapping_add(ms));
+            arrival.update_with_ticks(d, &ticks)
+        };
+        assert!(at(0, 9.0));
+        // Far beyond any distance margin, but only briefly.
+        assert!(!at(1_000, 40.0));
+        assert!(!at(2_500, 9.0));
+        // The window restarts on every exit.
+        assert!(!at(3_000, 12.0));
+        assert!(!at(4_999, 12.0));
+        assert!(!at(5_000, 12.0));
+        assert!(at(5_100, 10.0));
+    }
+
+    #[test]
+    fn test_distance_mode_ignores_time() {
+        let ticks = ManualTicks(Cell::new(0));
+        let mut arrival = Arrival::new(10.0, 5.0);
+        assert!(arrival.update_with_ticks(9.0, &ticks));
+        ticks.0.set(60_000);
+        assert!(!arrival.update_with_ticks(14.0, &ticks));
+        assert!(arrival.is_arrived());
+        assert!(!arrival.update_with_ticks(16.0, &ticks));
+        assert!(!arrival.is_arrived());
+    }
+}
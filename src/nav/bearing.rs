@@ -0,0 +1,104 @@
+//! Compass bearings with automatic wraparound.
+
+use core::ops::{Add, Sub};
+
+/// A direction in degrees clockwise from north, always in `0..360`.
+///
+/// Arithmetic wraps across the 0/360 seam, so `Bearing::new(350.0) + 20.0`
+/// is 10° and `Bearing::new(10.0) - 20.0` is 350°. Use
+/// [`Bearing::relative_to`] for the signed turn between two bearings.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Bearing(f32);
+
+impl Bearing {
+    /// Due north, 0°.
+    pub const NORTH: Bearing = Bearing(0.0);
+
+    /// Create a bearing from any angle in degrees, wrapping it into `0..360`.
+    pub fn new(deg: f32) -> Self {
+        let mut wrapped = deg % 360.0;
+        if wrapped < 0.0 {
+            wrapped += 360.0;
+        }
+        // A tiny negative input rounds up to exactly 360 when shifted.
+        if wrapped >= 360.0 {
+            wrapped = 0.0;
+        }
+        Bearing(wrapped)
+    }
+
+    /// The bearing in degrees, `0..360`.
+    pub fn deg(self) -> f32 {
+        self.0
+    }
+
+    /// Signed shortest turn from `other` to `self` in degrees, `-180..180`.
+    ///
+    /// Positive means `self` is clockwise (to the right) of `other`.
+    pub fn relative_to(self, other: Bearing) -> f32 {
+        let diff = self.0 - other.0;
+        if diff >= 180.0 {
+            diff - 360.0
+        } else if diff < -180.0 {
+            diff + 360.0
+        } else {
+            diff
+        }
+    }
+}
+
+impl From<f32> for Bearing {
+    fn from(deg: f32) -> Self {
+        Bearing::new(deg)
+    }
+}
+
+/// Turn clockwise by a number of degrees.
+impl Add<f32> for Bearing {
+    type Output = Bearing;
+
+    fn add(self, deg: f32) -> Bearing {
+        Bearing::new(self.0 + deg)
+    }
+}
+
+/// Turn anticlockwise by a number of degrees.
+impl Sub<f32> for Bearing {
+    type Output = Bearing;
+
+    fn sub(self, deg: f32) -> Bearing {
+        Bearing::new(self.0 - deg)
+    }
+}
+
+/// Clockwise angle from `rhs` to `self`, e.g. target bearing minus heading.
+impl Sub for Bearing {
+    type Output = Bearing;
+
+    fn sub(self, rhs: Bearing) -> Bearing {
+        Bearing::new(self.0 - rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_sub_wrap_across_north() {
+        assert_eq!((Bearing::new(350.0) + 20.0).deg(), 10.0);
+        assert_eq!((Bearing::new(10.0) - 20.0).deg(), 350.0);
+        assert_eq!((Bearing::new(10.0) - Bearing::new(350.0)).deg(), 20.0);
+        assert_eq!(Bearing::new(-720.0), Bearing::NORTH);
+        assert_eq!(Bearing::new(725.0).deg(), 5.0);
+        assert_eq!(Bearing::new(-1e-6), Bearing::NORTH);
+    }
+
+    #[test]
+    fn test_relative_to_takes_shortest_way() {
+        assert_eq!(Bearing::new(10.0).relative_to(Bearing::new(350.0)), 20.0);
+        assert_eq!(Bearing::new(350.0).relative_to(Bearing::new(10.0)), -20.0);
+        assert_eq!(Bearing::new(90.0).relative_to(Bearing::new(270.0)), -180.0);
+        assert_eq!(Bearing::new(0.0).relative_to(Bearing::new(0.0)), 0.0);
+    }
+}
@@ -0,0 +1,126 @@
+//! Coarse magnetic declination lookup.
+//!
+//! Declination (magnetic north relative to true north, positive east) comes
+//! from a whole-degree grid sampled from the World Magnetic Model at epoch
+//! 2020.0 and is bilinearly interpolated between grid points. Expect about
+//! 0.5° typical and 1.5° 95th-percentile error, growing by roughly 0.1° per
+//! year of secular drift.
+
+use super::Coord;
+use crate::util::{math, progmem::ProgMemBytes};
+
+/// Southernmost grid row in degrees; fixes further south are clamped to it.
+const LAT_MIN: f32 = -60.0;
+/// Latitude spacing of the grid rows.
+const LAT_STEP: f32 = 10.0;
+/// Longitude spacing of the grid columns; columns start at 180° W and wrap.
+const LON_STEP: f32 = 20.0;
+
+const ROWS: usize = 14;
+const COLS: usize = 18;
+
+/// Declination in whole degrees, rows from 60° S to 70° N, columns from
+/// 180° W eastwards.
+///
+/// Only used to build [`GRID`] at compile time, so it never reaches SRAM.
+#[rustfmt::skip]
+const TABLE: [[i8; COLS]; ROWS] = [
+    /* -60 */ [49, 45, 43, 39, 33, 23, 10, -1, -10, -20, -34, -50, -63, -73, -77, -65, 29, 49],
+    /* -50 */ [32, 31, 31, 30, 27, 18, 3, -9, -15, -21, -34, -48, -57, -57, -45, -15, 14, 28],
+    /* -40 */ [23, 23, 23, 23, 22, 13, -4, -17, -21, -23, -31, -42, -46, -39, -22, -3, 10, 19],
+    /* -30 */ [17, 18, 17, 17, 16, 7, -10, -23, -25, -22, -22, -30, -32, -23, -9, 1, 8, 14],
+    /* -20 */ [13, 14, 14, 13, 11, 3, -14, -24, -24, -16, -9, -15, -18, -12, -3, 2, 6, 11],
+    /* -10 */ [11, 11, 11, 10, 8, -1, -16, -23, -18, -9, -2, -4, -9, -6, -1, 1, 4, 9],
+    /*  +0 */ [10, 9, 9, 9, 6, -3, -16, -20, -13, -5, 1, 0, -4, -3, 0, 0, 2, 8],
+    /* +10 */ [9, 9, 9, 9, 5, -4, -16, -17, -9, -2, 2, 2, -1, -2, -1, -1, 0, 6],
+    /* +20 */ [8, 10, 10, 10, 5, -6, -15, -14, -7, 0, 3, 3, 1, 0, -1, -3, -2, 3],
+    /* +30 */ [6, 10, 12, 12, 5, -8, -15, -13, -6, 0, 4, 4, 3, 1, -1, -6, -6, 0],
+    /* +40 */ [5, 11, 14, 14, 5, -9, -16, -13, -6, 1, 5, 6, 6, 4, -2, -8, -9, -3],
+    /* +50 */ [3, 11, 16, 16, 5, -12, -19, -15, -8, 0, 6, 10, 11, 7, -2, -11, -12, -6],
+    /* +60 */ [2, 12, 18, 19, 5, -18, -25, -20, -11, -1, 8, 15, 18, 13, 0, -14, -15, -8],
+    /* +70 */ [1, 12, 20, 20, -2, -30, -34, -26, -14, -1, 11, 21, 27, 25, 5, -15, -17, -9],
+];
+
+/// [`TABLE`] row by row, as two's-complement bytes.
+///
+/// 252 bytes of flash. On AVR it stays in program memory rather than being
+/// copied into the ATmega16's 1 KiB of SRAM, at the cost of an `lpm` per
+/// sample (four per lookup).
+#[cfg_attr(target_arch = "avr", unsafe(link_section = ".progmem.data"))]
+static GRID: ProgMemBytes<{ ROWS * COLS }> = ProgMemBytes::new(flatten(TABLE));
+
+const fn flatten(table: [[i8; COLS]; ROWS]) -> [u8; ROWS * COLS] {
+    let mut bytes = [0; ROWS * COLS];
+    let mut i = 0;
+    while i < ROWS * COLS {
+        bytes[i] = table[i / COLS][i % COLS] as u8;
+        i += 1;
+    }
+    bytes
+}
+
+/// Magnetic declination at `coord` in degrees, positive when magnetic north
+/// is east of true north.
+///
+/// Add it to a magnetic compass heading to get a true heading. Latitudes
+/// outside 60° S..70° N use the nearest grid row, where the field changes
+/// too quickly near the poles for a table this coarse anyway.
+pub fn declination_deg(coord: Coord) -> f32 {
+    let y = (coord
+        .lat
+        .clamp(LAT_MIN, LAT_MIN + LAT_STEP * (ROWS - 1) as f32)
+        - LAT_MIN)
+        / LAT_STEP;
+    let row = (math::floor(y) as usize).min(ROWS - 2);
+    let ty = y - row as f32;
+
+    let mut lon = (coord.lon + 180.0) % 360.0;
+    if lon < 0.0 {
+        lon += 360.0;
+    }
+    let x = lon / LON_STEP;
+    let col = (math::floor(x) as usize).min(COLS - 1);
+    let tx = x - col as f32;
+    let next_col = (col + 1) % COLS;
+
+    let at = |r: usize, c: usize| f32::from(GRID.get(r * COLS + c).unwrap_or(0) as i8);
+    let south = at(row, col) + (at(row, next_col) - at(row, col)) * tx;
+    let north = at(row + 1, col) + (at(row + 1, next_col) - at(row + 1, col)) * tx;
+    south + (north - south) * ty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declination_near_reference_values() {
+        // (lat, lon, WMM2020 declination at 2020.0)
+        let cases = [
+            (51.5, -0.13, -0.2),    // London
+            (37.77, -122.42, 13.8), // San Francisco
+            (40.71, -74.0, -12.8),  // New York
+            (-33.87, 151.2, 12.8),  // Sydney
+            (35.68, 139.7, -7.7),   // Tokyo
+        ];
+        for (lat, lon, expected) in cases {
+            let got = declination_deg(Coord::new(lat, lon));
+            assert!(
+                (got - expected).abs() < 2.0,
+                "{lat},{lon}: {got} vs {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_declination_wraps_longitude_and_clamps_latitude() {
+        let east = declination_deg(Coord::new(10.0, 179.9));
+        let west = declination_deg(Coord::new(10.0, -180.1));
+        assert!((east - west).abs() < 0.1);
+        assert_eq!(
+            declination_deg(Coord::new(89.0, 0.0)),
+            declination_deg(Coord::new(70.0, 0.0))
+        );
+        assert_eq!(declination_deg(Coord::new(-20.0, -180.0)), 13.0);
+    }
+}
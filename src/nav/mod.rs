@@ -0,0 +1,664 @@
+//! Geographic coordinates and navigation math.
+//!
+//! The integer paths ([`FixedCoord`], [`flat_distance_m`],
+//! [`flat_bearing_deg`]) are always available. The spherical `f32` versions
+//! need `libm` and are behind the `float-nav` feature (on by default).
+
+use crate::{firmware::shared::cordic, util::math};
+
+pub mod arrival;
+pub mod bearing;
+pub mod declination;
+pub mod route;
+#[cfg(all(feature = "float-nav", any(test, feature = "test-util")))]
+pub mod track;
+
+pub use bearing::Bearing;
+pub use declination::declination_deg;
+
+/// Mean Earth radius in metres, as used by the haversine formula.
+pub const EARTH_RADIUS_M: f32 = 6_371_000.0;
+
+/// Fixed-point coordinates are degrees multiplied by this factor.
+pub const FIXED_SCALE: i32 = 10_000_000;
+
+/// A WGS-84 position in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coord {
+    /// Latitude in degrees, positive north.
+    pub lat: f32,
+    /// Longitude in degrees, positive east.
+    pub lon: f32,
+}
+
+impl Coord {
+    /// Create a [`Coord`] from latitude and longitude in degrees.
+    pub const fn new(lat: f32, lon: f32) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Decode integer degrees × `scale`, e.g. `1_000_000` or
+    /// [`FIXED_SCALE`] depending on the tool that wrote them.
+    pub fn from_fixed(raw_lat: i32, raw_lon: i32, scale: i32) -> Self {
+        Self::new(raw_lat as f32 / scale as f32, raw_lon as f32 / scale as f32)
+    }
+
+    /// Decode 8 bytes holding latitude then longitude as little-endian
+    /// `i32`s of degrees × `scale`.
+    pub fn from_fixed_le(raw: [u8; 8], scale: i32) -> Self {
+        let (lat, lon) = split_words(raw);
+        Self::from_fixed(i32::from_le_bytes(lat), i32::from_le_bytes(lon), scale)
+    }
+
+    /// As [`Coord::from_fixed_le`] for big-endian blobs.
+    pub fn from_fixed_be(raw: [u8; 8], scale: i32) -> Self {
+        let (lat, lon) = split_words(raw);
+        Self::from_fixed(i32::from_be_bytes(lat), i32::from_be_bytes(lon), scale)
+    }
+
+    /// Latitude and longitude as integer degrees × `scale`, rounded to the
+    /// nearest step; the inverse of [`Coord::from_fixed`].
+    ///
+    /// Uses `f32` only, so the AVR build pulls in no soft-double routines.
+    pub fn to_fixed(&self, scale: i32) -> (i32, i32) {
+        let scale = scale as f32;
+        (
+            math::round_i32(self.lat * scale),
+            math::round_i32(self.lon * scale),
+        )
+    }
+
+    /// Encode as 8 bytes holding latitude then longitude as little-endian
+    /// `i32`s of degrees × `scale`; the inverse of [`Coord::from_fixed_le`].
+    pub fn to_fixed_le(&self, scale: i32) -> [u8; 8] {
+        let (lat, lon) = self.to_fixed(scale);
+        join_words(lat.to_le_bytes(), lon.to_le_bytes())
+    }
+
+    /// `true` if latitude and longitude each differ from `other` by at most
+    /// `eps_deg`, with longitude wrapping at the antimeridian.
+    ///
+    /// Meant for tests, where `f32` round-off makes exact comparison fragile.
+    pub fn approx_eq(&self, other: &Coord, eps_deg: f32) -> bool {
+        let d_lon = (self.lon - other.lon).abs();
+        let d_lon = if d_lon > 180.0 { 360.0 - d_lon } else { d_lon };
+        (self.lat - other.lat).abs() <= eps_deg && d_lon <= eps_deg
+    }
+}
+
+fn split_words(raw: [u8; 8]) -> ([u8; 4], [u8; 4]) {
+    let [a, b, c, d, e, f, g, h] = raw;
+    ([a, b, c, d], [e, f, g, h])
+}
+
+/// Inverse of [`split_words`].
+pub(crate) fn join_words(first: [u8; 4], second: [u8; 4]) -> [u8; 8] {
+    let mut raw = [0u8; 8];
+    raw[..4].copy_from_slice(&first);
+    raw[4..].copy_from_slice(&second);
+    raw
+}
+
+impl From<FixedCoord> for Coord {
+    fn from(c: FixedCoord) -> Self {
+        Self::new(
+            c.lat as f32 / FIXED_SCALE as f32,
+            c.lon as f32 / FIXED_SCALE as f32,
+        )
+    }
+}
+
+/// A WGS-84 position in integer units of 10⁻⁷ degrees (~1 cm).
+///
+/// This is the native resolution of most GPS receivers and avoids soft-float
+/// on the AVR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedCoord {
+    /// Latitude in 10⁻⁷ degrees, positive north.
+    pub lat: i32,
+    /// Longitude in 10⁻⁷ degrees, positive east.
+    pub lon: i32,
+}
+
+impl FixedCoord {
+    /// Create a [`FixedCoord`] from latitude and longitude in 10⁻⁷ degrees.
+    pub const fn new(lat: i32, lon: i32) -> Self {
+        Self { lat, lon }
+    }
+}
+
+/// Rounds to the nearest 10⁻⁷ degree with [`Coord::to_fixed`], in `f32`
+/// only.
+impl From<Coord> for FixedCoord {
+    fn from(c: Coord) -> Self {
+        let (lat, lon) = c.to_fixed(FIXED_SCALE);
+        Self::new(lat, lon)
+    }
+}
+
+#[cfg(feature = "float-nav")]
+/// Great-circle distance between `from` and `to` in metres (haversine).
+pub fn distance_m(from: Coord, to: Coord) -> f32 {
+    let phi1 = from.lat.to_radians();
+    let phi2 = to.lat.to_radians();
+    let d_phi = phi2 - phi1;
+    let d_lambda = (to.lon - from.lon).to_radians();
+
+    let s_phi = libm::sinf(d_phi / 2.0);
+    let s_lambda = libm::sinf(d_lambda / 2.0);
+    let a = s_phi * s_phi + libm::cosf(phi1) * libm::cosf(phi2) * s_lambda * s_lambda;
+    2.0 * EARTH_RADIUS_M * libm::asinf(libm::sqrtf(a.min(1.0)))
+}
+
+#[cfg(feature = "float-nav")]
+/// Initial great-circle bearing from `from` to `to`, in degrees `0..360`
+/// clockwise from true north.
+pub fn initial_bearing_deg(from: Coord, to: Coord) -> f32 {
+    let phi1 = from.lat.to_radians();
+    let phi2 = to.lat.to_radians();
+    let d_lambda = (to.lon - from.lon).to_radians();
+
+    let y = libm::sinf(d_lambda) * libm::cosf(phi2);
+    let x = libm::cosf(phi1) * libm::sinf(phi2)
+        - libm::sinf(phi1) * libm::cosf(phi2) * libm::cosf(d_lambda);
+    let deg = libm::atan2f(y, x).to_degrees();
+    if deg < 0.0 { deg + 360.0 } else { deg }
+}
+
+#[cfg(feature = "float-nav")]
+/// Distance in metres and initial bearing in degrees `0..360` from `from` to
+/// `to`, in one pass.
+///
+/// Equivalent to [`distance_m`] plus [`initial_bearing_deg`], but the sines
+/// and cosines of both latitudes and of the longitude difference are computed
+/// once and shared: nine trig calls instead of thirteen per fix, which matters
+/// with soft-float on the AVR.
+pub fn range_bearing(from: Coord, to: Coord) -> (f32, f32) {
+    let phi1 = from.lat.to_radians();
+    let phi2 = to.lat.to_radians();
+    let d_lambda = (to.lon - from.lon).to_radians();
+    let (sin_phi1, cos_phi1) = libm::sincosf(phi1);
+    let (sin_phi2, cos_phi2) = libm::sincosf(phi2);
+    let (sin_dl, cos_dl) = libm::sincosf(d_lambda);
+
+    let s_phi = libm::sinf((phi2 - phi1) / 2.0);
+    // sin²(Δλ/2) from the shared sin/cos of Δλ, avoiding the cancellation in
+    // (1 − cos Δλ) / 2 for short distances.
+    let s_lambda_sq = if cos_dl > 0.0 {
+        sin_dl * sin_dl / (2.0 * (1.0 + cos_dl))
+    } else {
+        (1.0 - cos_dl) / 2.0
+    };
+    let a = s_phi * s_phi + cos_phi1 * cos_phi2 * s_lambda_sq;
+    let distance = 2.0 * EARTH_RADIUS_M * libm::asinf(libm::sqrtf(a.clamp(0.0, 1.0)));
+
+    let y = sin_dl * cos_phi2;
+    let x = cos_phi1 * sin_phi2 - sin_phi1 * cos_phi2 * cos_dl;
+    let deg = libm::atan2f(y, x).to_degrees();
+    (distance, if deg < 0.0 { deg + 360.0 } else { deg })
+}
+
+#[cfg(feature = "float-nav")]
+/// Signed distance in metres of `p` from the great circle through `start` and
+/// `end`: positive to the right of the track, negative to the left.
+///
+/// Distance is to the full great circle, not just the segment, so points far
+/// beyond either end still report only their sideways offset.
+pub fn cross_track_distance_m(start: Coord, end: Coord, p: Coord) -> f32 {
+    let (d13, theta13) = range_bearing(start, p);
+    let theta12 = initial_bearing_deg(start, end);
+    let delta13 = d13 / EARTH_RADIUS_M;
+    let sin_xt = libm::sinf(delta13) * libm::sinf((theta13 - theta12).to_radians());
+    libm::asinf(sin_xt.clamp(-1.0, 1.0)) * EARTH_RADIUS_M
+}
+
+#[cfg(feature = "float-nav")]
+/// Point reached by travelling `distance_m` metres from `start` along the
+/// great circle with initial bearing `bearing_deg` (the inverse of
+/// [`range_bearing`]).
+///
+/// The result's longitude is wrapped to the half-open `-180..180`, so 180° E
+/// comes back as -180°, routes may cross the
+/// antimeridian, and a path over a pole comes down the other side with the
+/// longitude flipped by 180°.
+pub fn destination(start: Coord, bearing_deg: f32, distance_m: f32) -> Coord {
+    let phi1 = start.lat.to_radians();
+    let theta = bearing_deg.to_radians();
+    let delta = distance_m / EARTH_RADIUS_M;
+    let (sin_phi1, cos_phi1) = libm::sincosf(phi1);
+    let (sin_delta, cos_delta) = libm::sincosf(delta);
+    let (sin_theta, cos_theta) = libm::sincosf(theta);
+
+    let sin_phi2 = (sin_phi1 * cos_delta + cos_phi1 * sin_delta * cos_theta).clamp(-1.0, 1.0);
+    let phi2 = libm::asinf(sin_phi2);
+    let d_lambda = libm::atan2f(
+        sin_theta * sin_delta * cos_phi1,
+        cos_delta - sin_phi1 * sin_phi2,
+    );
+
+    Coord::new(
+        phi2.to_degrees(),
+        wrap_lon_deg(start.lon + d_lambda.to_degrees()),
+    )
+}
+
+#[cfg(feature = "float-nav")]
+/// `lon` wrapped to the half-open `-180..180`.
+fn wrap_lon_deg(lon: f32) -> f32 {
+    let mut shifted = (lon + 180.0) % 360.0;
+    if shifted < 0.0 {
+        shifted += 360.0;
+    }
+    // A tiny negative remainder rounds up to exactly 360 when shifted.
+    if shifted >= 360.0 {
+        shifted -= 360.0;
+    }
+    shifted - 180.0
+}
+
+#[cfg(feature = "float-nav")]
+/// Point `fraction` of the way from `from` to `to` along the great circle,
+/// e.g. `0.5` for the midpoint. Fractions outside `0..=1` extrapolate.
+pub fn interpolate(from: Coord, to: Coord, fraction: f32) -> Coord {
+    let (distance, bearing) = range_bearing(from, to);
+    destination(from, bearing, distance * fraction)
+}
+
+/// Cheap test of whether `c` lies within a box around `center` that contains
+/// every point up to `half_extent_deg` degrees of latitude (1° ≈ 111 km) away.
+///
+/// Meant as a prefilter before [`distance_m`], e.g. to skip far waypoints in
+/// a nearest-waypoint search. Longitude differences wrap at the antimeridian.
+///
+/// # Latitude distortion
+///
+/// A degree of longitude shrinks with `cos(latitude)` (about 111 km at the
+/// equator, 56 km at 60°), so the box's longitude half-width is widened by
+/// `1 / cos` of the most poleward latitude it reaches. The box therefore
+/// never excludes a point inside the circle, but it lets through more false
+/// positives at high latitude, and any longitude once it reaches a pole.
+/// The cosine is the integer approximation used by [`flat_distance_m`], so
+/// no `libm` is needed, but the comparisons themselves are done in `f32`.
+pub fn within_box(center: Coord, half_extent_deg: f32, c: Coord) -> bool {
+    // Below this cosine (about 89.4°) every longitude is treated as close.
+    const MIN_COS: f32 = 0.01;
+    // Bhaskara's cosine can overshoot by this much; err on the wide side.
+    const COS_ERROR: f32 = 0.002;
+
+    let d_lat = (c.lat - center.lat).abs();
+    let d_lon = (c.lon - center.lon).abs();
+    let d_lon = if d_lon > 180.0 { 360.0 - d_lon } else { d_lon };
+    if d_lat > half_extent_deg {
+        return false;
+    }
+    let poleward = (center.lat.abs() + half_extent_deg).min(90.0);
+    let cos_lat = cos_q15((poleward * FIXED_SCALE as f32) as i64) as f32 / 32_768.0 - COS_ERROR;
+    cos_lat < MIN_COS || d_lon <= half_extent_deg / cos_lat
+}
+
+/// Short-range bearing from `from` to `to` in whole degrees `0..360`, using
+/// only integer math.
+///
+/// Uses the equirectangular approximation: the longitude difference is scaled
+/// by the cosine of the mean latitude and the bearing is taken with a single
+/// `atan2` on the resulting flat east/north offsets.
+///
+/// # Accuracy
+///
+/// The flat-earth error grows with distance and latitude. Below ±60° latitude
+/// the result stays within about ±0.5° of [`initial_bearing_deg`] up to ~10 km
+/// (mostly whole-degree rounding) and within ±1° at ~100 km. Beyond that, or
+/// closer to the poles, use the spherical version.
+pub fn flat_bearing_deg(from: FixedCoord, to: FixedCoord) -> u16 {
+    let (east, north) = flat_offsets(from, to);
+    // Both offsets are bounded by ±180° in 10⁻⁷ degrees, which fits in i32.
+    let centideg = cordic::atan2_centideg(east as i32, north as i32).rem_euclid(36_000);
+    (((centideg + 50) / 100) % 360) as u16
+}
+
+/// Short-range distance from `from` to `to` in whole metres, using only
+/// integer math.
+///
+/// Same equirectangular approximation as [`flat_bearing_deg`], with the
+/// offset length taken by CORDIC. Within 0.5% of the great-circle distance up
+/// to ~10 km below 60° latitude.
+pub fn flat_distance_m(from: FixedCoord, to: FixedCoord) -> u32 {
+    /// Metres per degree of arc on the mean Earth sphere, times 100.
+    const CM_PER_DEG: u64 = 11_119_493;
+
+    let (east, north) = flat_offsets(from, to);
+    let length = u64::from(cordic::hypot(east as i32, north as i32));
+    (length * CM_PER_DEG / (100 * FIXED_SCALE as u64)) as u32
+}
+
+/// East and north offsets from `from` to `to` in 10⁻⁷ degrees of arc, with
+/// the longitude difference wrapped at the antimeridian and scaled by the
+/// cosine of the mean latitude.
+fn flat_offsets(from: FixedCoord, to: FixedCoord) -> (i64, i64) {
+    const FULL_TURN: i64 = 360 * FIXED_SCALE as i64;
+
+    let d_lat = i64::from(to.lat) - i64::from(from.lat);
+    let mut d_lon = i64::from(to.lon) - i64::from(from.lon);
+    if d_lon > FULL_TURN / 2 {
+        d_lon -= FULL_TURN;
+    } else if d_lon < -FULL_TURN / 2 {
+        d_lon += FULL_TURN;
+    }
+
+    let mean_lat = (i64::from(from.lat) + i64::from(to.lat)) / 2;
+    ((d_lon * cos_q15(mean_lat)) >> 15, d_lat)
+}
+
+/// Distance in metres for guidance and ranking: haversine with `float-nav`,
+/// otherwise [`flat_distance_m`].
+pub(crate) fn leg_distance_m(from: Coord, to: Coord) -> f32 {
+    #[cfg(feature = "float-nav")]
+    {
+        distance_m(from, to)
+    }
+    #[cfg(not(feature = "float-nav"))]
+    {
+        flat_distance_m(from.into(), to.into()) as f32
+    }
+}
+
+/// Cross-track distance for guidance: [`cross_track_distance_m`] with
+/// `float-nav`, otherwise a flat projection around `start`.
+pub(crate) fn leg_cross_track_m(start: Coord, end: Coord, p: Coord) -> f32 {
+    #[cfg(feature = "float-nav")]
+    {
+        cross_track_distance_m(start, end, p)
+    }
+    #[cfg(not(feature = "float-nav"))]
+    {
+        let origin = FixedCoord::from(start);
+        let (tx, ty) = flat_offsets(origin, end.into());
+        let (px, py) = flat_offsets(origin, p.into());
+        let track_len = cordic::hypot(tx as i32, ty as i32);
+        if track_len == 0 {
+            return flat_distance_m(origin, p.into()) as f32;
+        }
+        // (track × point) / |track|, east = x and north = y; right is positive.
+        let cross = (ty as f32) * (px as f32) - (tx as f32) * (py as f32);
+        let offset_e7 = cross / track_len as f32;
+        offset_e7 * (111_194.93 / FIXED_SCALE as f32)
+    }
+}
+
+/// Distance in metres and bearing in degrees `0..360` for guidance:
+/// [`range_bearing`] with `float-nav`, otherwise the flat integer versions.
+pub(crate) fn leg_range_bearing(from: Coord, to: Coord) -> (f32, f32) {
+    #[cfg(feature = "float-nav")]
+    {
+        range_bearing(from, to)
+    }
+    #[cfg(not(feature = "float-nav"))]
+    {
+        let (from, to) = (from.into(), to.into());
+        (
+            flat_distance_m(from, to) as f32,
+            f32::from(flat_bearing_deg(from, to)),
+        )
+    }
+}
+
+/// Cosine of `lat` (10⁻⁷ degrees, `-90°..=90°`) in Q15, via Bhaskara I's
+/// approximation (max error ~0.0016).
+fn cos_q15(lat: i64) -> i64 {
+    // Work in centidegrees so the squares stay well inside i64.
+    let x = lat / 100_000;
+    let x2 = x * x;
+    const HALF_TURN_SQ: i64 = 18_000 * 18_000;
+    ((HALF_TURN_SQ - 4 * x2) << 15) / (HALF_TURN_SQ + x2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_fixed_decodes_micro_and_tenth_micro_degrees() {
+        let expected = Coord::new(51.477_93, -0.001_47);
+        let micro = Coord::from_fixed(51_477_930, -1_470, 1_000_000);
+        let tenth_micro = Coord::from_fixed_le(
+            [0xA4, 0xE8, 0xAE, 0x1E, 0x94, 0xC6, 0xFF, 0xFF],
+            FIXED_SCALE,
+        );
+        let big = Coord::from_fixed_be([0x03, 0x11, 0x7D, 0xAA, 0xFF, 0xFF, 0xFA, 0x42], 1_000_000);
+        for c in [micro, tenth_micro, big] {
+            assert!(c.approx_eq(&expected, 1e-5), "{c:?}");
+        }
+    }
+
+    #[test]
+    fn test_to_fixed_rounds_to_the_nearest_step() {
+        let c = Coord::new(0.000_000_29, -0.000_000_29);
+        assert_eq!(c.to_fixed(FIXED_SCALE), (3, -3));
+        assert_eq!(
+            c.to_fixed_le(FIXED_SCALE),
+            [3, 0, 0, 0, 0xFD, 0xFF, 0xFF, 0xFF]
+        );
+    }
+
+    #[test]
+    fn test_fixed_coord_rounds_instead_of_truncating() {
+        // 0.29 and 0.31 steps of 10⁻⁷°, either side of a rounding boundary.
+        let c = Coord::new(0.000_000_029, -0.000_000_031);
+        assert_eq!(FixedCoord::from(c), FixedCoord::new(0, 0));
+        let c = Coord::new(0.000_000_051, -0.000_000_069);
+        assert_eq!(FixedCoord::from(c), FixedCoord::new(1, -1));
+        let c = Coord::new(0.000_000_29, -0.000_000_29);
+        assert_eq!(FixedCoord::from(c), FixedCoord::new(3, -3));
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let c = Coord::new(51.5, -0.12);
+        assert!(c.approx_eq(&c, 0.0));
+        assert!(c.approx_eq(&Coord::new(51.500_005, -0.120_005), 1e-5));
+        assert!(!c.approx_eq(&Coord::new(51.5, -0.13), 1e-5));
+        assert!(!c.approx_eq(&Coord::new(-51.5, -0.12), 1e-5));
+        // Either side of the antimeridian.
+        assert!(Coord::new(0.0, 179.999_99).approx_eq(&Coord::new(0.0, -180.0), 1e-4));
+    }
+
+    #[cfg(feature = "float-nav")]
+    fn angle_diff(a: f32, b: f32) -> f32 {
+        let d = (a - b).abs() % 360.0;
+        d.min(360.0 - d)
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_destination_round_trips_through_range_bearing() {
+        let cases = [
+            (Coord::new(51.5, -0.12), 45.0, 2_500.0),
+            (Coord::new(-33.9, 151.2), 200.0, 40_000.0),
+            // Across the antimeridian.
+            (Coord::new(10.0, 179.99), 90.0, 5_000.0),
+        ];
+        for (start, bearing, d) in cases {
+            let end = destination(start, bearing, d);
+            assert!((-180.0..180.0).contains(&end.lon), "{end:?}");
+            let (back_d, back_bearing) = range_bearing(start, end);
+            assert!((back_d - d).abs() < d * 1e-3, "{back_d} vs {d}");
+            assert!(angle_diff(back_bearing, bearing) < 0.1, "{back_bearing}");
+        }
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_interpolate_midpoint_and_ends() {
+        let (a, b) = (Coord::new(10.0, 20.0), Coord::new(10.0, 21.0));
+        assert!(interpolate(a, b, 0.0).approx_eq(&a, 1e-5));
+        assert!(interpolate(a, b, 1.0).approx_eq(&b, 1e-4));
+        let mid = interpolate(a, b, 0.5);
+        let (to_a, to_b) = (distance_m(mid, a), distance_m(mid, b));
+        assert!((to_a - to_b).abs() < 1.0, "{to_a} vs {to_b}");
+        // The great circle bulges towards the pole.
+        assert!(mid.lat > 10.0, "{mid:?}");
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_longitude_wraps_to_half_open_range() {
+        assert_eq!(wrap_lon_deg(180.0), -180.0);
+        assert_eq!(wrap_lon_deg(-180.0), -180.0);
+        assert_eq!(wrap_lon_deg(190.0), -170.0);
+        // One ulp west of -180: the remainder rounds up to 360 when shifted.
+        let just_west = f32::from_bits((-180.0f32).to_bits() + 1);
+        assert_eq!(wrap_lon_deg(just_west), -180.0);
+        assert_eq!(destination(Coord::new(0.0, 180.0), 0.0, 0.0).lon, -180.0);
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_destination_over_the_pole() {
+        // 20 km due north from 89.9° N passes the pole (~11 km away) and
+        // comes ~9 km down the far side.
+        let end = destination(Coord::new(89.9, 30.0), 0.0, 20_000.0);
+        assert!((end.lat - 89.92).abs() < 0.005, "{end:?}");
+        assert!(angle_diff(end.lon, -150.0) < 0.5, "{end:?}");
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_distance_one_degree_of_latitude() {
+        let d = distance_m(Coord::new(10.0, 20.0), Coord::new(11.0, 20.0));
+        assert!((d - 111_195.0).abs() < 10.0, "{d}");
+        assert_eq!(distance_m(Coord::new(1.0, 2.0), Coord::new(1.0, 2.0)), 0.0);
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_flat_bearing_matches_great_circle_nearby() {
+        let origin = Coord::new(51.5007, -0.1246);
+        let targets = [
+            Coord::new(51.5107, -0.1246),
+            Coord::new(51.5057, -0.1103),
+            Coord::new(51.5007, -0.1053),
+            Coord::new(51.4907, -0.1153),
+            Coord::new(51.4952, -0.1353),
+            Coord::new(51.5012, -0.1453),
+            Coord::new(51.5202, -0.1303),
+        ];
+        for target in targets {
+            let flat = flat_bearing_deg(origin.into(), target.into());
+            let sphere = initial_bearing_deg(origin, target);
+            assert!(
+                angle_diff(f32::from(flat), sphere) <= 1.0,
+                "{target:?}: flat {flat} vs sphere {sphere}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_flat_bearing_cardinal_directions_and_antimeridian() {
+        let o = FixedCoord::new(0, 0);
+        assert_eq!(flat_bearing_deg(o, FixedCoord::new(1_000, 0)), 0);
+        assert_eq!(flat_bearing_deg(o, FixedCoord::new(0, 1_000)), 90);
+        assert_eq!(flat_bearing_deg(o, FixedCoord::new(-1_000, 0)), 180);
+        assert_eq!(flat_bearing_deg(o, FixedCoord::new(0, -1_000)), 270);
+
+        let west_of_seam = FixedCoord::new(0, 1_799_990_000);
+        let east_of_seam = FixedCoord::new(0, -1_799_990_000);
+        assert_eq!(flat_bearing_deg(west_of_seam, east_of_seam), 90);
+    }
+
+    #[test]
+    fn test_within_box_classifies_inside_and_outside() {
+        let center = Coord::new(51.5, -0.12);
+        assert!(within_box(center, 0.1, center));
+        assert!(within_box(center, 0.1, Coord::new(51.59, -0.03)));
+        assert!(!within_box(center, 0.1, Coord::new(51.61, -0.12)));
+        // 0.1° of latitude is about 0.16° of longitude here.
+        assert!(within_box(center, 0.1, Coord::new(51.5, 0.0)));
+        assert!(!within_box(center, 0.1, Coord::new(51.5, 0.06)));
+
+        let dateline = Coord::new(0.0, 179.95);
+        assert!(within_box(dateline, 0.1, Coord::new(0.0, -179.97)));
+        assert!(!within_box(dateline, 0.1, Coord::new(0.0, -179.8)));
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_within_box_contains_the_circle_at_high_latitude() {
+        const M_PER_DEG: f32 = EARTH_RADIUS_M * core::f32::consts::PI / 180.0;
+        for lat in [0.0, 45.0, 60.0, 75.0, 85.0, -70.0] {
+            let center = Coord::new(lat, 10.0);
+            for bearing in [0.0, 45.0, 90.0, 135.0, 180.0, 270.0] {
+                let c = destination(center, bearing, 990.0);
+                assert!(
+                    within_box(center, 1_000.0 / M_PER_DEG, c),
+                    "{lat} {bearing}"
+                );
+            }
+        }
+        // A box reaching a pole takes every longitude.
+        assert!(within_box(
+            Coord::new(89.9, 0.0),
+            0.2,
+            Coord::new(89.9, 180.0)
+        ));
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_flat_distance_matches_great_circle_nearby() {
+        let origin = Coord::new(51.5007, -0.1246);
+        let targets = [
+            Coord::new(51.5107, -0.1246),
+            Coord::new(51.5057, -0.1103),
+            Coord::new(51.4907, -0.1153),
+            Coord::new(51.5012, -0.1453),
+            Coord::new(51.5902, -0.0303),
+        ];
+        for target in targets {
+            let flat = flat_distance_m(origin.into(), target.into()) as f32;
+            let sphere = distance_m(origin, target);
+            assert!(
+                (flat - sphere).abs() <= sphere * 0.005 + 1.0,
+                "{target:?}: flat {flat} vs sphere {sphere}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_flat_distance_one_degree_and_antimeridian() {
+        let d = flat_distance_m(FixedCoord::new(0, 0), FixedCoord::new(FIXED_SCALE, 0));
+        assert!(d.abs_diff(111_195) <= 2, "{d}");
+
+        let west_of_seam = FixedCoord::new(0, 1_799_990_000);
+        let east_of_seam = FixedCoord::new(0, -1_799_990_000);
+        assert!(flat_distance_m(west_of_seam, east_of_seam).abs_diff(222) <= 1);
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_range_bearing_matches_separate_functions() {
+        let pairs = [
+            (Coord::new(51.5007, -0.1246), Coord::new(51.5107, -0.1103)),
+            (Coord::new(10.0, 20.0), Coord::new(11.0, 20.0)),
+            (Coord::new(-33.9, 151.2), Coord::new(40.7, -74.0)),
+            (Coord::new(0.0, 179.9), Coord::new(0.1, -179.9)),
+        ];
+        for (from, to) in pairs {
+            let (range, bearing) = range_bearing(from, to);
+            let (d, b) = (distance_m(from, to), initial_bearing_deg(from, to));
+            assert!((range - d).abs() <= d * 1e-3 + 0.5, "{range} vs {d}");
+            assert!(angle_diff(bearing, b) <= 0.01, "{bearing} vs {b}");
+        }
+    }
+
+    #[cfg(feature = "float-nav")]
+    #[test]
+    fn test_cross_track_sign_and_magnitude() {
+        let start = Coord::new(0.0, 0.0);
+        let end = Coord::new(1.0, 0.0);
+        let right = cross_track_distance_m(start, end, Coord::new(0.5, 0.001));
+        let left = cross_track_distance_m(start, end, Coord::new(0.5, -0.001));
+        assert!((right - 111.2).abs() < 0.5, "{right}");
+        assert!((left + 111.2).abs() < 0.5, "{left}");
+        assert!(cross_track_distance_m(start, end, Coord::new(0.3, 0.0)).abs() < 0.1);
+    }
+}
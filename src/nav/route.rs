@@ -0,0 +1,365 @@
+//! Ordered list of waypoints with a cursor on the current leg.
+
+use super::{Coord, FIXED_SCALE, leg_distance_m};
+
+/// Arrival radius used for legs added without an explicit radius.
+pub const DEFAULT_ARRIVAL_RADIUS_M: f32 = 10.0;
+
+/// Bytes per leg in the [`Route::to_bytes`] format.
+pub const LEG_RECORD_LEN: usize = 12;
+
+/// Reason [`Route::from_bytes`] rejected a blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The blob is empty or shorter than its leg count says.
+    Truncated,
+    /// The blob holds more legs than the route's capacity `N`.
+    TooManyLegs,
+}
+
+/// A route of up to `N` waypoints, navigated in order.
+///
+/// The "current leg" is the path towards [`Route::target`]. Guidance should
+/// read the target on every update, so any change made by [`Route::advance`],
+/// [`Route::skip`] or [`Route::back`] takes effect on the next fix.
+///
+/// Each leg carries its own arrival radius, so a precise point (a gate) can
+/// use a few metres while an area (a field) uses tens of metres.
+///
+/// In loop mode (see [`Route::set_loop`]) the route never completes, e.g. for
+/// a patrol that goes round the same waypoints until stopped.
+#[derive(Debug, Clone)]
+pub struct Route<const N: usize> {
+    waypoints: [Coord; N],
+    radii_m: [f32; N],
+    len: usize,
+    current: usize,
+    looped: bool,
+}
+
+impl<const N: usize> Route<N> {
+    /// Create an empty route.
+    pub const fn new() -> Self {
+        Self {
+            waypoints: [Coord::new(0.0, 0.0); N],
+            radii_m: [DEFAULT_ARRIVAL_RADIUS_M; N],
+            len: 0,
+            current: 0,
+            looped: false,
+        }
+    }
+
+    /// Build a route from `waypoints`, or `None` if there are more than `N`.
+    pub fn from_slice(waypoints: &[Coord]) -> Option<Self> {
+        let mut route = Self::new();
+        for &waypoint in waypoints {
+            route.push(waypoint).ok()?;
+        }
+        Some(route)
+    }
+
+    /// Append a waypoint, returning it back as `Err` if the route is full.
+    ///
+    /// The leg uses [`DEFAULT_ARRIVAL_RADIUS_M`].
+    pub fn push(&mut self, waypoint: Coord) -> Result<(), Coord> {
+        self.push_with_radius(waypoint, DEFAULT_ARRIVAL_RADIUS_M)
+    }
+
+    /// Append a waypoint that counts as reached within `radius_m` metres.
+    pub fn push_with_radius(&mut self, waypoint: Coord, radius_m: f32) -> Result<(), Coord> {
+        if self.len == N {
+            return Err(waypoint);
+        }
+        self.waypoints[self.len] = waypoint;
+        self.radii_m[self.len] = radius_m;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Decode a route written by [`Route::to_bytes`], starting on the first
+    /// leg.
+    ///
+    /// Bytes after the last leg are ignored.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, ParseError> {
+        let (&count, records) = buf.split_first().ok_or(ParseError::Truncated)?;
+        let count = usize::from(count);
+        if count > N {
+            return Err(ParseError::TooManyLegs);
+        }
+        if records.len() < count * LEG_RECORD_LEN {
+            return Err(ParseError::Truncated);
+        }
+        let mut route = Self::new();
+        for record in records.chunks_exact(LEG_RECORD_LEN).take(count) {
+            let mut coord = [0u8; 8];
+            coord.copy_from_slice(&record[..8]);
+            let radius_m = f32::from_le_bytes([record[8], record[9], record[10], record[11]]);
+            // Cannot fail: `count <= N` was checked above.
+            let _ = route.push_with_radius(Coord::from_fixed_le(coord, FIXED_SCALE), radius_m);
+        }
+        Ok(route)
+    }
+
+    /// Encode the waypoints and their arrival radii into `out`, e.g. to send
+    /// a route over UART, and return the number of bytes written.
+    ///
+    /// The format is a one-byte leg count followed by one
+    /// [`LEG_RECORD_LEN`]-byte record per leg:
+    ///
+    /// | Byte | Field                                            |
+    /// |------|--------------------------------------------------|
+    /// | 0–7  | waypoint, see [`Coord::to_fixed_le`] at [`FIXED_SCALE`] |
+    /// | 8–11 | arrival radius in metres, `f32` little-endian    |
+    ///
+    /// The current leg is not stored. Returns 0 and leaves `out` untouched
+    /// if it is shorter than [`Route::encoded_len`] or the route has more
+    /// than 255 legs.
+    pub fn to_bytes(&self, out: &mut [u8]) -> usize {
+        let len = self.encoded_len();
+        let Ok(count) = u8::try_from(self.len) else {
+            return 0;
+        };
+        if out.len() < len {
+            return 0;
+        }
+        out[0] = count;
+        let legs = self.waypoints().iter().zip(&self.radii_m);
+        for (record, (waypoint, radius_m)) in out[1..len].chunks_exact_mut(LEG_RECORD_LEN).zip(legs)
+        {
+            record[..8].copy_from_slice(&waypoint.to_fixed_le(FIXED_SCALE));
+            record[8..].copy_from_slice(&radius_m.to_le_bytes());
+        }
+        len
+    }
+
+    /// Bytes [`Route::to_bytes`] needs for this route.
+    pub fn encoded_len(&self) -> usize {
+        1 + self.len * LEG_RECORD_LEN
+    }
+
+    /// Number of waypoints in the route.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the route has no waypoints.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// All waypoints, in order.
+    pub fn waypoints(&self) -> &[Coord] {
+        &self.waypoints[..self.len]
+    }
+
+    /// Index of the waypoint currently being navigated to.
+    ///
+    /// Equal to [`Route::len`] once the route is complete.
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Waypoint currently being navigated to, or `None` once complete.
+    pub fn target(&self) -> Option<Coord> {
+        self.waypoints().get(self.current).copied()
+    }
+
+    /// Arrival radius of waypoint `index`, or `None` if out of range.
+    pub fn radius_m(&self, index: usize) -> Option<f32> {
+        self.radii_m[..self.len].get(index).copied()
+    }
+
+    /// Arrival radius of the current leg, or `None` once complete.
+    pub fn target_radius_m(&self) -> Option<f32> {
+        self.radius_m(self.current)
+    }
+
+    /// Whether every waypoint has been reached (or skipped).
+    ///
+    /// Always `false` for a non-empty route in loop mode.
+    pub fn is_complete(&self) -> bool {
+        self.current >= self.len
+    }
+
+    /// Wrap from the last waypoint back to the first instead of completing.
+    ///
+    /// Turning loop mode on for a completed route restarts it from the first
+    /// waypoint.
+    pub fn set_loop(&mut self, looped: bool) {
+        self.looped = looped;
+        if looped && self.is_complete() {
+            self.current = 0;
+        }
+    }
+
+    /// Whether the route is in loop mode; see [`Route::set_loop`].
+    pub fn is_loop(&self) -> bool {
+        self.looped
+    }
+
+    /// Index of the remaining waypoint closest to `current`.
+    ///
+    /// Only waypoints from [`Route::current_index`] onwards are considered;
+    /// ties go to the earlier waypoint. Returns the current index unchanged
+    /// if the route is already complete.
+    pub fn nearest_index(&self, current: Coord) -> usize {
+        let mut best = self.current;
+        let mut best_m = f32::INFINITY;
+        for (i, &waypoint) in self.waypoints().iter().enumerate().skip(self.current) {
+            let d = leg_distance_m(current, waypoint);
+            if d < best_m {
+                best = i;
+                best_m = d;
+            }
+        }
+        best
+    }
+
+    /// Target the remaining waypoint closest to `current`, e.g. when joining
+    /// a trail part-way along.
+    pub fn start_from_nearest(&mut self, current: Coord) {
+        self.current = self.nearest_index(current);
+    }
+
+    /// Move on to the next waypoint after arriving at the current one.
+    ///
+    /// In loop mode the last waypoint is followed by the first.
+    pub fn advance(&mut self) {
+        if !self.is_complete() {
+            self.current += 1;
+            if self.looped && self.current == self.len {
+                self.current = 0;
+            }
+        }
+    }
+
+    /// Give up on the current waypoint and target the next one.
+    ///
+    /// Skipping the last waypoint completes the route, unless it loops.
+    pub fn skip(&mut self) {
+        self.advance();
+    }
+
+    /// Return to the previous leg.
+    ///
+    /// On a completed route this re-targets the last waypoint; on the first
+    /// leg it does nothing.
+    pub fn back(&mut self) {
+        self.current = self.current.min(self.len).saturating_sub(1);
+    }
+}
+
+impl<const N: usize> Default for Route<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Coord = Coord::new(10.0, 20.0);
+    const B: Coord = Coord::new(10.1, 20.0);
+    const C: Coord = Coord::new(10.2, 20.1);
+
+    #[test]
+    fn test_skip_and_back_through_three_legs() {
+        let mut route = Route::<4>::from_slice(&[A, B, C]).unwrap();
+        assert_eq!(route.target(), Some(A));
+
+        route.back();
+        assert_eq!(route.target(), Some(A));
+
+        route.skip();
+        route.skip();
+        assert_eq!(route.target(), Some(C));
+
+        route.back();
+        assert_eq!(route.target(), Some(B));
+
+        route.skip();
+        route.skip();
+        assert!(route.is_complete());
+        assert_eq!(route.target(), None);
+        route.skip();
+        assert_eq!(route.current_index(), 3);
+
+        route.back();
+        assert_eq!(route.target(), Some(C));
+    }
+
+    #[test]
+    fn test_looped_route_wraps_and_never_completes() {
+        let mut route = Route::<4>::from_slice(&[A, B, C]).unwrap();
+        route.set_loop(true);
+        let mut visited = vec![route.current_index()];
+        for _ in 0..4 {
+            route.advance();
+            assert!(!route.is_complete());
+            visited.push(route.current_index());
+        }
+        assert_eq!(visited, [0, 1, 2, 0, 1]);
+
+        route.set_loop(false);
+        route.skip();
+        route.skip();
+        assert!(route.is_complete());
+        route.set_loop(true);
+        assert_eq!(route.target(), Some(A));
+    }
+
+    #[test]
+    fn test_start_from_nearest_picks_middle_waypoint() {
+        let mut route = Route::<4>::from_slice(&[A, B, C]).unwrap();
+        let here = Coord::new(10.09, 20.01);
+        assert_eq!(route.nearest_index(here), 1);
+
+        route.start_from_nearest(here);
+        assert_eq!(route.target(), Some(B));
+    }
+
+    #[test]
+    fn test_nearest_index_ties_pick_first() {
+        let route = Route::<4>::from_slice(&[B, A, B]).unwrap();
+        assert_eq!(route.nearest_index(B), 0);
+    }
+
+    #[test]
+    fn test_bytes_round_trip_three_legs() {
+        let mut route = Route::<4>::new();
+        route.push(A).unwrap();
+        route.push_with_radius(B, 3.5).unwrap();
+        route.push_with_radius(C, 40.0).unwrap();
+        let mut buf = [0u8; 64];
+        let len = route.to_bytes(&mut buf);
+        assert_eq!(len, 1 + 3 * LEG_RECORD_LEN);
+        assert_eq!(route.to_bytes(&mut buf[..len - 1]), 0);
+
+        let decoded = Route::<3>::from_bytes(&buf[..len]).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for (got, want) in decoded.waypoints().iter().zip([A, B, C]) {
+            assert!(got.approx_eq(&want, 1e-6), "{got:?}");
+        }
+        assert_eq!(decoded.radius_m(1), Some(3.5));
+        assert_eq!(decoded.radius_m(2), Some(40.0));
+
+        assert_eq!(
+            Route::<2>::from_bytes(&buf[..len]).unwrap_err(),
+            ParseError::TooManyLegs
+        );
+        assert_eq!(
+            Route::<3>::from_bytes(&buf[..len - 1]).unwrap_err(),
+            ParseError::Truncated
+        );
+        assert_eq!(
+            Route::<3>::from_bytes(&[]).unwrap_err(),
+            ParseError::Truncated
+        );
+    }
+
+    #[test]
+    fn test_from_slice_rejects_too_many_waypoints() {
+        assert!(Route::<2>::from_slice(&[A, B, C]).is_none());
+    }
+}
@@ -0,0 +1,159 @@
+//! Synthetic GPS tracks for exercising guidance end-to-end.
+//!
+//! Available in unit tests and behind the `test-util` feature, with
+//! `float-nav`.
+
+use super::{Coord, destination, range_bearing, route::Route};
+use crate::{drivers::gps::RmcFix, util::rng::Xorshift32};
+
+/// Knots per metre per second.
+const KNOTS_PER_M_S: f32 = 1.943_844;
+
+/// Fixes of someone walking a [`Route`] at a steady speed.
+///
+/// Starts at `start` and heads for each remaining waypoint in turn, one fix
+/// every `interval_ms`, stopping exactly on the last waypoint. Corners are
+/// cut only by the distance left over in the step that reaches a waypoint.
+/// [`SyntheticTrack::with_noise`] scatters the reported fixes, but not the
+/// underlying path, like receiver noise.
+#[derive(Debug, Clone)]
+pub struct SyntheticTrack<'a> {
+    waypoints: &'a [Coord],
+    step_m: f32,
+    speed_m_s: f32,
+    position: Coord,
+    course_deg: f32,
+    noise_m: f32,
+    rng: Xorshift32,
+    started: bool,
+}
+
+impl<'a> SyntheticTrack<'a> {
+    /// Walk from `start` through the waypoints of `route` from its current
+    /// leg on, at `speed_m_s` with a fix every `interval_ms`.
+    pub fn new<const N: usize>(
+        start: Coord,
+        route: &'a Route<N>,
+        speed_m_s: f32,
+        interval_ms: u32,
+    ) -> Self {
+        let waypoints = &route.waypoints()[route.current_index().min(route.len())..];
+        Self {
+            waypoints,
+            step_m: speed_m_s * interval_ms as f32 / 1_000.0,
+            speed_m_s,
+            position: start,
+            course_deg: 0.0,
+            noise_m: 0.0,
+            rng: Xorshift32::new(1),
+            started: false,
+        }
+    }
+
+    /// Offset each reported fix by up to `noise_m` in a random direction,
+    /// repeatably for a given `seed`.
+    pub fn with_noise(mut self, noise_m: f32, seed: u32) -> Self {
+        self.noise_m = noise_m;
+        self.rng = Xorshift32::new(seed);
+        self
+    }
+
+    /// The next fix as an active `RMC` sentence with speed and course.
+    pub fn next_rmc(&mut self) -> Option<RmcFix> {
+        let position = self.next()?;
+        Some(RmcFix {
+            time: None,
+            status: b'A',
+            position: Some(position),
+            speed_knots: Some(self.speed_m_s * KNOTS_PER_M_S),
+            course_deg: Some(self.course_deg),
+        })
+    }
+
+    /// Move up to `step_m` along the route; `false` once already at the end.
+    fn walk(&mut self) -> bool {
+        let mut remaining = self.step_m;
+        let mut moved = false;
+        while let Some((&target, rest)) = self.waypoints.split_first() {
+            let (distance, bearing) = range_bearing(self.position, target);
+            moved = true;
+            if distance > remaining {
+                self.course_deg = bearing;
+                self.position = destination(self.position, bearing, remaining);
+                break;
+            }
+            if distance > 0.0 {
+                self.course_deg = bearing;
+            }
+            self.position = target;
+            self.waypoints = rest;
+            remaining -= distance;
+        }
+        moved
+    }
+}
+
+impl Iterator for SyntheticTrack<'_> {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Coord> {
+        if self.started && !self.walk() {
+            return None;
+        }
+        self.started = true;
+        if self.noise_m == 0.0 {
+            return Some(self.position);
+        }
+        let bearing = self.rng.next_f32() * 360.0;
+        let offset = self.rng.next_f32() * self.noise_m;
+        Some(destination(self.position, bearing, offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guidance::{Guidance, GuidanceEvent};
+
+    fn route() -> Route<3> {
+        Route::from_slice(&[
+            Coord::new(10.001, 20.0),
+            Coord::new(10.001, 20.001),
+            Coord::new(10.0, 20.001),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_walking_the_track_arrives_at_each_waypoint_in_order() {
+        let route = route();
+        let start = Coord::new(10.0, 20.0);
+        for track in [
+            SyntheticTrack::new(start, &route, 1.4, 1_000),
+            SyntheticTrack::new(start, &route, 1.4, 1_000).with_noise(3.0, 42),
+        ] {
+            let mut guidance = Guidance::new(route.clone());
+            let events: Vec<_> = track.filter_map(|fix| guidance.update(fix)).collect();
+            use GuidanceEvent::Arrived;
+            assert_eq!(events, [Arrived(0), Arrived(1), Arrived(2)]);
+            assert!(guidance.route().is_complete());
+        }
+    }
+
+    #[test]
+    fn test_track_steps_at_speed_and_ends_on_last_waypoint() {
+        let route = route();
+        let mut track = SyntheticTrack::new(Coord::new(10.0, 20.0), &route, 2.0, 5_000);
+        let first = track.next_rmc().unwrap();
+        assert_eq!(first.valid_position(), Some(Coord::new(10.0, 20.0)));
+        let second = track.next_rmc().unwrap();
+        let (step, _) = range_bearing(Coord::new(10.0, 20.0), second.position.unwrap());
+        // f32 degrees resolve about 0.1 m here.
+        assert!((step - 10.0).abs() < 0.2, "{step}");
+        assert!(second.course_deg.unwrap() < 0.1);
+        assert!((second.speed_knots.unwrap() - 3.89).abs() < 0.01);
+
+        let last = track.last().unwrap();
+        assert_eq!(last, Coord::new(10.0, 20.001));
+    }
+}
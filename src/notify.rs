@@ -0,0 +1,278 @@
+//! Audible feedback for application events.
+
+use embedded_hal::{delay::DelayNs, digital::OutputPin, pwm::SetDutyCycle};
+
+use crate::drivers::{
+    async_buzzer::AsyncBuzzer,
+    buzzer::{Buzzer, SetFrequency},
+    tick::TickSource,
+};
+
+/// Pitch of the confirmation double beep.
+pub const CONFIRM_HZ: u32 = 2_000;
+/// Pitch of the error triple beep.
+pub const ERROR_HZ: u32 = 400;
+/// Length of each confirmation/error beep.
+pub const BEEP_MS: u32 = 80;
+/// Silence between consecutive beeps.
+pub const GAP_MS: u32 = 80;
+
+/// Something that can tell the user an action worked or went wrong.
+pub trait Notifier {
+    /// Signal success, e.g. "waypoint saved".
+    fn confirm(&mut self);
+
+    /// Signal a problem, e.g. "could not save" or "off route".
+    fn error(&mut self);
+
+    /// `true` while a cue started earlier is still playing.
+    ///
+    /// Blocking notifiers finish each cue before returning, so the default
+    /// is always `false`.
+    fn is_busy(&self) -> bool {
+        false
+    }
+
+    /// Stop the current cue at once, e.g. to make way for a more important
+    /// one. The default does nothing, as there is never a cue to stop.
+    fn cancel(&mut self) {}
+}
+
+/// High double beep for [`Notifier::confirm`], low triple beep for
+/// [`Notifier::error`].
+impl<PWM, D, EN> Notifier for Buzzer<PWM, D, EN>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+    EN: OutputPin,
+{
+    fn confirm(&mut self) {
+        beeps(self, CONFIRM_HZ, 2);
+    }
+
+    fn error(&mut self) {
+        beeps(self, ERROR_HZ, 3);
+    }
+}
+
+/// Same cues as the blocking [`Buzzer`], played in the background; keep
+/// calling [`AsyncBuzzer::poll`] from the main loop.
+impl<PWM, T> Notifier for AsyncBuzzer<PWM, T>
+where
+    PWM: SetDutyCycle + SetFrequency,
+    T: TickSource,
+{
+    fn confirm(&mut self) {
+        let _ = self.play_beeps(CONFIRM_HZ, 2, BEEP_MS, GAP_MS);
+    }
+
+    fn error(&mut self) {
+        let _ = self.play_beeps(ERROR_HZ, 3, BEEP_MS, GAP_MS);
+    }
+
+    fn is_busy(&self) -> bool {
+        self.is_playing()
+    }
+
+    fn cancel(&mut self) {
+        self.stop();
+    }
+}
+
+/// Cue a [`Notifier`] can play, see [`Arbiter::notify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// [`Notifier::confirm`].
+    Confirm,
+    /// [`Notifier::error`].
+    Error,
+}
+
+/// Importance of an [`Event`]; later variants outrank earlier ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Background information, e.g. a low battery.
+    Low,
+    /// Routine feedback, e.g. "waypoint saved".
+    Normal,
+    /// Must be heard now, e.g. arrival or off route.
+    High,
+}
+
+/// What [`Arbiter::notify`] did with an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arbitration {
+    /// Started playing, interrupting any lower-priority cue.
+    Played,
+    /// Waiting for the current cue to finish.
+    Queued,
+    /// Discarded in favour of a cue at least as important.
+    Dropped,
+}
+
+/// Keeps cues from overlapping when several events want the buzzer at once.
+///
+/// A new event interrupts the playing cue only if it has a strictly higher
+/// [`Priority`]; otherwise it waits in a single-entry queue, where it
+/// replaces a queued event of no higher priority, or is dropped. Call
+/// [`Arbiter::poll`] from the main loop to start the queued event once the
+/// buzzer is free. A blocking notifier is never busy, so every event plays
+/// at once.
+pub struct Arbiter<N: Notifier> {
+    notifier: N,
+    playing: Priority,
+    queued: Option<(Priority, Event)>,
+}
+
+impl<N: Notifier> Arbiter<N> {
+    /// Wrap `notifier`.
+    pub fn new(notifier: N) -> Self {
+        Self {
+            notifier,
+            playing: Priority::Low,
+            queued: None,
+        }
+    }
+
+    /// Play `event` now, later or not at all, depending on what is playing.
+    pub fn notify(&mut self, priority: Priority, event: Event) -> Arbitration {
+        if !self.notifier.is_busy() {
+            self.play(priority, event);
+            return Arbitration::Played;
+        }
+        if priority > self.playing {
+            self.notifier.cancel();
+            self.play(priority, event);
+            return Arbitration::Played;
+        }
+        match self.queued {
+            Some((queued, _)) if queued > priority => Arbitration::Dropped,
+            _ => {
+                self.queued = Some((priority, event));
+                Arbitration::Queued
+            }
+        }
+    }
+
+    /// Start the queued event if the notifier has gone quiet.
+    pub fn poll(&mut self) {
+        if self.notifier.is_busy() {
+            return;
+        }
+        if let Some((priority, event)) = self.queued.take() {
+            self.play(priority, event);
+        }
+    }
+
+    /// The wrapped notifier, e.g. to call [`AsyncBuzzer::poll`].
+    pub fn notifier_mut(&mut self) -> &mut N {
+        &mut self.notifier
+    }
+
+    /// Consume the arbiter and return the notifier.
+    pub fn release(self) -> N {
+        self.notifier
+    }
+
+    fn play(&mut self, priority: Priority, event: Event) {
+        self.playing = priority;
+        match event {
+            Event::Confirm => self.notifier.confirm(),
+            Event::Error => self.notifier.error(),
+        }
+    }
+}
+
+fn beeps<PWM, D, EN>(buzzer: &mut Buzzer<PWM, D, EN>, frequency_hz: u32, count: u8)
+where
+    PWM: SetDutyCycle + SetFrequency,
+    D: DelayNs,
+    EN: OutputPin,
+{
+    for i in 0..count {
+        if i > 0 {
+            buzzer.rest(GAP_MS);
+        }
+        let _ = buzzer.tone(frequency_hz, 50, BEEP_MS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::shared::fixtures::ManualTicks;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+    use embedded_hal::pwm::ErrorType;
+
+    /// PWM whose current duty can be inspected.
+    #[derive(Default)]
+    struct Pwm {
+        duty: u16,
+    }
+    impl ErrorType for Pwm {
+        type Error = Infallible;
+    }
+    impl SetDutyCycle for Pwm {
+        fn max_duty_cycle(&self) -> u16 {
+            100
+        }
+        fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+            self.duty = duty;
+            Ok(())
+        }
+    }
+    impl SetFrequency for Pwm {
+        type Error = Infallible;
+        fn set_frequency(&mut self, _hz: u32) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_cancel_zeroes_async_tone_immediately() {
+        let ticks = ManualTicks(Cell::new(0));
+        let mut notifier = AsyncBuzzer::new(Pwm::default(), &ticks);
+        notifier.error();
+        ticks.0.set(10);
+        notifier.poll();
+        assert!(notifier.is_busy());
+
+        notifier.cancel();
+        assert!(!notifier.is_busy());
+        assert_eq!(notifier.release().0.duty, 0);
+    }
+
+    #[test]
+    fn test_high_priority_preempts_low_and_low_waits() {
+        let ticks = ManualTicks(Cell::new(0));
+        let mut arbiter = Arbiter::new(AsyncBuzzer::new(Pwm::default(), &ticks));
+
+        assert_eq!(
+            arbiter.notify(Priority::Low, Event::Confirm),
+            Arbitration::Played
+        );
+        assert_eq!(
+            arbiter.notify(Priority::High, Event::Error),
+            Arbitration::Played
+        );
+        assert_eq!(arbiter.notifier_mut().frequency_hz(), Some(ERROR_HZ));
+
+        // Equal or lower priority waits; the more important of two is kept.
+        assert_eq!(
+            arbiter.notify(Priority::Normal, Event::Confirm),
+            Arbitration::Queued
+        );
+        assert_eq!(
+            arbiter.notify(Priority::Low, Event::Error),
+            Arbitration::Dropped
+        );
+        arbiter.poll();
+        assert_eq!(arbiter.notifier_mut().frequency_hz(), Some(ERROR_HZ));
+
+        ticks.0.set(10_000);
+        arbiter.notifier_mut().poll();
+        arbiter.poll();
+        assert_eq!(arbiter.notifier_mut().frequency_hz(), Some(CONFIRM_HZ));
+    }
+}
@@ -0,0 +1,131 @@
+//! Integer-only text formatting into byte buffers.
+//!
+//! These avoid `core::fmt`, whose formatting machinery costs several
+//! kilobytes of flash on the AVR. Each helper writes ASCII into the start of
+//! `buf` and returns the number of bytes written; if `buf` is too small
+//! nothing is written and `0` is returned.
+
+use crate::nav::{Coord, FixedCoord};
+
+/// Decimal places printed by [`fmt_coord`] (5 ≈ 1 m).
+pub const COORD_DECIMALS: u32 = 5;
+
+/// Longest output of [`fmt_coord`]: `-180.00000,-180.00000`.
+pub const MAX_COORD_LEN: usize = 21;
+
+/// Write `value` in decimal, e.g. `1234`.
+pub fn fmt_u32(buf: &mut [u8], value: u32) -> usize {
+    let mut digits = [0u8; 10];
+    let mut n = value;
+    let mut i = digits.len();
+    loop {
+        i -= 1;
+        digits[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    copy(buf, &digits[i..])
+}
+
+/// Write `lat,lon` with [`COORD_DECIMALS`] decimal places, e.g.
+/// `51.50070,-0.12460`.
+pub fn fmt_coord(buf: &mut [u8], coord: Coord) -> usize {
+    let fixed = FixedCoord::from(coord);
+    let mut out = [0u8; MAX_COORD_LEN];
+    let mut len = fmt_degrees(&mut out, fixed.lat);
+    out[len] = b',';
+    len += 1;
+    len += fmt_degrees(&mut out[len..], fixed.lon);
+    copy(buf, &out[..len])
+}
+
+/// Write a bearing as three zero-padded digits `000`–`359`.
+///
+/// Values of 360 and above are wrapped.
+pub fn fmt_bearing(buf: &mut [u8], bearing_deg: u16) -> usize {
+    let deg = bearing_deg % 360;
+    copy(
+        buf,
+        &[
+            b'0' + (deg / 100) as u8,
+            b'0' + (deg / 10 % 10) as u8,
+            b'0' + (deg % 10) as u8,
+        ],
+    )
+}
+
+/// Write 10⁻⁷ degrees as signed `d.ddddd`, rounded to [`COORD_DECIMALS`].
+fn fmt_degrees(buf: &mut [u8], e7: i32) -> usize {
+    const UNIT: u32 = 10u32.pow(7 - COORD_DECIMALS);
+    const SCALE: u32 = 10u32.pow(COORD_DECIMALS);
+
+    let rounded = (e7.unsigned_abs() + UNIT / 2) / UNIT;
+    let mut len = 0;
+    if e7 < 0 && rounded != 0 {
+        buf[0] = b'-';
+        len = 1;
+    }
+    len += fmt_u32(&mut buf[len..], rounded / SCALE);
+    buf[len] = b'.';
+    len += 1;
+    let frac = rounded % SCALE;
+    for place in (0..COORD_DECIMALS).rev() {
+        buf[len] = b'0' + (frac / 10u32.pow(place) % 10) as u8;
+        len += 1;
+    }
+    len
+}
+
+fn copy(buf: &mut [u8], bytes: &[u8]) -> usize {
+    match buf.get_mut(..bytes.len()) {
+        Some(dst) => {
+            dst.copy_from_slice(bytes);
+            bytes.len()
+        }
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatted(f: impl FnOnce(&mut [u8]) -> usize) -> Vec<u8> {
+        let mut buf = [0u8; 32];
+        let len = f(&mut buf);
+        buf[..len].to_vec()
+    }
+
+    #[test]
+    fn test_fmt_u32() {
+        assert_eq!(formatted(|b| fmt_u32(b, 0)), b"0");
+        assert_eq!(formatted(|b| fmt_u32(b, 1_234)), b"1234");
+        assert_eq!(formatted(|b| fmt_u32(b, u32::MAX)), b"4294967295");
+        assert_eq!(fmt_u32(&mut [0u8; 3], 1_234), 0);
+    }
+
+    #[test]
+    fn test_fmt_coord() {
+        let london = Coord::new(51.5007, -0.1246);
+        assert_eq!(formatted(|b| fmt_coord(b, london)), b"51.50070,-0.12460");
+        let south_east = Coord::new(-33.856_78, 151.215_3);
+        assert_eq!(
+            formatted(|b| fmt_coord(b, south_east)),
+            b"-33.85678,151.21530"
+        );
+        assert_eq!(
+            formatted(|b| fmt_coord(b, Coord::new(-0.000_001, 0.0))),
+            b"0.00000,0.00000"
+        );
+    }
+
+    #[test]
+    fn test_fmt_bearing() {
+        assert_eq!(formatted(|b| fmt_bearing(b, 7)), b"007");
+        assert_eq!(formatted(|b| fmt_bearing(b, 45)), b"045");
+        assert_eq!(formatted(|b| fmt_bearing(b, 359)), b"359");
+        assert_eq!(formatted(|b| fmt_bearing(b, 360)), b"000");
+    }
+}
@@ -0,0 +1,214 @@
+//! Minimal `log`-style tracing for bench debugging.
+//!
+//! [`log_info!`](crate::log_info) and [`log_warn!`](crate::log_warn) take
+//! `format!`-style arguments. They are prefixed because `#[macro_export]`
+//! puts them at the crate root, where plain `info!`/`warn!` would clash with
+//! the `log` crate's macros in a firmware that also uses it. With the `serial-log` feature they are passed to the sink
+//! installed by `set_sink`, typically a function that calls
+//! [`write_serial`] on a spare USART. Without the feature they expand to
+//! nothing: the arguments are type-checked but never evaluated, so release
+//! builds pay no flash or time for them.
+
+use core::fmt::{self, Write as _};
+
+use embedded_hal_nb::serial::Write;
+
+/// Severity of a log message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Normal decisions, e.g. "arrived at waypoint 2".
+    Info,
+    /// Something the user may want to look into, e.g. a dropped sentence.
+    Warn,
+}
+
+impl Level {
+    /// Tag printed before each message by [`write_serial`].
+    pub const fn tag(self) -> &'static str {
+        match self {
+            Level::Info => "I",
+            Level::Warn => "W",
+        }
+    }
+}
+
+/// Receives every message logged with the `serial-log` feature on.
+pub type Sink = fn(Level, fmt::Arguments<'_>);
+
+/// Write one message to `usart` as `[I] message\r\n`, blocking until sent.
+///
+/// Output errors are ignored; logging must never disturb the caller.
+pub fn write_serial<W>(usart: &mut W, level: Level, args: fmt::Arguments<'_>)
+where
+    W: Write<u8>,
+{
+    let mut out = SerialFmt(usart);
+    let _ = write!(out, "[{}] {}\r\n", level.tag(), args);
+}
+
+/// Adapts an `embedded-hal-nb` serial port to [`core::fmt::Write`].
+struct SerialFmt<'a, W>(&'a mut W);
+
+impl<W: Write<u8>> fmt::Write for SerialFmt<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            nb::block!(self.0.write(byte)).map_err(|_| fmt::Error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serial-log")]
+mod sink {
+    use super::{Level, Sink};
+    use core::{cell::UnsafeCell, fmt};
+
+    struct Slot(UnsafeCell<Option<Sink>>);
+
+    // SAFETY: the slot is only written by `set_sink`, whose contract rules
+    // out concurrent access.
+    unsafe impl Sync for Slot {}
+
+    #[cfg(not(test))]
+    static SINK: Slot = Slot(UnsafeCell::new(None));
+
+    // Unit tests run on parallel threads, so each gets its own slot rather
+    // than racing on a shared one.
+    #[cfg(test)]
+    std::thread_local! {
+        static SINK: Slot = const { Slot(UnsafeCell::new(None)) };
+    }
+
+    /// Install `sink` as the destination of [`log_info!`](crate::log_info)
+    /// and [`log_warn!`](crate::log_warn). Messages logged before this are
+    /// dropped.
+    ///
+    /// # Safety
+    ///
+    /// Must not run while anything else may be logging, e.g. call it once at
+    /// start-up before enabling interrupts.
+    pub unsafe fn set_sink(sink: Sink) {
+        // SAFETY: no concurrent readers, per the contract above.
+        with_slot(|slot| unsafe { *slot.0.get() = Some(sink) });
+    }
+
+    #[doc(hidden)]
+    pub fn log(level: Level, args: fmt::Arguments<'_>) {
+        // SAFETY: the slot is not written while logging is possible.
+        if let Some(sink) = with_slot(|slot| unsafe { *slot.0.get() }) {
+            sink(level, args);
+        }
+    }
+
+    #[cfg(not(test))]
+    fn with_slot<R>(f: impl FnOnce(&Slot) -> R) -> R {
+        f(&SINK)
+    }
+
+    #[cfg(test)]
+    fn with_slot<R>(f: impl FnOnce(&Slot) -> R) -> R {
+        SINK.with(f)
+    }
+}
+
+#[cfg(feature = "serial-log")]
+pub use sink::{log as __log, set_sink};
+
+/// Log a [`Level::Info`] message; see [`util::log`](crate::util::log).
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)+) => {
+        $crate::__log_at!($crate::util::log::Level::Info, $($arg)+)
+    };
+}
+
+/// Log a [`Level::Warn`] message; see [`util::log`](crate::util::log).
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)+) => {
+        $crate::__log_at!($crate::util::log::Level::Warn, $($arg)+)
+    };
+}
+
+#[cfg(feature = "serial-log")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_at {
+    ($level:expr, $($arg:tt)+) => {
+        $crate::util::log::__log($level, format_args!($($arg)+))
+    };
+}
+
+#[cfg(not(feature = "serial-log"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_at {
+    ($level:expr, $($arg:tt)+) => {
+        if false {
+            let _ = ($level, format_args!($($arg)+));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use embedded_hal_nb::serial::ErrorType;
+
+    #[derive(Default)]
+    struct Capture(Vec<u8>);
+    impl ErrorType for Capture {
+        type Error = Infallible;
+    }
+    impl Write<u8> for Capture {
+        fn write(&mut self, word: u8) -> nb::Result<(), Infallible> {
+            self.0.push(word);
+            Ok(())
+        }
+        fn flush(&mut self) -> nb::Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_serial_tags_and_terminates_lines() {
+        let mut usart = Capture::default();
+        write_serial(&mut usart, Level::Warn, format_args!("dropped {}", 3));
+        assert_eq!(usart.0, b"[W] dropped 3\r\n");
+    }
+
+    #[cfg(not(feature = "serial-log"))]
+    #[test]
+    fn test_macros_do_not_evaluate_arguments_without_feature() {
+        let mut evaluated = 0;
+        crate::log_info!("{}", {
+            evaluated += 1;
+            evaluated
+        });
+        crate::log_warn!("{}", {
+            evaluated += 1;
+            evaluated
+        });
+        assert_eq!(evaluated, 0);
+    }
+
+    #[cfg(feature = "serial-log")]
+    #[test]
+    fn test_macros_reach_installed_sink() {
+        use std::cell::RefCell;
+
+        std::thread_local! {
+            static LINES: RefCell<Capture> = RefCell::default();
+        }
+        fn sink(level: Level, args: fmt::Arguments<'_>) {
+            LINES.with(|l| write_serial(&mut *l.borrow_mut(), level, args));
+        }
+        // SAFETY: the slot is per test thread, and nothing on this one is
+        // logging.
+        unsafe { set_sink(sink) };
+
+        crate::log_info!("leg {}", 2);
+        assert_eq!(LINES.with(|l| l.borrow().0.clone()), b"[I] leg 2\r\n");
+    }
+}
@@ -0,0 +1,123 @@
+//! Small `f32` approximations for code that must not depend on `libm`.
+//!
+//! Accurate to about 1e-5 relative, which is plenty for audio pitch maths.
+
+use core::f32::consts::LN_2;
+
+/// Base-2 logarithm of `x`. Returns `f32::NEG_INFINITY` for `x <= 0`.
+pub fn log2(x: f32) -> f32 {
+    if x <= 0.0 {
+        return f32::NEG_INFINITY;
+    }
+    let bits = x.to_bits();
+    let exponent = ((bits >> 23) & 0xFF) as i32 - 127;
+    // Mantissa in 1..2.
+    let m = f32::from_bits((bits & 0x007F_FFFF) | 0x3F80_0000);
+    // ln(m) = 2·atanh(s) with s = (m-1)/(m+1) in 0..1/3.
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let ln_m = 2.0 * s * (1.0 + s2 * (1.0 / 3.0 + s2 * (1.0 / 5.0 + s2 * (1.0 / 7.0 + s2 / 9.0))));
+    exponent as f32 + ln_m / LN_2
+}
+
+/// `2^x`. Saturates to 0 or `f32::INFINITY` outside the `f32` range.
+pub fn exp2(x: f32) -> f32 {
+    if x < -126.0 {
+        return 0.0;
+    }
+    if x >= 128.0 {
+        return f32::INFINITY;
+    }
+    let whole = floor(x);
+    // e^(f·ln2) by Taylor series, f in 0..1.
+    let t = (x - whole) * LN_2;
+    let frac = 1.0
+        + t * (1.0
+            + t / 2.0
+                * (1.0
+                    + t / 3.0
+                        * (1.0 + t / 4.0 * (1.0 + t / 5.0 * (1.0 + t / 6.0 * (1.0 + t / 7.0))))));
+    frac * f32::from_bits(((whole as i32 + 127) as u32) << 23)
+}
+
+/// Largest integer not greater than `x`, for `x` within the `i32` range.
+pub fn floor(x: f32) -> f32 {
+    let t = x as i32 as f32;
+    if t > x { t - 1.0 } else { t }
+}
+
+/// Smallest integer not less than `x`, for `x` within the `i32` range.
+pub fn ceil(x: f32) -> f32 {
+    -floor(-x)
+}
+
+/// Round a non-negative `x` to the nearest integer, halves away from zero.
+///
+/// Negative inputs saturate to 0, as with `as u32`.
+pub fn round_u32(x: f32) -> u32 {
+    (x + 0.5) as u32
+}
+
+/// Round `x` to the nearest integer, halves away from zero.
+///
+/// Saturates outside the `i32` range, as with `as i32`.
+pub fn round_i32(x: f32) -> i32 {
+    (if x < 0.0 { x - 0.5 } else { x + 0.5 }) as i32
+}
+
+/// Map `x` linearly from `x0..=x1` onto `y0..=y1`, holding `y0` below `x0`
+/// and `y1` above `x1`.
+///
+/// With `x1 <= x0` the output steps from `y0` to `y1` just above `x0`.
+pub fn lerp_clamped(x: f32, x0: f32, x1: f32, y0: f32, y1: f32) -> f32 {
+    let span = x1 - x0;
+    let t = if span > 0.0 {
+        ((x - x0) / span).clamp(0.0, 1.0)
+    } else if x > x0 {
+        1.0
+    } else {
+        0.0
+    };
+    y0 + (y1 - y0) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log2_and_exp2_match_libm() {
+        for x in [0.001, 0.3, 1.0, 1.5, 2.0, 3.0, 440.0, 12_345.0] {
+            let expected = libm::log2f(x);
+            assert!((log2(x) - expected).abs() < 1e-5, "log2({x})");
+        }
+        for x in [-10.5, -1.0, -0.25, 0.0, 0.5, 1.0, 3.3, 20.0] {
+            let expected = libm::exp2f(x);
+            assert!((exp2(x) - expected).abs() <= expected * 1e-5, "exp2({x})");
+        }
+    }
+
+    #[test]
+    fn test_lerp_clamped() {
+        assert_eq!(lerp_clamped(5.0, 0.0, 10.0, 100.0, 200.0), 150.0);
+        assert_eq!(lerp_clamped(-1.0, 0.0, 10.0, 100.0, 200.0), 100.0);
+        assert_eq!(lerp_clamped(11.0, 0.0, 10.0, 200.0, 100.0), 100.0);
+        // Zero-width range: a step at x0.
+        assert_eq!(lerp_clamped(3.0, 3.0, 3.0, 1.0, 2.0), 1.0);
+        assert_eq!(lerp_clamped(3.1, 3.0, 3.0, 1.0, 2.0), 2.0);
+    }
+
+    #[test]
+    fn test_rounding_helpers() {
+        assert_eq!((floor(2.7), floor(-2.2), floor(3.0)), (2.0, -3.0, 3.0));
+        assert_eq!((ceil(2.2), ceil(-2.7), ceil(3.0)), (3.0, -2.0, 3.0));
+        assert_eq!(
+            (round_u32(2.5), round_u32(2.49), round_u32(-4.0)),
+            (3, 2, 0)
+        );
+        assert_eq!(
+            (round_i32(2.5), round_i32(-2.5), round_i32(-2.49)),
+            (3, -3, -2)
+        );
+    }
+}
@@ -0,0 +1,7 @@
+//! Small `no_std` helpers shared across drivers and the application.
+
+pub mod fmt;
+pub mod log;
+pub mod math;
+pub mod progmem;
+pub mod rng;
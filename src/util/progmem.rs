@@ -0,0 +1,62 @@
+//! Read-only byte tables kept in flash on AVR.
+//!
+//! AVR is a Harvard machine: an ordinary `static` or `const` table is copied
+//! from flash into SRAM at start-up, and the ATmega16 only has 1 KiB of it.
+//! A [`ProgMemBytes`] placed in `.progmem.data` stays in flash and is read
+//! with `lpm`, costing its size in flash only. On other targets (and in host
+//! tests) it is an ordinary array.
+
+/// A byte table that is only readable through [`ProgMemBytes::get`].
+///
+/// Declare it as a `static` with
+/// `#[cfg_attr(target_arch = "avr", unsafe(link_section = ".progmem.data"))]`;
+/// without the section attribute AVR copies it into SRAM like any other
+/// static, and [`ProgMemBytes::get`] would read the wrong address space.
+#[repr(transparent)]
+pub struct ProgMemBytes<const N: usize>([u8; N]);
+
+impl<const N: usize> ProgMemBytes<N> {
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    /// The byte at `index`, or `None` past the end.
+    pub fn get(&self, index: usize) -> Option<u8> {
+        if index >= N {
+            return None;
+        }
+        #[cfg(target_arch = "avr")]
+        {
+            let addr = self.0.as_ptr().wrapping_add(index);
+            let byte: u8;
+            // SAFETY: `addr` is inside the table, which lives in program
+            // memory (see the type docs); `lpm` only reads flash.
+            unsafe {
+                core::arch::asm!(
+                    "lpm {}, Z",
+                    out(reg) byte,
+                    in("Z") addr,
+                    options(readonly, preserves_flags, nostack),
+                );
+            }
+            Some(byte)
+        }
+        #[cfg(not(target_arch = "avr"))]
+        {
+            Some(self.0[index])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_reads_bytes_and_stops_at_the_end() {
+        static TABLE: ProgMemBytes<3> = ProgMemBytes::new([7, 0xFF, 0]);
+        assert_eq!(TABLE.get(0), Some(7));
+        assert_eq!(TABLE.get(1).map(|b| b as i8), Some(-1));
+        assert_eq!(TABLE.get(3), None);
+    }
+}
@@ -0,0 +1,78 @@
+//! Tiny deterministic pseudo-random numbers.
+
+/// Marsaglia's xorshift32 generator.
+///
+/// Not suitable for anything security related, but small, fast and fully
+/// repeatable for a given seed, which is what noise injection in tests and
+/// duty dithering need.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xorshift32 {
+    state: u32,
+}
+
+impl Xorshift32 {
+    /// Start a sequence from `seed`. Xorshift never leaves an all-zero
+    /// state, so a zero seed is replaced by 1.
+    pub const fn new(seed: u32) -> Self {
+        Self {
+            state: if seed == 0 { 1 } else { seed },
+        }
+    }
+
+    /// The next value, never 0.
+    pub fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+
+    /// The next value in `0..n`, or 0 when `n` is 0.
+    ///
+    /// Scales rather than taking a remainder, so the bias towards low values
+    /// is at most one part in 2³² / `n`.
+    pub fn next_range(&mut self, n: u32) -> u32 {
+        ((u64::from(self.next_u32()) * u64::from(n)) >> 32) as u32
+    }
+
+    /// The next value, uniform in `0..1`, from the top 24 bits of
+    /// [`Xorshift32::next_u32`].
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut rng = Xorshift32::new(1);
+        assert_eq!(
+            [rng.next_u32(), rng.next_u32(), rng.next_u32()],
+            [270_369, 67_634_689, 2_647_435_461]
+        );
+
+        let mut a = Xorshift32::new(42);
+        let mut b = Xorshift32::new(42);
+        assert!((0..100).all(|_| a.next_u32() == b.next_u32()));
+        assert_ne!(
+            Xorshift32::new(43).next_u32(),
+            Xorshift32::new(42).next_u32()
+        );
+        assert_eq!(Xorshift32::new(0), Xorshift32::new(1));
+    }
+
+    #[test]
+    fn test_next_range_stays_in_bounds() {
+        let mut rng = Xorshift32::new(7);
+        let mut seen = [false; 6];
+        for _ in 0..200 {
+            seen[rng.next_range(6) as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(rng.next_range(0), 0);
+        assert!((0..100).all(|_| (0.0..1.0).contains(&rng.next_f32())));
+    }
+}